@@ -1,93 +1,138 @@
+use crate::linalg::dtype::DType;
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype};
 use crate::linalg::tensor::{Scalar, Tensor};
+
+/// Checks that the target and prediction tensors have the same shape.
+fn check_same_shape(op: &'static str, target: &Tensor, pred: &Tensor) -> Result<()> {
+    if target.shape() != pred.shape() {
+        return Err(TensorError::ShapeMismatch {
+            op,
+            lhs: target.shape().to_vec(),
+            rhs: pred.shape().to_vec(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `tensor` is a 2D tensor.
+fn check_2d(op: &'static str, tensor: &Tensor) -> Result<()> {
+    if tensor.shape().len() != 2 {
+        return Err(TensorError::Rank {
+            op,
+            expected: 2,
+            actual: tensor.shape().len(),
+        });
+    }
+    Ok(())
+}
+
 /// Computes the negative log-likelihood loss, where the current tensor contains raw probabilities
 /// # Arguments
 /// * `target` - A tensor of the same shape as self, containing the target labels (one-hot encoded)
 /// # Returns
 /// A tensor containing the NLL loss value
 pub fn nll_loss(target: &Tensor, pred: &Tensor) -> Tensor {
-    let shape = &pred.shape;
-    assert_eq!(shape.len(), 2, "Expected 2D tensor for input");
-    assert_eq!(target.shape.len(), 2, "Expected 2D tensor for target");
-    assert_eq!(
-        shape[0], target.shape[0],
-        "Input and target batch sizes must match"
-    );
-    assert_eq!(
-        shape[1], target.shape[1],
-        "Input and target must have the same number of classes"
-    );
+    try_nll_loss(target, pred).or_panic()
+}
+
+/// Computes the negative log-likelihood loss.
+/// Returns an error if `target` and `pred` are not 2D tensors of the same shape.
+pub fn try_nll_loss(target: &Tensor, pred: &Tensor) -> Result<Tensor> {
+    check_2d("nll_loss", pred)?;
+    check_2d("nll_loss", target)?;
+    check_same_shape("nll_loss", target, pred)?;
+    let batch_size = pred.shape()[0];
+    let dtype = pred.dtype().promote_scalar();
+    let (pred, target) = (pred.to_dtype(DType::F64), target.to_dtype(DType::F64));
+    // The targets are read first, as they may share the storage of the predictions
+    let targets = target.values::<f64>().collect::<Vec<f64>>();
     let mut loss = 0.0;
-    for (i, &value) in pred.storage.data.iter().enumerate() {
-        let target_index = target.storage.data[i];
+    for (value, target_index) in pred.values::<f64>().zip(targets) {
         if target_index == 1.0 {
             loss -= value.ln();
         }
     }
     // Normalize by batch size
-    Tensor::new(vec![loss / (shape[0] as f32)], &[1])
+    Ok(Tensor::from_vec(vec![loss / (batch_size as f64)], &[1]).to_dtype(dtype))
 }
 
 pub fn mse(target: &Tensor, pred: &Tensor) -> Tensor {
-    assert_eq!(
-        target.shape(),
-        pred.shape(),
-        "Target and prediction tensors must have the same shape for MSE"
-    );
-    (pred - target).square().mean_scalar()
+    try_mse(target, pred).or_panic()
+}
+
+pub fn try_mse(target: &Tensor, pred: &Tensor) -> Result<Tensor> {
+    check_same_shape("mse", target, pred)?;
+    Ok((pred - target).square().mean_scalar())
 }
 
 pub fn mae(target: &Tensor, pred: &Tensor) -> Tensor {
-    assert_eq!(
-        target.shape(),
-        pred.shape(),
-        "Target and prediction tensors must have the same shape for MAE"
-    );
-    (target - pred).abs().mean_scalar()
+    try_mae(target, pred).or_panic()
+}
+
+pub fn try_mae(target: &Tensor, pred: &Tensor) -> Result<Tensor> {
+    check_same_shape("mae", target, pred)?;
+    Ok((target - pred).abs().mean_scalar())
 }
 
 pub fn binary_cross_entropy(target: &Tensor, pred: &Tensor) -> Tensor {
-    assert_eq!(
-        target.shape(),
-        pred.shape(),
-        "Target and prediction tensors must have the same shape for binary cross-entropy"
-    );
+    try_binary_cross_entropy(target, pred).or_panic()
+}
+
+pub fn try_binary_cross_entropy(target: &Tensor, pred: &Tensor) -> Result<Tensor> {
+    check_same_shape("binary_cross_entropy", target, pred)?;
     let epsilon = 1e-12;
     let pred_clipped = pred.clamp(epsilon, 1.0 - epsilon);
     let out = -target * pred_clipped.log() - (1.0 - target) * (1.0 - pred_clipped).log();
-    out.mean_scalar()
+    Ok(out.mean_scalar())
 }
 
 pub fn binary_cross_entropy_with_logits(target: &Tensor, pred: &Tensor) -> Tensor {
-    assert_eq!(
-        target.shape(),
-        pred.shape(),
-        "Target and prediction tensors must have the same shape for binary cross-entropy with logits"
-    );
+    try_binary_cross_entropy_with_logits(target, pred).or_panic()
+}
 
+pub fn try_binary_cross_entropy_with_logits(target: &Tensor, pred: &Tensor) -> Result<Tensor> {
+    check_same_shape("binary_cross_entropy_with_logits", target, pred)?;
     let epsilon = 1e-12;
     let pred_clipped = pred.clamp(epsilon, 1.0 - epsilon);
     let out = target * &pred_clipped.sigmoid().log()
         + (1.0 - target) * (1.0 - &pred_clipped.sigmoid().log());
-    -out.mean_scalar()
+    Ok(-out.mean_scalar())
 }
 
 pub fn cross_entropy(target: &Tensor, pred: &Tensor) -> Tensor {
-    nll_loss(target, &pred.log_softmax())
+    try_cross_entropy(target, pred).or_panic()
+}
+
+pub fn try_cross_entropy(target: &Tensor, pred: &Tensor) -> Result<Tensor> {
+    check_2d("cross_entropy", pred)?;
+    try_nll_loss(target, &pred.try_log_softmax(1)?)
 }
 
+/// Computes the classification accuracy of the predictions
+/// # Arguments
+/// * `target` - An integer tensor of shape `[batch]` containing the target class indices
+/// * `pred` - A tensor of shape `[batch, classes]` containing the class scores
+/// # Returns
+/// The fraction of rows whose highest score matches the target class
 pub fn accuracy(target: &Tensor, pred: &Tensor) -> Scalar {
-    assert_eq!(
-        target.shape(),
-        pred.shape(),
-        "Target and prediction tensors must have the same shape for accuracy"
-    );
-    let mut correct = 0;
-    for i in 0..target.shape()[0] {
-        let target_class = target.storage.data[i] as usize;
-        let predicted_class = pred.slice(0, i, 1).argmax_axis(0)[0];
-        if target_class == predicted_class {
-            correct += 1;
-        }
+    try_accuracy(target, pred).or_panic()
+}
+
+/// Computes the classification accuracy of the predictions.
+/// Returns an error if `target` does not contain integer class indices matching the batch of `pred`.
+pub fn try_accuracy(target: &Tensor, pred: &Tensor) -> Result<Scalar> {
+    check_dtype("accuracy", target.dtype(), |dtype| {
+        !dtype.is_floating_point()
+    })?;
+    check_2d("accuracy", pred)?;
+    if target.numel() != pred.shape()[0] {
+        return Err(TensorError::ShapeMismatch {
+            op: "accuracy: batch sizes must match",
+            lhs: target.shape().to_vec(),
+            rhs: pred.shape().to_vec(),
+        });
     }
-    correct as Scalar / target.shape()[0] as Scalar
+    let target = target.clone().reshape(&[target.numel()]);
+    let correct = pred.try_argmax_axis(1)?.try_eq(&target)?;
+    Ok(correct.to_dtype(DType::F32).mean_scalar().as_slice()[0])
 }
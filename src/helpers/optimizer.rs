@@ -21,14 +21,13 @@ impl Optimizer for SGD {
             if !param.requires_grad {
                 continue;
             }
-            let grad = param.grad().expect(
-                format!(
+            let grad = param.grad().unwrap_or_else(|| {
+                panic!(
                     "Gradient not found for parameter with shape {:?}",
                     param.shape()
                 )
-                .as_str(),
-            );
-            *param = &*param - &(grad * self.learning_rate);
+            });
+            param.sub_(&(grad * self.learning_rate));
             if zero_grad {
                 param.zero_grad();
             }
@@ -69,14 +68,10 @@ impl Optimizer for Adam {
             }
             let grad = param.grad().expect("Gradient not found for parameter");
             if i >= self.mean_vectors.len() {
-                self.mean_vectors.push(Tensor::new(
-                    vec![0.0; param.storage.data.len()],
-                    param.shape(),
-                ));
-                self.variance_vectors.push(Tensor::new(
-                    vec![0.0; param.storage.data.len()],
-                    param.shape(),
-                ));
+                self.mean_vectors
+                    .push(Tensor::zeros(param.shape()).to_dtype(param.dtype()));
+                self.variance_vectors
+                    .push(Tensor::zeros(param.shape()).to_dtype(param.dtype()));
             }
 
             let m = &self.mean_vectors[i] * self.beta1 + &grad * (1.0 - self.beta1);
@@ -85,7 +80,7 @@ impl Optimizer for Adam {
             let m_hat = &m / (1.0 - self.beta1.powi((self.time_step + 1) as i32));
             let v_hat = &v / (1.0 - self.beta2.powi((self.time_step + 1) as i32));
 
-            *param = &*param - &(&m_hat / &(&v_hat.sqrt() + self.epsilon) * self.learning_rate);
+            param.sub_(&(&m_hat / &(&v_hat.sqrt() + self.epsilon) * self.learning_rate));
             if zero_grad {
                 param.zero_grad();
             }
@@ -1,22 +1,40 @@
 use crate::linalg::autograd::grad_fn::GradFn;
+use crate::linalg::autograd::grad_mode::{enable_grad, no_grad};
 use crate::linalg::tensor::{InternalTensor, Scalar, Tensor};
-use std::cell::RefCell;
-use std::collections::HashSet;
-use std::rc::Rc;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::Mutex;
 
 impl InternalTensor {
     pub(crate) fn with_requires_grad(mut self, requires_grad: bool) -> Self {
-        self.requires_grad = requires_grad;
+        self.set_requires_grad(requires_grad);
         self
     }
+
+    fn set_requires_grad(&mut self, requires_grad: bool) {
+        assert!(
+            !requires_grad || self.dtype().is_floating_point(),
+            "Only floating point tensors can require gradients, got {:?}",
+            self.dtype()
+        );
+        self.requires_grad = requires_grad;
+    }
 }
 
 impl Tensor {
-    pub(crate) fn set_grad_metadata(&mut self, grad_fn: Rc<dyn GradFn>, parents: Vec<Tensor>) {
-        let inner = Rc::make_mut(&mut self.0);
+    pub(crate) fn set_grad_metadata(&mut self, grad_fn: Arc<dyn GradFn>, parents: Vec<Tensor>) {
+        let inner = Arc::make_mut(&mut self.0);
         inner.grad_fn = Some(grad_fn);
         inner.parents = parents;
     }
+
+    /// Marks the tensor as requiring gradients. Panics if it is not a floating point tensor.
+    pub(crate) fn requiring_grad(mut self) -> Tensor {
+        Arc::make_mut(&mut self.0).set_requires_grad(true);
+        self
+    }
+
     /// Creates a new tensor with the given data and shape, with `requires_grad` set to true.
     /// # Arguments
     /// * `data` - A vector containing the tensor data.
@@ -29,140 +47,170 @@ impl Tensor {
             .into()
     }
 
+    /// Returns true if gradients are tracked for this tensor.
+    pub fn requires_grad(&self) -> bool {
+        self.requires_grad
+    }
+
     /// Returns the gradient if it exists as an `Option`.
     pub fn grad(&self) -> Option<Tensor> {
-        self.grad.borrow().as_ref().cloned()
+        self.grad_lock().as_ref().cloned()
+    }
+
+    /// Returns a new tensor sharing the same storage, detached from the computation graph.
+    pub(crate) fn detached(&self) -> Tensor {
+        InternalTensor {
+            storage: Arc::clone(&self.storage),
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            offset: self.offset,
+            grad: Mutex::new(None),
+            grad_fn: None,
+            parents: Vec::new(),
+            requires_grad: false,
+        }
+        .into()
     }
 
     pub fn detach(&mut self) {
-        let inner = Rc::make_mut(&mut self.0);
-        inner.grad = RefCell::new(None);
+        let inner = Arc::make_mut(&mut self.0);
+        inner.grad = Mutex::new(None);
         inner.grad_fn = None;
         inner.parents = Vec::new();
     }
 
     pub fn zero_grad(&self) {
-        *self.grad.borrow_mut() = None;
+        *self.grad_lock() = None;
     }
 
     /// Performs backpropagation to compute gradients for all tensors in the computation graph
     /// that have `requires_grad` set to true.
     pub fn backward(&self) {
+        self.backward_with(false)
+    }
+
+    /// Performs backpropagation like [`Tensor::backward`], optionally recording the gradient
+    /// computations themselves so that the gradients can be differentiated again.
+    /// # Arguments
+    /// * `create_graph` - Whether the gradients record a graph. Without it they are computed with
+    ///   gradients disabled and have no history.
+    ///
+    /// A gradient with history refers to the tensors it was computed from, including the tensor
+    /// holding it, so the graph is only freed once the gradient is cleared with
+    /// [`Tensor::zero_grad`].
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let x = Tensor::with_grad(vec![2.0], &[1]);
+    /// x.pow(3.0).sum().backward_with(true);
+    /// // dy/dx = 3x^2 = 12, and differentiating it again gives 6x = 12
+    /// let dx = x.grad().unwrap();
+    /// x.zero_grad();
+    /// dx.sum().backward();
+    /// assert_eq!(x.grad().unwrap().as_slice(), &[12.0]);
+    /// ```
+    pub fn backward_with(&self, create_graph: bool) {
         assert!(self.requires_grad);
+        let _guard = if create_graph {
+            enable_grad()
+        } else {
+            no_grad()
+        };
+
+        let grad = Tensor::ones(&self.shape).to_dtype(self.dtype());
+        for (t, g) in propagate(&[self], vec![grad]) {
+            let mut grad = t.grad_lock();
+            match &mut *grad {
+                // Leaves accumulate the gradients of every pass, while intermediate tensors keep
+                // the one of the last pass
+                Some(existing) if t.grad_fn.is_none() => {
+                    *existing = &*existing + &g;
+                }
+                _ => {
+                    // Gradients are stored contiguously so they can be read as slices
+                    *grad = Some(g.contiguous());
+                }
+            }
+        }
+    }
+}
 
-        let mut topo = Vec::new();
-        let mut visited = HashSet::new();
-        build_topo(self, &mut visited, &mut topo);
+/// Returns a key identifying a tensor, shared by its clones.
+pub(crate) fn node_id(t: &Tensor) -> usize {
+    Arc::as_ptr(&t.0) as usize
+}
 
-        *self.grad.borrow_mut() = Some(Tensor::ones(&self.shape));
+/// Propagates the gradients `grad_outputs` of `outputs` back through their graph, without storing
+/// them in the tensors.
+/// # Returns
+/// Every tensor reached along with its gradient, in reverse topological order
+pub(crate) fn propagate(outputs: &[&Tensor], grad_outputs: Vec<Tensor>) -> Vec<(Tensor, Tensor)> {
+    let mut topo = Vec::new();
+    let mut visited = HashSet::new();
+    for output in outputs {
+        build_topo(output, &mut visited, &mut topo);
+    }
 
-        for t in topo.into_iter().rev() {
-            let grad_out = match &*t.grad.borrow() {
-                Some(g) => g.clone(),
-                None => continue,
-            };
+    let mut grads = HashMap::new();
+    for (output, grad) in outputs.iter().zip(grad_outputs) {
+        accumulate(&mut grads, output, grad);
+    }
 
-            let grad_fn = match &t.grad_fn {
-                Some(f) => f,
-                None => continue,
-            };
+    let mut reached = Vec::new();
+    for t in topo.into_iter().rev() {
+        let Some(grad_out) = grads.remove(&node_id(&t)) else {
+            continue;
+        };
+
+        if let Some(grad_fn) = &t.grad_fn {
+            // Saved inputs must still hold the values the operation was computed with
+            let saved = grad_fn.saved_tensors();
+            if let Some((tensor, _)) = saved.iter().find(|(t, version)| t.version() != *version) {
+                panic!(
+                    "backward: a tensor of shape {:?} saved by {} was modified in place after \
+                     being used, so its gradient cannot be computed",
+                    tensor.shape,
+                    grad_fn.type_name()
+                );
+            }
 
             let parent_grads = grad_fn.apply(&grad_out);
-
             let parents = t.parents.iter().filter(|p| p.requires_grad);
-
             for (parent, g) in parents.zip(parent_grads) {
-                if !parent.requires_grad {
-                    continue;
-                }
-
                 let g = if g.shape() != parent.shape() {
                     g.sum_to_shape(parent.shape())
                 } else {
                     g
                 };
-
-                let mut parent_grad = parent.grad.borrow_mut();
-                match &mut *parent_grad {
-                    Some(existing) => {
-                        *existing = &*existing + &g;
-                    }
-                    None => {
-                        *parent_grad = Some(g);
-                    }
-                }
+                accumulate(&mut grads, parent, g);
             }
         }
+        reached.push((t, grad_out));
     }
+    reached
+}
 
-    /// Sums the tensor to match the specified shape by summing over dimensions where the target shape has size 1.
-    /// # Arguments
-    /// * `shape` - The target shape to sum to.
-    /// # Returns
-    /// A new tensor summed to the specified shape.
-    pub fn sum_to_shape(&self, shape: &[usize]) -> Tensor {
-        let mut result = self.clone();
-        for (i, &dim) in Tensor::reduce_shape(self.shape()).iter().enumerate() {
-            if shape[i] == 1 && dim != 1 {
-                result = result.sum_axis(i);
-            }
+fn accumulate(grads: &mut HashMap<usize, Tensor>, t: &Tensor, grad: Tensor) {
+    match grads.entry(node_id(t)) {
+        Entry::Occupied(mut entry) => {
+            let sum = entry.get() + &grad;
+            entry.insert(sum);
         }
-        result.reshape(shape)
-    }
-
-    /// Expands the tensor by repeating its data along a new dimension.
-    /// # Arguments
-    /// * `axis` - The axis along which to expand the tensor.
-    /// * `size` - The size of the new dimension.
-    /// # Returns
-    /// A new tensor with the expanded dimension.
-    pub fn expand_dim(&self, axis: usize, size: usize) -> Tensor {
-        assert!(axis <= self.shape.len(), "expand_dim: invalid axis");
-
-        let mut new_shape = self.shape.clone();
-        new_shape.insert(axis, size);
-
-        let old_shape = &self.shape;
-        let old_strides = Tensor::compute_strides(old_shape);
-        let new_strides = Tensor::compute_strides(&new_shape);
-
-        let mut new_data = vec![0.0; new_shape.iter().product()];
-
-        for idx in 0..new_data.len() {
-            // Convert flat index → multi-index in new tensor
-            let mut rem = idx;
-            let mut new_indices = vec![0; new_shape.len()];
-            for i in 0..new_shape.len() {
-                new_indices[i] = rem / new_strides[i];
-                rem %= new_strides[i];
-            }
-
-            // Remove expanded axis to get old tensor index
-            let mut old_indices = new_indices;
-            old_indices.remove(axis);
-
-            let old_flat = old_indices
-                .iter()
-                .zip(old_strides.iter())
-                .map(|(i, s)| i * s)
-                .sum::<usize>();
-
-            new_data[idx] = self.storage.data[old_flat];
+        Entry::Vacant(entry) => {
+            entry.insert(grad);
         }
-
-        Tensor::new(new_data, &new_shape)
     }
 }
 
 fn build_topo(t: &Tensor, visited: &mut HashSet<usize>, out: &mut Vec<Tensor>) {
-    let id = Rc::as_ptr(&t.0) as usize;
+    let id = node_id(t);
     if visited.contains(&id) {
         return;
     }
     visited.insert(id);
 
     for p in &t.parents {
-        build_topo(&p, visited, out);
+        build_topo(p, visited, out);
     }
 
     out.push(t.clone());
@@ -0,0 +1,622 @@
+//! Forward-mode automatic differentiation with dual tensors.
+//!
+//! A [`DualTensor`] carries a tangent along with its value: the direction in which the value
+//! moves. Operations on dual tensors compute their result and how it moves, so a single call of
+//! a function gives the product of its Jacobian by the tangents of its inputs. This costs one
+//! evaluation per input direction, which makes it cheaper than reverse mode when the function
+//! has few inputs and many outputs.
+//! ```rust
+//! use nn_rs::linalg::autograd::forward_ad::{DualTensor, jvp};
+//! use nn_rs::linalg::tensor::Tensor;
+//!
+//! // f(x) = sum(x^2), which moves by 2 x . v along v
+//! let x = Tensor::new(vec![1.0, 2.0], &[2]);
+//! let v = Tensor::new(vec![1.0, 1.0], &[2]);
+//! let (value, tangent) = jvp(|inputs| inputs[0].square().sum(), &[&x], &[&v]);
+//! assert_eq!(value.as_slice(), &[5.0]);
+//! assert_eq!(tangent.as_slice(), &[6.0]);
+//!
+//! // Tensors that do not move are constants
+//! let w = DualTensor::constant(Tensor::new(vec![3.0], &[1]));
+//! let y = &DualTensor::new(x, v) * &w;
+//! assert_eq!(y.tangent().as_slice(), &[3.0, 3.0]);
+//! ```
+
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype};
+use crate::linalg::tensor::{Scalar, Tensor};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A tensor paired with a tangent of the same shape, for forward-mode differentiation.
+#[derive(Clone, Debug)]
+pub struct DualTensor {
+    primal: Tensor,
+    // None stands for a tangent of zeros, which constants do not need to compute
+    tangent: Option<Tensor>,
+}
+
+impl DualTensor {
+    /// Creates a dual tensor moving along `tangent`. Panics if the shapes differ or if `primal`
+    /// is not a floating point tensor.
+    pub fn new(primal: Tensor, tangent: Tensor) -> DualTensor {
+        DualTensor::try_new(primal, tangent).or_panic()
+    }
+
+    /// Creates a dual tensor moving along `tangent`, which is converted to the dtype of `primal`.
+    /// Returns an error if the shapes differ or if `primal` is not a floating point tensor.
+    pub fn try_new(primal: Tensor, tangent: Tensor) -> Result<DualTensor> {
+        check_dtype("dual", primal.dtype(), |dtype| dtype.is_floating_point())?;
+        if tangent.shape() != primal.shape() {
+            return Err(TensorError::ShapeMismatch {
+                op: "dual",
+                lhs: primal.shape().to_vec(),
+                rhs: tangent.shape().to_vec(),
+            });
+        }
+        let tangent = tangent.to_dtype(primal.dtype());
+        Ok(DualTensor {
+            primal,
+            tangent: Some(tangent),
+        })
+    }
+
+    /// Creates a dual tensor that does not move, whose tangent is zero.
+    pub fn constant(primal: Tensor) -> DualTensor {
+        DualTensor {
+            primal,
+            tangent: None,
+        }
+    }
+
+    /// Pairs the result of an operation with its tangent, broadcasting the tangent to the shape
+    /// of the result when it comes from a broadcast operand.
+    fn from_parts(primal: Tensor, tangent: Option<Tensor>) -> DualTensor {
+        let tangent = tangent.map(|tangent| {
+            let tangent = tangent.to_dtype(primal.dtype());
+            if tangent.shape() == primal.shape() {
+                tangent
+            } else {
+                tangent.broadcast_to(primal.shape())
+            }
+        });
+        DualTensor { primal, tangent }
+    }
+
+    /// Returns the value of the tensor.
+    pub fn primal(&self) -> &Tensor {
+        &self.primal
+    }
+
+    /// Returns the tangent of the tensor, which is zero for constants.
+    pub fn tangent(&self) -> Tensor {
+        match &self.tangent {
+            Some(tangent) => tangent.clone(),
+            None => self.primal.zeros_like(),
+        }
+    }
+
+    /// Returns the value and the tangent of the tensor.
+    pub fn into_parts(self) -> (Tensor, Tensor) {
+        let tangent = self.tangent();
+        (self.primal, tangent)
+    }
+
+    /// Returns the shape of the tensor.
+    pub fn shape(&self) -> &[usize] {
+        self.primal.shape()
+    }
+
+    /// Applies `f` to the tangent, if the tensor moves.
+    fn map_tangent(&self, f: impl FnOnce(&Tensor) -> Tensor) -> Option<Tensor> {
+        self.tangent.as_ref().map(f)
+    }
+
+    /// Element-wise addition.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_add(&self, other: &DualTensor) -> Result<DualTensor> {
+        let primal = self.primal.try_add(&other.primal)?;
+        let tangent = sum_tangents(self.tangent.clone(), other.tangent.clone());
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Element-wise subtraction.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_sub(&self, other: &DualTensor) -> Result<DualTensor> {
+        let primal = self.primal.try_sub(&other.primal)?;
+        let tangent = sum_tangents(self.tangent.clone(), other.map_tangent(|t| -t));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Element-wise multiplication.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_mul(&self, other: &DualTensor) -> Result<DualTensor> {
+        let primal = self.primal.try_mul(&other.primal)?;
+        let tangent = sum_tangents(
+            self.map_tangent(|t| t * &other.primal),
+            other.map_tangent(|t| &self.primal * t),
+        );
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Element-wise division.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_div(&self, other: &DualTensor) -> Result<DualTensor> {
+        let primal = self.primal.try_div(&other.primal)?;
+        // d(a / b) = (da - (a / b) db) / b
+        let tangent = sum_tangents(self.tangent.clone(), other.map_tangent(|t| -(&primal * t)))
+            .map(|t| &t / &other.primal);
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Raises every element to the power `exponent`.
+    pub fn pow(&self, exponent: Scalar) -> DualTensor {
+        let tangent = self.map_tangent(|t| t * &(self.primal.pow(exponent - 1.0) * exponent));
+        DualTensor::from_parts(self.primal.pow(exponent), tangent)
+    }
+
+    /// Squares every element.
+    pub fn square(&self) -> DualTensor {
+        let tangent = self.map_tangent(|t| t * &(&self.primal * 2.0));
+        DualTensor::from_parts(self.primal.square(), tangent)
+    }
+
+    /// Computes the square root of every element.
+    pub fn sqrt(&self) -> DualTensor {
+        let primal = self.primal.sqrt();
+        let tangent = self.map_tangent(|t| t / &(&primal * 2.0));
+        DualTensor::from_parts(primal, tangent)
+    }
+
+    /// Computes the exponential of every element.
+    pub fn exp(&self) -> DualTensor {
+        let primal = self.primal.exp();
+        let tangent = self.map_tangent(|t| t * &primal);
+        DualTensor::from_parts(primal, tangent)
+    }
+
+    /// Computes the natural logarithm of every element.
+    pub fn log(&self) -> DualTensor {
+        let tangent = self.map_tangent(|t| t / &self.primal);
+        DualTensor::from_parts(self.primal.log(), tangent)
+    }
+
+    /// Computes the absolute value of every element.
+    pub fn abs(&self) -> DualTensor {
+        let tangent = self.map_tangent(|t| t * &self.primal.sign());
+        DualTensor::from_parts(self.primal.abs(), tangent)
+    }
+
+    /// Clamps every element between `min` and `max`. Clamped elements do not move.
+    pub fn clamp(&self, min: Scalar, max: Scalar) -> DualTensor {
+        let primal = self.primal.clamp(min, max);
+        let tangent = self.map_tangent(|t| masked(&self.primal.eq(&primal), t));
+        DualTensor::from_parts(primal, tangent)
+    }
+
+    /// Computes the sigmoid of every element.
+    pub fn sigmoid(&self) -> DualTensor {
+        let primal = self.primal.sigmoid();
+        let tangent = self.map_tangent(|t| t * &(&primal * &(1.0 - &primal)));
+        DualTensor::from_parts(primal, tangent)
+    }
+
+    /// Computes the ReLU of every element.
+    pub fn relu(&self) -> DualTensor {
+        let zero = Tensor::from_scalar(0.0).to_dtype(self.primal.dtype());
+        let tangent = self.map_tangent(|t| masked(&self.primal.ge(&zero), t));
+        DualTensor::from_parts(self.primal.relu(), tangent)
+    }
+
+    /// Computes the softmax along `axis`. Panics if the axis is out of bounds.
+    pub fn softmax(&self, axis: usize) -> DualTensor {
+        self.try_softmax(axis).or_panic()
+    }
+
+    /// Computes the softmax along `axis`.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_softmax(&self, axis: usize) -> Result<DualTensor> {
+        let primal = self.primal.try_softmax(axis)?;
+        // ds = s (dx - sum(s dx))
+        let tangent = self.map_tangent(|t| {
+            let moved = &primal * t;
+            &moved - &(&primal * &moved.sum_axes(&[axis], true))
+        });
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Computes the log-softmax along `axis`. Panics if the axis is out of bounds.
+    pub fn log_softmax(&self, axis: usize) -> DualTensor {
+        self.try_log_softmax(axis).or_panic()
+    }
+
+    /// Computes the log-softmax along `axis`.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_log_softmax(&self, axis: usize) -> Result<DualTensor> {
+        let primal = self.primal.try_log_softmax(axis)?;
+        let tangent = self.map_tangent(|t| {
+            let softmax = primal.exp();
+            t - &(&softmax * t).sum_axes(&[axis], true)
+        });
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Computes `log(sum(exp(x)))` along `axis`. Panics if the axis is out of bounds.
+    pub fn logsumexp(&self, axis: usize, keepdim: bool) -> DualTensor {
+        self.try_logsumexp(axis, keepdim).or_panic()
+    }
+
+    /// Computes `log(sum(exp(x)))` along `axis`.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_logsumexp(&self, axis: usize, keepdim: bool) -> Result<DualTensor> {
+        let primal = self.primal.try_logsumexp(axis, keepdim)?;
+        let tangent = self.map_tangent(|t| {
+            let softmax = self.primal.softmax(axis);
+            (&softmax * t)
+                .sum_axes(&[axis], true)
+                .reshape(primal.shape())
+        });
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Matrix product, following the broadcasting rules of [`Tensor::matmul`]. Panics if the
+    /// shapes do not fit.
+    pub fn matmul(&self, other: &DualTensor) -> DualTensor {
+        self.try_matmul(other).or_panic()
+    }
+
+    /// Matrix product.
+    /// Returns an error if the shapes do not fit.
+    pub fn try_matmul(&self, other: &DualTensor) -> Result<DualTensor> {
+        let primal = self.primal.try_matmul(&other.primal)?;
+        let tangent = sum_tangents(
+            self.map_tangent(|t| t.matmul(&other.primal)),
+            other.map_tangent(|t| self.primal.matmul(t)),
+        );
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Computes the sum of all elements.
+    pub fn sum(&self) -> DualTensor {
+        DualTensor::from_parts(self.primal.sum(), self.map_tangent(Tensor::sum))
+    }
+
+    /// Computes the sum over `axes`. Panics if an axis is out of bounds or repeated.
+    pub fn sum_axes(&self, axes: &[usize], keepdim: bool) -> DualTensor {
+        self.try_sum_axes(axes, keepdim).or_panic()
+    }
+
+    /// Computes the sum over `axes`.
+    /// Returns an error if one of the axes is out of bounds or repeated.
+    pub fn try_sum_axes(&self, axes: &[usize], keepdim: bool) -> Result<DualTensor> {
+        let primal = self.primal.try_sum_axes(axes, keepdim)?;
+        let tangent = self.map_tangent(|t| t.sum_axes(axes, keepdim));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Computes the mean over `axes`. Panics if an axis is out of bounds or repeated.
+    pub fn mean_axes(&self, axes: &[usize], keepdim: bool) -> DualTensor {
+        self.try_mean_axes(axes, keepdim).or_panic()
+    }
+
+    /// Computes the mean over `axes`.
+    /// Returns an error if one of the axes is out of bounds or repeated.
+    pub fn try_mean_axes(&self, axes: &[usize], keepdim: bool) -> Result<DualTensor> {
+        let primal = self.primal.try_mean_axes(axes, keepdim)?;
+        let tangent = self.map_tangent(|t| t.mean_axes(axes, keepdim));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Computes the maximum over `axes`. The tangent is the one of the first maximum of every
+    /// reduced slice. Panics if an axis is out of bounds, repeated or empty.
+    pub fn max_axes(&self, axes: &[usize], keepdim: bool) -> DualTensor {
+        self.try_max_axes(axes, keepdim).or_panic()
+    }
+
+    /// Computes the maximum over `axes`.
+    /// Returns an error if one of the axes is out of bounds, repeated or empty.
+    pub fn try_max_axes(&self, axes: &[usize], keepdim: bool) -> Result<DualTensor> {
+        let primal = self.primal.try_max_axes(axes, keepdim)?;
+        let tangent = self.map_tangent(|t| self.selected_tangent(t, axes, &primal, false));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Computes the minimum over `axes`. The tangent is the one of the first minimum of every
+    /// reduced slice. Panics if an axis is out of bounds, repeated or empty.
+    pub fn min_axes(&self, axes: &[usize], keepdim: bool) -> DualTensor {
+        self.try_min_axes(axes, keepdim).or_panic()
+    }
+
+    /// Computes the minimum over `axes`.
+    /// Returns an error if one of the axes is out of bounds, repeated or empty.
+    pub fn try_min_axes(&self, axes: &[usize], keepdim: bool) -> Result<DualTensor> {
+        let primal = self.primal.try_min_axes(axes, keepdim)?;
+        let tangent = self.map_tangent(|t| self.selected_tangent(t, axes, &primal, true));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Picks the tangent of the first maximum (or minimum) of every slice over `axes`, which
+    /// have been validated by the reduction giving `primal`.
+    fn selected_tangent(
+        &self,
+        tangent: &Tensor,
+        axes: &[usize],
+        primal: &Tensor,
+        min: bool,
+    ) -> Tensor {
+        // Moving the reduced axes to the end and merging them turns every slice into a lane
+        // along the last axis, in the row-major order used to break ties
+        let mut axes = axes.to_vec();
+        axes.sort_unstable();
+        let shape = self.primal.shape();
+        let permutation = (0..shape.len())
+            .filter(|axis| !axes.contains(axis))
+            .chain(axes.iter().copied())
+            .collect::<Vec<usize>>();
+        let last = shape.len() - axes.len();
+        let mut lanes_shape = permutation[..last]
+            .iter()
+            .map(|&axis| shape[axis])
+            .collect::<Vec<usize>>();
+        lanes_shape.push(axes.iter().map(|&axis| shape[axis]).product());
+        let lanes = |t: &Tensor| t.permute(&permutation).reshape(&lanes_shape);
+
+        let values = lanes(&self.primal);
+        let index = if min {
+            values.argmin_axes(&[last], true)
+        } else {
+            values.argmax_axes(&[last], true)
+        };
+        lanes(tangent).gather(last, &index).reshape(primal.shape())
+    }
+
+    /// Reshapes the tensor. Panics if the number of elements differs.
+    pub fn reshape(self, shape: &[usize]) -> DualTensor {
+        self.try_reshape(shape).or_panic()
+    }
+
+    /// Reshapes the tensor.
+    /// Returns an error if the number of elements differs.
+    pub fn try_reshape(self, shape: &[usize]) -> Result<DualTensor> {
+        let primal = self.primal.try_reshape(shape)?;
+        let tangent = self.tangent.map(|t| t.reshape(shape));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Transposes the last two axes, which transposes every matrix of a batch.
+    /// Panics if the tensor has fewer than two axes.
+    pub fn transpose(&self) -> DualTensor {
+        self.try_transpose().or_panic()
+    }
+
+    /// Transposes the last two axes, which transposes every matrix of a batch.
+    /// Returns an error if the tensor has fewer than two axes.
+    pub fn try_transpose(&self) -> Result<DualTensor> {
+        let rank = self.primal.shape().len();
+        if rank < 2 {
+            return Err(TensorError::Rank {
+                op: "transpose",
+                expected: 2,
+                actual: rank,
+            });
+        }
+        Ok(DualTensor::from_parts(
+            self.primal.matrix_transpose(),
+            self.map_tangent(Tensor::matrix_transpose),
+        ))
+    }
+
+    /// Reorders the axes. Panics if `axes` is not a permutation of the axes.
+    pub fn permute(&self, axes: &[usize]) -> DualTensor {
+        self.try_permute(axes).or_panic()
+    }
+
+    /// Reorders the axes.
+    /// Returns an error if `axes` is not a permutation of the axes.
+    pub fn try_permute(&self, axes: &[usize]) -> Result<DualTensor> {
+        let primal = self.primal.try_permute(axes)?;
+        let tangent = self.map_tangent(|t| t.permute(axes));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Inserts an axis of size one at `axis`. Panics if the axis is out of bounds.
+    pub fn unsqueeze(&self, axis: usize) -> DualTensor {
+        self.try_unsqueeze(axis).or_panic()
+    }
+
+    /// Inserts an axis of size one at `axis`.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_unsqueeze(&self, axis: usize) -> Result<DualTensor> {
+        let primal = self.primal.try_unsqueeze(axis)?;
+        let tangent = self.map_tangent(|t| t.unsqueeze(axis));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Keeps `len` elements along `axis`, starting at `start`. Panics if the range is out of
+    /// bounds.
+    pub fn narrow(&self, axis: usize, start: usize, len: usize) -> DualTensor {
+        self.try_narrow(axis, start, len).or_panic()
+    }
+
+    /// Keeps `len` elements along `axis`, starting at `start`.
+    /// Returns an error if the axis or the range is out of bounds.
+    pub fn try_narrow(&self, axis: usize, start: usize, len: usize) -> Result<DualTensor> {
+        let primal = self.primal.try_narrow(axis, start, len)?;
+        let tangent = self.map_tangent(|t| t.narrow(axis, start, len));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+
+    /// Selects the element at `index` along `axis`, removing the axis. Panics if the axis or the
+    /// index is out of bounds.
+    pub fn select(&self, axis: usize, index: usize) -> DualTensor {
+        self.try_select(axis, index).or_panic()
+    }
+
+    /// Selects the element at `index` along `axis`.
+    /// Returns an error if the axis or the index is out of bounds.
+    pub fn try_select(&self, axis: usize, index: usize) -> Result<DualTensor> {
+        let primal = self.primal.try_select(axis, index)?;
+        let tangent = self.map_tangent(|t| t.select(axis, index));
+        Ok(DualTensor::from_parts(primal, tangent))
+    }
+}
+
+/// Adds two tangents, either of which may be zero.
+fn sum_tangents(a: Option<Tensor>, b: Option<Tensor>) -> Option<Tensor> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(&a + &b),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Keeps the tangent where `mask` is true and sets it to zero elsewhere.
+fn masked(mask: &Tensor, tangent: &Tensor) -> Tensor {
+    let zero = Tensor::from_scalar(0.0).to_dtype(tangent.dtype());
+    Tensor::where_(mask, tangent, &zero)
+}
+
+impl Add for &DualTensor {
+    type Output = DualTensor;
+    fn add(self, other: &DualTensor) -> DualTensor {
+        self.try_add(other).or_panic()
+    }
+}
+
+impl Add<Scalar> for &DualTensor {
+    type Output = DualTensor;
+    fn add(self, other: Scalar) -> DualTensor {
+        DualTensor::from_parts(&self.primal + other, self.tangent.clone())
+    }
+}
+
+impl Sub for &DualTensor {
+    type Output = DualTensor;
+    fn sub(self, other: &DualTensor) -> DualTensor {
+        self.try_sub(other).or_panic()
+    }
+}
+
+impl Sub<Scalar> for &DualTensor {
+    type Output = DualTensor;
+    fn sub(self, other: Scalar) -> DualTensor {
+        DualTensor::from_parts(&self.primal - other, self.tangent.clone())
+    }
+}
+
+impl Mul for &DualTensor {
+    type Output = DualTensor;
+    fn mul(self, other: &DualTensor) -> DualTensor {
+        self.try_mul(other).or_panic()
+    }
+}
+
+impl Mul<Scalar> for &DualTensor {
+    type Output = DualTensor;
+    fn mul(self, other: Scalar) -> DualTensor {
+        DualTensor::from_parts(&self.primal * other, self.map_tangent(|t| t * other))
+    }
+}
+
+impl Div for &DualTensor {
+    type Output = DualTensor;
+    fn div(self, other: &DualTensor) -> DualTensor {
+        self.try_div(other).or_panic()
+    }
+}
+
+impl Div<Scalar> for &DualTensor {
+    type Output = DualTensor;
+    fn div(self, other: Scalar) -> DualTensor {
+        DualTensor::from_parts(&self.primal / other, self.map_tangent(|t| t / other))
+    }
+}
+
+impl Neg for &DualTensor {
+    type Output = DualTensor;
+    fn neg(self) -> DualTensor {
+        DualTensor::from_parts(-&self.primal, self.map_tangent(|t| -t))
+    }
+}
+
+/// Computes `f` and the product of its Jacobian by `tangents` in a single forward pass.
+/// # Arguments
+/// * `f` - The function to differentiate, written with dual tensors
+/// * `primals` - The point at which to differentiate `f`
+/// * `tangents` - The direction in which every input moves, with its shape
+/// # Returns
+/// The output of `f` and its tangent
+pub fn jvp<F: Fn(&[DualTensor]) -> DualTensor>(
+    f: F,
+    primals: &[&Tensor],
+    tangents: &[&Tensor],
+) -> (Tensor, Tensor) {
+    try_jvp(f, primals, tangents).or_panic()
+}
+
+/// Computes `f` and the product of its Jacobian by `tangents`.
+/// Returns an error if the tangents do not match the inputs, or if an input is not a floating
+/// point tensor.
+pub fn try_jvp<F: Fn(&[DualTensor]) -> DualTensor>(
+    f: F,
+    primals: &[&Tensor],
+    tangents: &[&Tensor],
+) -> Result<(Tensor, Tensor)> {
+    if tangents.len() != primals.len() {
+        return Err(TensorError::InvalidArgument(format!(
+            "jvp: {} tangents given for {} inputs",
+            tangents.len(),
+            primals.len()
+        )));
+    }
+    let inputs = primals
+        .iter()
+        .zip(tangents)
+        .map(|(&primal, &tangent)| DualTensor::try_new(primal.clone(), tangent.clone()))
+        .collect::<Result<Vec<DualTensor>>>()?;
+    Ok(f(&inputs).into_parts())
+}
+
+/// Computes the Jacobian of `f` one column at a time, with one forward pass per input element.
+/// # Returns
+/// The Jacobian with respect to every input, whose shape is the shape of the output followed by
+/// the shape of the input
+pub fn jacobian<F: Fn(&[DualTensor]) -> DualTensor>(f: F, inputs: &[&Tensor]) -> Vec<Tensor> {
+    try_jacobian(f, inputs).or_panic()
+}
+
+/// Computes the Jacobian of `f` one column at a time.
+/// Returns an error if an input is not a floating point tensor.
+pub fn try_jacobian<F: Fn(&[DualTensor]) -> DualTensor>(
+    f: F,
+    inputs: &[&Tensor],
+) -> Result<Vec<Tensor>> {
+    for input in inputs {
+        check_dtype("jacobian", input.dtype(), |dtype| dtype.is_floating_point())?;
+    }
+    let constants = inputs
+        .iter()
+        .map(|&input| DualTensor::constant(input.clone()))
+        .collect::<Vec<DualTensor>>();
+    let output = f(&constants).primal;
+
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, &input)| {
+            let shape = [output.shape(), input.shape()].concat();
+            if input.numel() == 0 {
+                return Ok(Tensor::zeros(&shape).to_dtype(output.dtype()));
+            }
+            // Moving one element of the input at a time gives one column of the Jacobian
+            let identity = Tensor::eye(input.numel()).to_dtype(input.dtype());
+            let columns = (0..input.numel())
+                .map(|k| {
+                    let mut duals = constants.clone();
+                    let direction = identity.select(0, k).reshape(input.shape());
+                    duals[i] = DualTensor::try_new(input.clone(), direction)?;
+                    Ok(f(&duals).tangent())
+                })
+                .collect::<Result<Vec<Tensor>>>()?;
+            let columns = columns.iter().collect::<Vec<&Tensor>>();
+            Ok(Tensor::stack(&columns, output.shape().len()).reshape(&shape))
+        })
+        .collect()
+}
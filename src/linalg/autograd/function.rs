@@ -0,0 +1,170 @@
+//! Differentiable operations defined outside of the crate.
+//!
+//! A [`Function`] computes its output with any tensor operations, and provides the gradient of
+//! its inputs itself. Applying it records a single node in the graph, so `backward` calls its
+//! [`Function::backward`] like the gradient of a built-in operation.
+//! ```rust
+//! use nn_rs::linalg::autograd::function::{Context, Function};
+//! use nn_rs::linalg::error::Result;
+//! use nn_rs::linalg::tensor::Tensor;
+//!
+//! /// Computes `x^3`.
+//! struct Cube;
+//!
+//! impl Function for Cube {
+//!     fn forward(&self, ctx: &mut Context, inputs: &[&Tensor]) -> Result<Tensor> {
+//!         ctx.save_for_backward(&[inputs[0]]);
+//!         Ok(&inputs[0].square() * inputs[0])
+//!     }
+//!
+//!     fn backward(&self, ctx: &Context, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+//!         let x = &ctx.saved_tensors()[0];
+//!         vec![Some(grad_output * &(x.square() * 3.0))]
+//!     }
+//! }
+//!
+//! let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+//! let y = Cube.apply(&[&x]);
+//! y.sum().backward();
+//! assert_eq!(x.grad().unwrap().as_slice(), &[3.0, 12.0]);
+//! ```
+
+use crate::linalg::autograd::grad_fn::GradFn;
+use crate::linalg::autograd::grad_mode::no_grad;
+use crate::linalg::error::{OrPanic, Result, check_dtype};
+use crate::linalg::tensor::Tensor;
+use std::sync::Arc;
+
+/// State shared by the forward and backward pass of a [`Function`].
+pub struct Context {
+    saved: Vec<Tensor>,
+    saved_versions: Vec<usize>,
+    needs_input_grad: Vec<bool>,
+}
+
+impl Context {
+    /// Keeps tensors needed to compute the gradients. Modifying them in place before `backward`
+    /// makes it panic. Saved inputs keep their history, so that `backward` can be differentiated
+    /// when [`Tensor::backward_with`] creates a graph.
+    pub fn save_for_backward(&mut self, tensors: &[&Tensor]) {
+        self.saved
+            .extend(tensors.iter().map(|&tensor| tensor.clone()));
+        self.saved_versions
+            .extend(tensors.iter().map(|tensor| tensor.version()));
+    }
+
+    /// Returns the tensors kept by [`Context::save_for_backward`], in order.
+    pub fn saved_tensors(&self) -> &[Tensor] {
+        &self.saved
+    }
+
+    /// Returns true if the gradient of the input at `index` is needed. The gradients of the other
+    /// inputs are ignored, so `backward` may skip computing them.
+    pub fn needs_input_grad(&self, index: usize) -> bool {
+        self.needs_input_grad.get(index).copied().unwrap_or(false)
+    }
+}
+
+/// A differentiable operation whose gradient is given by [`Function::backward`].
+pub trait Function: Send + Sync + 'static {
+    /// Computes the output of the operation. No graph is recorded while it runs, so the
+    /// operations it uses do not need to be differentiable.
+    /// # Arguments
+    /// * `ctx` - Where to save the tensors needed by `backward`
+    /// * `inputs` - The inputs of the operation
+    fn forward(&self, ctx: &mut Context, inputs: &[&Tensor]) -> Result<Tensor>;
+
+    /// Computes the gradient of every input from the gradient of the output. Gradients are only
+    /// enabled while it runs when [`Tensor::backward_with`] creates a graph, in which case the
+    /// operations it uses are recorded so that its gradients can be differentiated again.
+    /// # Returns
+    /// One gradient per input, in order, with the shape of the input or one that can be summed
+    /// to it. `None` stands for a gradient of zeros.
+    fn backward(&self, ctx: &Context, grad_output: &Tensor) -> Vec<Option<Tensor>>;
+
+    /// Applies the operation to `inputs`, recording it in the graph when one of them requires
+    /// gradients.
+    fn apply(self, inputs: &[&Tensor]) -> Tensor
+    where
+        Self: Sized,
+    {
+        self.try_apply(inputs).or_panic()
+    }
+
+    /// Applies the operation to `inputs`, recording it in the graph when one of them requires
+    /// gradients.
+    /// Returns the error of `forward`, or an error if the output cannot require gradients
+    /// because it is not a floating point tensor.
+    fn try_apply(self, inputs: &[&Tensor]) -> Result<Tensor>
+    where
+        Self: Sized,
+    {
+        let mut ctx = Context {
+            saved: Vec::new(),
+            saved_versions: Vec::new(),
+            needs_input_grad: inputs.iter().map(|input| input.records_grad()).collect(),
+        };
+        let output = {
+            let _guard = no_grad();
+            self.forward(&mut ctx, inputs)?
+        };
+        if !ctx.needs_input_grad.contains(&true) {
+            return Ok(output);
+        }
+        check_dtype("apply", output.dtype(), |dtype| dtype.is_floating_point())?;
+
+        let input_shapes = inputs.iter().map(|input| input.shape().to_vec()).collect();
+        let mut output = output.detached().requiring_grad();
+        output.set_grad_metadata(
+            Arc::new(FunctionGradFn {
+                function: self,
+                ctx,
+                input_shapes,
+            }),
+            inputs.iter().map(|&input| input.clone()).collect(),
+        );
+        Ok(output)
+    }
+}
+
+/// Gradient of a [`Function`], returning the gradients of the inputs that require them.
+struct FunctionGradFn<F: Function> {
+    function: F,
+    ctx: Context,
+    input_shapes: Vec<Vec<usize>>,
+}
+
+impl<F: Function> GradFn for FunctionGradFn<F> {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        let grads = self.function.backward(&self.ctx, grad_output);
+        assert_eq!(
+            grads.len(),
+            self.input_shapes.len(),
+            "{}: backward returned {} gradients for {} inputs",
+            self.type_name(),
+            grads.len(),
+            self.input_shapes.len()
+        );
+        grads
+            .into_iter()
+            .zip(&self.input_shapes)
+            .zip(&self.ctx.needs_input_grad)
+            .filter(|(_, needs_grad)| **needs_grad)
+            .map(|((grad, shape), _)| {
+                grad.unwrap_or_else(|| Tensor::zeros(shape).to_dtype(grad_output.dtype()))
+            })
+            .collect()
+    }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        self.ctx
+            .saved
+            .iter()
+            .zip(self.ctx.saved_versions.iter().copied())
+            .collect()
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<F>()
+    }
+}
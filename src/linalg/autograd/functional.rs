@@ -0,0 +1,302 @@
+//! Gradients computed as values rather than accumulated into tensors.
+//!
+//! [`grad`] differentiates outputs with respect to chosen inputs and returns the gradients,
+//! leaving the `grad` of every tensor untouched. The other functions differentiate a function of
+//! tensors, which they call on copies of the inputs that require gradients:
+//! * [`vjp`] and [`jvp`] multiply the Jacobian of the function by a vector, from the left or
+//!   from the right
+//! * [`jacobian`] and [`hessian`] build the whole matrices, one row at a time
+//! ```rust
+//! use nn_rs::linalg::autograd::functional::{grad, hessian};
+//! use nn_rs::linalg::tensor::Tensor;
+//!
+//! let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+//! let y = x.pow(3.0).sum();
+//! assert_eq!(grad(&[&y], &[&x], None)[0].as_slice(), &[3.0, 12.0]);
+//! assert!(x.grad().is_none());
+//!
+//! // The Hessian of sum(x^3) is diagonal, with 6x on the diagonal
+//! let h = hessian(|inputs| inputs[0].pow(3.0).sum(), &[&x]);
+//! assert_eq!(h[0][0].as_slice(), &[6.0, 0.0, 0.0, 12.0]);
+//! ```
+
+use crate::linalg::autograd::backward::{node_id, propagate};
+use crate::linalg::autograd::grad_mode::{enable_grad, is_inference_mode_enabled, no_grad};
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype};
+use crate::linalg::tensor::Tensor;
+use std::collections::HashMap;
+
+/// Computes the gradients of `outputs` with respect to `inputs`, without storing them in the
+/// `grad` of any tensor.
+/// # Arguments
+/// * `outputs` - The tensors to differentiate
+/// * `inputs` - The tensors to differentiate with respect to, which may be intermediate results
+/// * `grad_outputs` - The gradient of every output, or `None` for gradients of ones
+/// # Returns
+/// The gradient of every input, in order. Inputs the outputs do not depend on get zeros.
+pub fn grad(
+    outputs: &[&Tensor],
+    inputs: &[&Tensor],
+    grad_outputs: Option<&[&Tensor]>,
+) -> Vec<Tensor> {
+    try_grad(outputs, inputs, grad_outputs).or_panic()
+}
+
+/// Computes the gradients of `outputs` with respect to `inputs`.
+/// Returns an error if the number or the shapes of `grad_outputs` do not match `outputs`.
+pub fn try_grad(
+    outputs: &[&Tensor],
+    inputs: &[&Tensor],
+    grad_outputs: Option<&[&Tensor]>,
+) -> Result<Vec<Tensor>> {
+    gradients("grad", outputs, inputs, grad_outputs, false)
+}
+
+/// Computes the gradients of `outputs` with respect to `inputs` like [`grad`], optionally
+/// recording the gradient computations so that the gradients can be differentiated again.
+/// # Arguments
+/// * `create_graph` - Whether the gradients record a graph
+pub fn grad_with(
+    outputs: &[&Tensor],
+    inputs: &[&Tensor],
+    grad_outputs: Option<&[&Tensor]>,
+    create_graph: bool,
+) -> Vec<Tensor> {
+    try_grad_with(outputs, inputs, grad_outputs, create_graph).or_panic()
+}
+
+/// Computes the gradients of `outputs` with respect to `inputs`.
+/// Returns an error if the number or the shapes of `grad_outputs` do not match `outputs`.
+pub fn try_grad_with(
+    outputs: &[&Tensor],
+    inputs: &[&Tensor],
+    grad_outputs: Option<&[&Tensor]>,
+    create_graph: bool,
+) -> Result<Vec<Tensor>> {
+    gradients("grad", outputs, inputs, grad_outputs, create_graph)
+}
+
+/// Computes the product of a vector by the Jacobian of `f`, which is the gradient of the inputs
+/// when the output has the gradient `v`.
+/// # Arguments
+/// * `f` - The function to differentiate
+/// * `inputs` - The point at which to differentiate `f`
+/// * `v` - A tensor with the shape of the output of `f`
+/// # Returns
+/// The output of `f`, and the product for every input
+pub fn vjp<F: Fn(&[Tensor]) -> Tensor>(
+    f: F,
+    inputs: &[&Tensor],
+    v: &Tensor,
+) -> (Tensor, Vec<Tensor>) {
+    try_vjp(f, inputs, v).or_panic()
+}
+
+/// Computes the product of a vector by the Jacobian of `f`.
+/// Returns an error if an input is not a floating point tensor, if `v` does not have the shape
+/// of the output, or if called in inference mode.
+pub fn try_vjp<F: Fn(&[Tensor]) -> Tensor>(
+    f: F,
+    inputs: &[&Tensor],
+    v: &Tensor,
+) -> Result<(Tensor, Vec<Tensor>)> {
+    let inputs = differentiable_inputs("vjp", inputs)?;
+    let output = call(f, &inputs);
+    let grads = gradients("vjp", &[&output], &refs(&inputs), Some(&[v]), false)?;
+    Ok((output.detached(), grads))
+}
+
+/// Computes the product of the Jacobian of `f` by a vector, which is the change of the output
+/// when the inputs move along `v`. It takes two backward passes, while
+/// [`forward_ad::jvp`](crate::linalg::autograd::forward_ad::jvp) computes it in a single forward
+/// pass for functions written with dual tensors.
+/// # Arguments
+/// * `f` - The function to differentiate
+/// * `inputs` - The point at which to differentiate `f`
+/// * `v` - One tensor per input, with its shape
+/// # Returns
+/// The output of `f`, and the product, which has the shape of the output
+pub fn jvp<F: Fn(&[Tensor]) -> Tensor>(
+    f: F,
+    inputs: &[&Tensor],
+    v: &[&Tensor],
+) -> (Tensor, Tensor) {
+    try_jvp(f, inputs, v).or_panic()
+}
+
+/// Computes the product of the Jacobian of `f` by a vector.
+/// Returns an error if an input or the output is not a floating point tensor, if `v` does not
+/// match the inputs, or if called in inference mode.
+pub fn try_jvp<F: Fn(&[Tensor]) -> Tensor>(
+    f: F,
+    inputs: &[&Tensor],
+    v: &[&Tensor],
+) -> Result<(Tensor, Tensor)> {
+    let inputs = differentiable_inputs("jvp", inputs)?;
+    let output = call(f, &inputs);
+    check_dtype("jvp", output.dtype(), |dtype| dtype.is_floating_point())?;
+
+    // The product u -> u J is linear in u, so differentiating it along v gives J v
+    let u = output.zeros_like().requiring_grad();
+    let vjps = gradients("jvp", &[&output], &refs(&inputs), Some(&[&u]), true)?;
+    let mut jvp = gradients("jvp", &refs(&vjps), &[&u], Some(v), false)?;
+    Ok((output.detached(), jvp.remove(0)))
+}
+
+/// Computes the Jacobian of `f`, with one row per element of the output.
+/// # Returns
+/// The Jacobian with respect to every input, whose shape is the shape of the output followed by
+/// the shape of the input
+pub fn jacobian<F: Fn(&[Tensor]) -> Tensor>(f: F, inputs: &[&Tensor]) -> Vec<Tensor> {
+    try_jacobian(f, inputs).or_panic()
+}
+
+/// Computes the Jacobian of `f`.
+/// Returns an error if an input is not a floating point tensor, or if called in inference mode.
+pub fn try_jacobian<F: Fn(&[Tensor]) -> Tensor>(f: F, inputs: &[&Tensor]) -> Result<Vec<Tensor>> {
+    let inputs = differentiable_inputs("jacobian", inputs)?;
+    let output = call(f, &inputs);
+    jacobian_blocks("jacobian", &output, &refs(&inputs))
+}
+
+/// Computes the Hessian of `f`, which returns a single value, as the Jacobian of its gradient.
+/// # Returns
+/// The block of second derivatives with respect to every pair of inputs, where the block `[i][j]`
+/// has the shape of input `i` followed by the shape of input `j`
+pub fn hessian<F: Fn(&[Tensor]) -> Tensor>(f: F, inputs: &[&Tensor]) -> Vec<Vec<Tensor>> {
+    try_hessian(f, inputs).or_panic()
+}
+
+/// Computes the Hessian of `f`.
+/// Returns an error if an input is not a floating point tensor, if `f` returns more than one
+/// value, or if called in inference mode.
+pub fn try_hessian<F: Fn(&[Tensor]) -> Tensor>(
+    f: F,
+    inputs: &[&Tensor],
+) -> Result<Vec<Vec<Tensor>>> {
+    let inputs = differentiable_inputs("hessian", inputs)?;
+    let output = call(f, &inputs);
+    if output.numel() != 1 {
+        return Err(TensorError::InvalidArgument(format!(
+            "hessian: the function must return a single value, got shape {:?}",
+            output.shape()
+        )));
+    }
+    let inputs = refs(&inputs);
+    let grads = gradients("hessian", &[&output], &inputs, None, true)?;
+    grads
+        .iter()
+        .map(|grad| jacobian_blocks("hessian", grad, &inputs))
+        .collect()
+}
+
+/// Propagates `grad_outputs` from `outputs` and returns the gradients reaching `inputs`.
+fn gradients(
+    op: &'static str,
+    outputs: &[&Tensor],
+    inputs: &[&Tensor],
+    grad_outputs: Option<&[&Tensor]>,
+    create_graph: bool,
+) -> Result<Vec<Tensor>> {
+    let grad_outputs = match grad_outputs {
+        None => outputs.iter().map(|output| output.ones_like()).collect(),
+        Some(grad_outputs) => {
+            if grad_outputs.len() != outputs.len() {
+                return Err(TensorError::InvalidArgument(format!(
+                    "{op}: {} gradients given for {} outputs",
+                    grad_outputs.len(),
+                    outputs.len()
+                )));
+            }
+            outputs
+                .iter()
+                .zip(grad_outputs)
+                .map(|(output, grad)| {
+                    if grad.shape() != output.shape() {
+                        return Err(TensorError::ShapeMismatch {
+                            op,
+                            lhs: output.shape().to_vec(),
+                            rhs: grad.shape().to_vec(),
+                        });
+                    }
+                    Ok(grad.to_dtype(output.dtype()))
+                })
+                .collect::<Result<Vec<Tensor>>>()?
+        }
+    };
+
+    let _guard = if create_graph {
+        enable_grad()
+    } else {
+        no_grad()
+    };
+    // Outputs that do not require gradients do not depend on any input
+    let (outputs, grad_outputs): (Vec<&Tensor>, Vec<Tensor>) = outputs
+        .iter()
+        .zip(grad_outputs)
+        .filter(|(output, _)| output.requires_grad())
+        .map(|(&output, grad)| (output, grad))
+        .unzip();
+    let grads = propagate(&outputs, grad_outputs)
+        .into_iter()
+        .map(|(t, grad)| (node_id(&t), grad))
+        .collect::<HashMap<usize, Tensor>>();
+    Ok(inputs
+        .iter()
+        .map(|input| match grads.get(&node_id(input)) {
+            Some(grad) => grad.contiguous(),
+            None => input.zeros_like(),
+        })
+        .collect())
+}
+
+/// Returns the Jacobian of `output` with respect to every input, built from the gradients of
+/// every element of the output.
+fn jacobian_blocks(op: &'static str, output: &Tensor, inputs: &[&Tensor]) -> Result<Vec<Tensor>> {
+    let identity = Tensor::eye(output.numel()).to_dtype(output.dtype());
+    let rows = (0..output.numel())
+        .map(|i| {
+            let one_hot = identity.select(0, i).reshape(output.shape());
+            gradients(op, &[output], inputs, Some(&[&one_hot]), false)
+        })
+        .collect::<Result<Vec<Vec<Tensor>>>>()?;
+    Ok(inputs
+        .iter()
+        .enumerate()
+        .map(|(j, input)| {
+            let shape = [output.shape(), input.shape()].concat();
+            if rows.is_empty() {
+                return Tensor::zeros(&shape).to_dtype(input.dtype());
+            }
+            let column = rows.iter().map(|row| &row[j]).collect::<Vec<&Tensor>>();
+            Tensor::stack(&column, 0).reshape(&shape)
+        })
+        .collect())
+}
+
+/// Returns copies of `inputs` that require gradients and have no history, so that the gradients
+/// of a function called on them stop at them.
+fn differentiable_inputs(op: &'static str, inputs: &[&Tensor]) -> Result<Vec<Tensor>> {
+    if is_inference_mode_enabled() {
+        return Err(TensorError::InvalidArgument(format!(
+            "{op}: gradients cannot be computed in inference mode"
+        )));
+    }
+    inputs
+        .iter()
+        .map(|input| {
+            check_dtype(op, input.dtype(), |dtype| dtype.is_floating_point())?;
+            Ok(input.detached().requiring_grad())
+        })
+        .collect()
+}
+
+/// Calls `f` with gradients enabled, so that it records a graph even within `no_grad`.
+fn call<F: Fn(&[Tensor]) -> Tensor>(f: F, inputs: &[Tensor]) -> Tensor {
+    let _guard = enable_grad();
+    f(inputs)
+}
+
+fn refs(tensors: &[Tensor]) -> Vec<&Tensor> {
+    tensors.iter().collect()
+}
@@ -1,32 +1,80 @@
 pub(crate) mod activation;
 pub(crate) mod binary;
+pub(crate) mod index;
 pub(crate) mod matmul;
 pub(crate) mod reduce;
 pub(crate) mod shape;
 pub(crate) mod unary;
 
+use crate::dispatch_numeric;
+use crate::linalg::dtype::Numeric;
 use crate::linalg::tensor::Tensor;
+use std::ops::Deref;
 
-pub(crate) trait GradFn {
+pub(crate) trait GradFn: Send + Sync {
     /// Applies the gradient function to the given gradient output tensor_old and returns the gradients for each parent tensor_old.
+    /// The gradients are computed with tensor operations, so that they record a graph of their
+    /// own when `backward_with` is asked to create one.
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor>;
+    /// Returns the tensors read by `apply`, with the version of their data when they were saved.
+    /// Backpropagation panics if one of them was modified in place since.
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        Vec::new()
+    }
     fn type_name(&self) -> &'static str {
         std::any::type_name::<Self>()
     }
 }
 
-pub(crate) struct NotImplementedGradFn(pub(crate) &'static str);
+/// A tensor saved for the backward pass, along with the version of its data at that time.
+pub(crate) struct SavedTensor {
+    tensor: Tensor,
+    version: usize,
+}
+
+impl SavedTensor {
+    pub(crate) fn new(tensor: Tensor) -> Self {
+        let version = tensor.version();
+        Self { tensor, version }
+    }
+
+    /// Returns the tensor along with its saved version, as listed by [`GradFn::saved_tensors`].
+    pub(crate) fn entry(&self) -> (&Tensor, usize) {
+        (&self.tensor, self.version)
+    }
+}
+
+impl Deref for SavedTensor {
+    type Target = Tensor;
 
-impl GradFn for NotImplementedGradFn {
-    fn apply(&self, _grad_output: &Tensor) -> Vec<Tensor> {
-        panic!("{}'s gradient is not defined", self.0);
+    fn deref(&self) -> &Tensor {
+        &self.tensor
     }
 }
-#[macro_export]
-macro_rules! not_implemented_grad_fn {
-    ($name:expr) => {
-        Some(Rc::new(
-            $crate::linalg::autograd::grad_fn::NotImplementedGradFn($name),
-        ))
-    };
+
+/// Adds every element of `grad` to the element at the matching row-major position in a tensor of
+/// zeros of shape `shape`. When `grad` records a graph the sum is done with `scatter_add`, so that
+/// it can be differentiated again.
+pub(crate) fn accumulate_at(
+    grad: &Tensor,
+    positions: impl Iterator<Item = usize>,
+    shape: &[usize],
+) -> Tensor {
+    let numel = shape.iter().product();
+    if grad.records_grad() {
+        let index = positions
+            .map(|position| position as i64)
+            .collect::<Vec<i64>>();
+        let index = Tensor::from_vec(index, &[grad.numel()]);
+        let grad = grad.contiguous().reshape(&[grad.numel()]);
+        let zeros = Tensor::zeros(&[numel]).to_dtype(grad.dtype());
+        return zeros.scatter_add(0, &index, &grad).reshape(shape);
+    }
+    dispatch_numeric!(grad.dtype(), T => {
+        let mut data = vec![T::zero(); numel];
+        for (position, value) in positions.zip(grad.values::<T>()) {
+            data[position] = data[position].add(value);
+        }
+        Tensor::from_vec(data, shape)
+    })
 }
@@ -1,28 +1,33 @@
-use crate::linalg::autograd::grad_fn::GradFn;
-use crate::linalg::tensor::{Scalar, Tensor};
+use crate::linalg::autograd::grad_fn::{GradFn, SavedTensor};
+use crate::linalg::tensor::Tensor;
 
 pub(crate) struct SigmoidGradFn {
-    output: Tensor,
+    input: SavedTensor,
+    output: SavedTensor,
 }
 
 impl SigmoidGradFn {
-    pub fn new(output: Tensor) -> Self {
-        Self { output }
+    pub fn new(input: Tensor, output: Tensor) -> Self {
+        Self {
+            input: SavedTensor::new(input),
+            output: SavedTensor::new(output),
+        }
     }
 }
 
 impl GradFn for SigmoidGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let out_data = self
-            .output
-            .storage
-            .data
-            .iter()
-            .zip(grad_output.storage.data.iter())
-            .map(|(&o, &g)| g * o * (1.0 - o))
-            .collect::<Vec<Scalar>>();
+        // The saved output has no history, so it is recomputed when the gradient records a graph
+        let output = if self.input.records_grad() {
+            self.input.sigmoid()
+        } else {
+            Tensor::clone(&self.output)
+        };
+        vec![grad_output * &(&output * &(1.0 - &output))]
+    }
 
-        vec![Tensor::new(out_data, self.output.shape())]
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.input.entry(), self.output.entry()]
     }
 }
 
@@ -38,14 +43,6 @@ impl ReLUGradFn {
 
 impl GradFn for ReLUGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let out_data = grad_output
-            .storage
-            .data
-            .iter()
-            .zip(self.mask.storage.data.iter())
-            .map(|(&g, &m)| g * m)
-            .collect::<Vec<Scalar>>();
-
-        vec![Tensor::new(out_data, grad_output.shape())]
+        vec![grad_output * &self.mask]
     }
 }
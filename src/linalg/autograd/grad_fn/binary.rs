@@ -1,4 +1,4 @@
-use crate::linalg::autograd::grad_fn::GradFn;
+use crate::linalg::autograd::grad_fn::{GradFn, SavedTensor};
 use crate::linalg::tensor::{Scalar, Tensor};
 
 /// Gradient for element-wise addition
@@ -41,14 +41,12 @@ impl SubGradFn {
 impl GradFn for SubGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
         let mut grads = Vec::new();
-        let mut i = 0;
         if self.grad_left {
-            let shape = self.parents[i].shape();
+            let shape = self.parents[0].shape();
             grads.push(grad_output.sum_to_shape(shape));
-            i += 1;
         }
         if self.grad_right {
-            let shape = self.parents[i].shape();
+            let shape = self.parents[self.parents.len() - 1].shape();
             grads.push(-grad_output.sum_to_shape(shape));
         }
         grads
@@ -57,8 +55,8 @@ impl GradFn for SubGradFn {
 
 /// Gradient for element-wise multiplication (supports optional scalar)
 pub(crate) struct EWSMultGradFn {
-    a: Tensor,
-    b: Tensor,
+    a: SavedTensor,
+    b: SavedTensor,
 }
 
 impl EWSMultGradFn {
@@ -67,7 +65,10 @@ impl EWSMultGradFn {
             .map(Tensor::from_scalar)
             .or(parent_b)
             .expect("Either scalar or tensor must be provided");
-        Self { a, b: b_tensor }
+        Self {
+            a: SavedTensor::new(a),
+            b: SavedTensor::new(b_tensor),
+        }
     }
 }
 
@@ -75,19 +76,23 @@ impl GradFn for EWSMultGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
         let mut grads = Vec::new();
         if self.a.requires_grad {
-            grads.push((grad_output * &self.b).sum_to_shape(self.a.shape()));
+            grads.push((grad_output * &*self.b).sum_to_shape(self.a.shape()));
         }
         if self.b.requires_grad {
-            grads.push((grad_output * &self.a).sum_to_shape(self.b.shape()));
+            grads.push((grad_output * &*self.a).sum_to_shape(self.b.shape()));
         }
         grads
     }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.a.entry(), self.b.entry()]
+    }
 }
 
 /// Gradient for division (supports optional scalars)
 pub(crate) struct DivGradFn {
-    a: Tensor,
-    b: Tensor,
+    a: SavedTensor,
+    b: SavedTensor,
 }
 
 impl DivGradFn {
@@ -106,8 +111,8 @@ impl DivGradFn {
             .or(parent_b)
             .expect("Either scalar or parent tensor b must be provided");
         Self {
-            a: a_tensor,
-            b: b_tensor,
+            a: SavedTensor::new(a_tensor),
+            b: SavedTensor::new(b_tensor),
         }
     }
 }
@@ -116,11 +121,77 @@ impl GradFn for DivGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
         let mut grads = Vec::new();
         if self.a.requires_grad {
-            grads.push(grad_output.sum_to_shape(self.a.shape()));
+            // d(a / b) / da = 1 / b
+            grads.push((grad_output / &*self.b).sum_to_shape(self.a.shape()));
         }
         if self.b.requires_grad {
-            grads.push((-grad_output).sum_to_shape(self.b.shape()));
+            // d(a / b) / db = -a / b^2
+            let grad = -(grad_output * &*self.a) / self.b.square();
+            grads.push(grad.sum_to_shape(self.b.shape()));
+        }
+        grads
+    }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.a.entry(), self.b.entry()]
+    }
+}
+
+/// Gradient for broadcasting a tensor to a larger shape
+pub(crate) struct BroadcastToGradFn {
+    input_shape: Vec<usize>,
+}
+
+impl BroadcastToGradFn {
+    pub fn new(input_shape: Vec<usize>) -> Self {
+        Self { input_shape }
+    }
+}
+
+impl GradFn for BroadcastToGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        vec![grad_output.sum_to_shape(&self.input_shape)]
+    }
+}
+
+/// Gradient for selecting elements from two tensors with a boolean condition
+pub(crate) struct WhereGradFn {
+    condition: SavedTensor,
+    a_shape: Option<Vec<usize>>,
+    b_shape: Option<Vec<usize>>,
+}
+
+impl WhereGradFn {
+    /// The shape of each branch is given only if it requires a gradient.
+    pub fn new(
+        condition: Tensor,
+        a_shape: Option<Vec<usize>>,
+        b_shape: Option<Vec<usize>>,
+    ) -> Self {
+        Self {
+            condition: SavedTensor::new(condition),
+            a_shape,
+            b_shape,
+        }
+    }
+}
+
+impl GradFn for WhereGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        // Selecting zeros rather than multiplying by the mask keeps infinite gradients of the
+        // other branch from turning into NaN
+        let zero = Tensor::from_scalar(0.0).to_dtype(grad_output.dtype());
+        let mut grads = Vec::new();
+        if let Some(shape) = &self.a_shape {
+            grads.push(Tensor::where_(&self.condition, grad_output, &zero).sum_to_shape(shape));
+        }
+        if let Some(shape) = &self.b_shape {
+            grads.push(Tensor::where_(&self.condition, &zero, grad_output).sum_to_shape(shape));
         }
         grads
     }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.condition.entry()]
+    }
 }
@@ -0,0 +1,110 @@
+use crate::linalg::autograd::grad_fn::{GradFn, SavedTensor, accumulate_at};
+use crate::linalg::tensor::Tensor;
+
+/// Gradient for gathering elements along an axis, which scatter-adds every output gradient back to
+/// the input element it was read from. Elements gathered several times accumulate several gradients.
+pub(crate) struct GatherGradFn {
+    axis: usize,
+    index: SavedTensor,
+    input_shape: Vec<usize>,
+}
+
+impl GatherGradFn {
+    pub fn new(axis: usize, index: Tensor, input_shape: Vec<usize>) -> Self {
+        Self {
+            axis,
+            index: SavedTensor::new(index),
+            input_shape,
+        }
+    }
+}
+
+impl GradFn for GatherGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        let zeros = Tensor::zeros(&self.input_shape).to_dtype(grad_output.dtype());
+        vec![zeros.scatter_add(self.axis, &self.index, grad_output)]
+    }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.index.entry()]
+    }
+}
+
+/// Gradient for writing (or adding) the elements of `src` into the input at the positions given
+/// by `index` along an axis. `src` has the shape of `index`.
+pub(crate) struct ScatterGradFn {
+    axis: usize,
+    index: SavedTensor,
+    accumulate: bool,
+    input_requires_grad: bool,
+    src_requires_grad: bool,
+}
+
+impl ScatterGradFn {
+    pub fn new(
+        axis: usize,
+        index: Tensor,
+        accumulate: bool,
+        input_requires_grad: bool,
+        src_requires_grad: bool,
+    ) -> Self {
+        Self {
+            axis,
+            index: SavedTensor::new(index),
+            accumulate,
+            input_requires_grad,
+            src_requires_grad,
+        }
+    }
+}
+
+impl GradFn for ScatterGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        let mut grads = Vec::new();
+        if self.input_requires_grad {
+            if self.accumulate {
+                grads.push(grad_output.clone());
+            } else {
+                // Overwritten elements do not contribute to the output
+                let zeros = Tensor::zeros(self.index.shape()).to_dtype(grad_output.dtype());
+                grads.push(grad_output.scatter(self.axis, &self.index, &zeros));
+            }
+        }
+        if self.src_requires_grad {
+            grads.push(grad_output.gather(self.axis, &self.index));
+        }
+        grads
+    }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.index.entry()]
+    }
+}
+
+/// Gradient for selecting the elements of a tensor where a mask is true. `positions` holds the
+/// row-major position of every selected element in the tensor broadcast to `shape`.
+pub(crate) struct MaskedSelectGradFn {
+    shape: Vec<usize>,
+    positions: Vec<usize>,
+    input_shape: Vec<usize>,
+}
+
+impl MaskedSelectGradFn {
+    pub fn new(shape: Vec<usize>, positions: Vec<usize>, input_shape: Vec<usize>) -> Self {
+        Self {
+            shape,
+            positions,
+            input_shape,
+        }
+    }
+}
+
+impl GradFn for MaskedSelectGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        // Every position is selected at most once, so accumulating amounts to writing
+        let positions = self.positions.iter().copied();
+        let grad = accumulate_at(grad_output, positions, &self.shape);
+        // Elements of a broadcast input were selected once per copy
+        vec![grad.sum_to_shape(&self.input_shape)]
+    }
+}
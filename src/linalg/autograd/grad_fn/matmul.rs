@@ -1,36 +1,40 @@
-use crate::linalg::autograd::grad_fn::GradFn;
+use crate::linalg::autograd::grad_fn::{GradFn, SavedTensor};
 use crate::linalg::tensor::Tensor;
 
 pub struct MatMulGradFn {
-    pub lhs: Tensor, // normalized A
-    pub rhs: Tensor, // normalized B
-    pub lhs_shape: Vec<usize>,
-    pub rhs_shape: Vec<usize>,
+    lhs: SavedTensor, // normalized A, at least 2D
+    rhs: SavedTensor, // normalized B, at least 2D
+}
+
+impl MatMulGradFn {
+    pub fn new(lhs: Tensor, rhs: Tensor) -> Self {
+        Self {
+            lhs: SavedTensor::new(lhs),
+            rhs: SavedTensor::new(rhs),
+        }
+    }
 }
 
 impl GradFn for MatMulGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
         let mut grads = Vec::new();
 
-        // dL/dA
+        // dL/dA, summed over the batch dimensions A was broadcast along
         if self.lhs.requires_grad {
-            let mut grad_a = grad_output.matmul(&self.rhs.transpose());
-            // only sum if lhs_shape had extra dims (unsqueezed)
-            if grad_a.shape() != self.lhs_shape {
-                grad_a = grad_a.sum_to_shape(&self.lhs_shape);
-            }
-            grads.push(grad_a);
+            let grad_a = grad_output.matmul(&self.rhs.matrix_transpose());
+            grads.push(grad_a.sum_to_shape(self.lhs.shape()));
         }
 
-        // dL/dB
+        // dL/dB, summed over the batch dimensions B was broadcast along
         if self.rhs.requires_grad {
-            let mut grad_b = self.lhs.transpose().matmul(grad_output);
-            if grad_b.shape() != self.rhs_shape {
-                grad_b = grad_b.sum_to_shape(&self.rhs_shape);
-            }
-            grads.push(grad_b);
+            let grad_b = self.lhs.matrix_transpose().matmul(grad_output);
+            grads.push(grad_b.sum_to_shape(self.rhs.shape()));
         }
 
         grads
     }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.lhs.entry(), self.rhs.entry()]
+    }
 }
@@ -1,78 +1,182 @@
-use crate::linalg::autograd::grad_fn::GradFn;
+use crate::dispatch_numeric;
+use crate::linalg::autograd::grad_fn::{GradFn, SavedTensor, accumulate_at};
+use crate::linalg::dtype::{DType, Numeric};
+use crate::linalg::ops::reduce::reduction_slots;
 use crate::linalg::tensor::Tensor;
 
-pub(crate) struct MeanGradFn {
-    pub(crate) axes: Vec<usize>,
+pub(crate) struct SumAxesGradFn {
+    pub(crate) kept_shape: Vec<usize>,
     pub(crate) input_shape: Vec<usize>,
-    pub(crate) scale: f32,
 }
 
-impl MeanGradFn {
-    pub fn new(axes: Vec<usize>, input_shape: Vec<usize>) -> Self {
-        let scale = axes.iter().map(|&a| input_shape[a] as f32).product();
+impl SumAxesGradFn {
+    /// `kept_shape` is the input shape with the reduced axes set to one.
+    pub fn new(kept_shape: Vec<usize>, input_shape: Vec<usize>) -> Self {
         Self {
-            axes,
+            kept_shape,
             input_shape,
-            scale,
         }
     }
 }
 
-impl GradFn for MeanGradFn {
+impl GradFn for SumAxesGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let mut grad_input = grad_output.clone();
-
-        for &axis in &self.axes {
-            grad_input = grad_input.expand_dim(axis, self.input_shape[axis]);
-        }
-
-        grad_input = grad_input * (1.0 / self.scale);
+        let grad_input = grad_output
+            .clone()
+            .reshape(&self.kept_shape)
+            .expand(&self.input_shape);
         vec![grad_input]
     }
 }
 
-pub(crate) struct SumAxisGradFn {
-    pub(crate) input_shape: Vec<usize>,
+pub(crate) struct ProdGradFn {
+    pub(crate) input: SavedTensor,
+    pub(crate) kept_shape: Vec<usize>,
 }
 
-impl SumAxisGradFn {
-    pub fn new(input_shape: Vec<usize>) -> Self {
-        Self { input_shape }
+impl ProdGradFn {
+    pub fn new(input: Tensor, kept_shape: Vec<usize>) -> Self {
+        Self {
+            input: SavedTensor::new(input),
+            kept_shape,
+        }
     }
 }
 
-impl GradFn for SumAxisGradFn {
+impl GradFn for ProdGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let mut grad_input = grad_output.clone();
-
-        for (axis, &dim) in self.input_shape.iter().enumerate() {
-            if grad_input.shape.get(axis).copied().unwrap_or(1) != dim {
-                grad_input = grad_input.expand_dim(axis, dim);
+        // The gradient of every element is the product of the other elements of its slice.
+        // Zeros are counted apart so that this product never divides by zero.
+        let slots = reduction_slots(self.input.shape(), &self.kept_shape);
+        let (others, has_zero) = dispatch_numeric!(self.input.dtype(), T => {
+            let size = self.kept_shape.iter().product();
+            let mut zeros = vec![0usize; size];
+            let mut non_zero_product = vec![T::one(); size];
+            for (slot, value) in slots.clone().zip(self.input.values::<T>()) {
+                if value == T::zero() {
+                    zeros[slot] += 1;
+                } else {
+                    non_zero_product[slot] = non_zero_product[slot].mul(value);
+                }
             }
-        }
+            let data = slots
+                .zip(self.input.values::<T>())
+                .map(|(slot, value)| match zeros[slot] {
+                    0 => non_zero_product[slot].div(value),
+                    1 if value == T::zero() => non_zero_product[slot],
+                    _ => T::zero(),
+                })
+                .collect::<Vec<T>>();
+            let has_zero = zeros.iter().any(|&count| count > 0);
+            (Tensor::from_vec(data, self.input.shape()), has_zero)
+        });
+        // To differentiate the gradient again, the product of the other elements is recomputed
+        // with tensor operations: as the product of the slice divided by the element, or
+        // without dividing when the input holds zeros.
+        let others = if !self.input.records_grad() {
+            others
+        } else if has_zero {
+            others_product(&self.input, &self.kept_shape)
+        } else {
+            let axes = (0..self.kept_shape.len())
+                .filter(|&d| self.kept_shape[d] == 1)
+                .collect::<Vec<usize>>();
+            &self.input.prod_axes(&axes, true) / &*self.input
+        };
+        let grad_output = grad_output
+            .clone()
+            .reshape(&self.kept_shape)
+            .expand(self.input.shape());
+        vec![&grad_output * &others]
+    }
 
-        vec![grad_input]
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.input.entry()]
+    }
+}
+
+/// Returns the product of the other elements of the slice of every element of `input`, computed
+/// with differentiable operations. The elements of every slice are repeated once per element,
+/// with that element replaced by one, and each copy is multiplied.
+fn others_product(input: &Tensor, kept_shape: &[usize]) -> Tensor {
+    // Moves the reduced axes last and flattens the input to one slice per row
+    let (kept, reduced): (Vec<usize>, Vec<usize>) =
+        (0..kept_shape.len()).partition(|&d| kept_shape[d] != 1);
+    let order = [kept.as_slice(), reduced.as_slice()].concat();
+    let rows = kept.iter().map(|&d| input.shape()[d]).product();
+    let len = reduced.iter().map(|&d| input.shape()[d]).product();
+    let slices = input.permute(&order).contiguous().reshape(&[rows, len]);
+
+    let diagonal = Tensor::eye(len).to_dtype(DType::Bool);
+    let one = Tensor::from_scalar(1.0).to_dtype(input.dtype());
+    let copies = slices.unsqueeze(1).expand(&[rows, len, len]);
+    let others = Tensor::where_(&diagonal, &one, &copies).prod_axes(&[2], false);
+
+    let permuted_shape = order
+        .iter()
+        .map(|&d| input.shape()[d])
+        .collect::<Vec<usize>>();
+    let mut inverse = vec![0; order.len()];
+    for (i, &d) in order.iter().enumerate() {
+        inverse[d] = i;
     }
+    others.reshape(&permuted_shape).permute(&inverse)
 }
 
-pub(crate) struct SumGradFn {
+pub(crate) struct ExtremumGradFn {
     pub(crate) input_shape: Vec<usize>,
+    pub(crate) positions: Vec<usize>,
 }
 
-impl SumGradFn {
-    pub fn new(input_shape: Vec<usize>) -> Self {
-        Self { input_shape }
+impl ExtremumGradFn {
+    /// `positions` holds the logical position in the input of the selected element of every output element.
+    pub fn new(input_shape: Vec<usize>, positions: Vec<usize>) -> Self {
+        Self {
+            input_shape,
+            positions,
+        }
     }
 }
 
-impl GradFn for SumGradFn {
+impl GradFn for ExtremumGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let mut grad_input = grad_output.clone();
+        // Only the selected maximum or minimum of every slice receives the gradient
+        let positions = self.positions.iter().copied();
+        vec![accumulate_at(grad_output, positions, &self.input_shape)]
+    }
+}
+
+pub(crate) struct LogSumExpGradFn {
+    pub(crate) input: SavedTensor,
+    pub(crate) output: SavedTensor,
+    pub(crate) axis: usize,
+}
 
-        for (axis, &dim) in self.input_shape.iter().enumerate() {
-            grad_input = grad_input.expand_dim(axis, dim);
+impl LogSumExpGradFn {
+    /// `output` must keep the reduced axis with size one, so that it broadcasts against `input`.
+    pub fn new(input: Tensor, output: Tensor, axis: usize) -> Self {
+        Self {
+            input: SavedTensor::new(input),
+            output: SavedTensor::new(output),
+            axis,
         }
+    }
+}
 
-        vec![grad_input]
+impl GradFn for LogSumExpGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        // The gradient of logsumexp is the softmax along the reduced axis
+        let grad_output = grad_output.clone().reshape(self.output.shape());
+        let softmax = if self.input.records_grad() {
+            // Recomputing the output lets the softmax be differentiated again
+            self.input.softmax(self.axis)
+        } else {
+            (&*self.input - &*self.output).exp()
+        };
+        vec![&softmax * &grad_output]
+    }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.input.entry(), self.output.entry()]
     }
 }
@@ -1,4 +1,6 @@
-use crate::linalg::autograd::grad_fn::GradFn;
+use crate::linalg::autograd::grad_fn::{GradFn, accumulate_at};
+use crate::linalg::ops::view::Layout;
+use crate::linalg::strided::StridedIter;
 use crate::linalg::tensor::Tensor;
 
 pub(crate) struct TransposeGradFn;
@@ -8,3 +10,77 @@ impl GradFn for TransposeGradFn {
         vec![grad_output.transpose()]
     }
 }
+
+/// Gradient for copying a tensor into a contiguous layout, which does not change its values
+pub(crate) struct ContiguousGradFn;
+
+impl GradFn for ContiguousGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        vec![grad_output.clone()]
+    }
+}
+
+/// Gradient for views sharing the storage of their input (narrow, select, permute, expand, ...).
+/// `layout` describes where each element of the view is read from in a contiguous input, so the
+/// gradient of every element is accumulated back at that position. Positions read several times
+/// (zero strides) accumulate several gradients, and positions never read get zero.
+pub(crate) struct ViewGradFn {
+    input_shape: Vec<usize>,
+    layout: Layout,
+}
+
+impl ViewGradFn {
+    pub fn new(input_shape: Vec<usize>, layout: Layout) -> Self {
+        Self {
+            input_shape,
+            layout,
+        }
+    }
+}
+
+impl GradFn for ViewGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        let Layout {
+            shape,
+            strides,
+            offset,
+        } = &self.layout;
+        let positions = StridedIter::new(shape, strides, *offset);
+        let grad_input = accumulate_at(grad_output, positions, &self.input_shape);
+        vec![grad_input]
+    }
+}
+
+/// Gradient for concatenating tensors along an axis, which hands every input the slice of the
+/// gradient covering its piece of the output
+pub(crate) struct CatGradFn {
+    axis: usize,
+    sizes: Vec<usize>,
+    requires_grad: Vec<bool>,
+}
+
+impl CatGradFn {
+    /// `sizes` holds the size of every input along `axis` and `requires_grad` tells which inputs
+    /// get a gradient.
+    pub fn new(axis: usize, sizes: Vec<usize>, requires_grad: Vec<bool>) -> Self {
+        Self {
+            axis,
+            sizes,
+            requires_grad,
+        }
+    }
+}
+
+impl GradFn for CatGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        let mut start = 0;
+        let mut grads = Vec::new();
+        for (&size, &requires_grad) in self.sizes.iter().zip(&self.requires_grad) {
+            if requires_grad {
+                grads.push(grad_output.narrow(self.axis, start, size));
+            }
+            start += size;
+        }
+        grads
+    }
+}
@@ -1,44 +1,37 @@
-use crate::linalg::autograd::grad_fn::GradFn;
+use crate::linalg::autograd::grad_fn::{GradFn, SavedTensor};
+use crate::linalg::dtype::DType;
 use crate::linalg::tensor::{Scalar, Tensor};
 
 pub(crate) struct NegGradFn;
 
 impl GradFn for NegGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let data = grad_output
-            .storage
-            .data
-            .iter()
-            .map(|&g| -g)
-            .collect::<Vec<Scalar>>();
-
-        vec![Tensor::new(data, grad_output.shape())]
+        vec![-grad_output]
     }
 }
 
 pub(crate) struct PowGradFn {
-    base: Tensor,
+    base: SavedTensor,
     exponent: Scalar,
 }
 
 impl PowGradFn {
     pub fn new(base: Tensor, exponent: Scalar) -> Self {
-        Self { base, exponent }
+        Self {
+            base: SavedTensor::new(base),
+            exponent,
+        }
     }
 }
 
 impl GradFn for PowGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let n = self.base.storage.data.len();
-        let mut grad_input = vec![0.0; n];
-
-        for i in 0..n {
-            grad_input[i] = grad_output.storage.data[i]
-                * self.exponent
-                * self.base.storage.data[i].powf(self.exponent - 1.0);
-        }
+        let local = self.base.pow(self.exponent - 1.0) * self.exponent;
+        vec![grad_output * &local]
+    }
 
-        vec![Tensor::new(grad_input, self.base.shape())]
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.base.entry()]
     }
 }
 
@@ -54,14 +47,7 @@ impl AbsGradFn {
 
 impl GradFn for AbsGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let n = grad_output.storage.data.len();
-        let mut grad_input = vec![0.0; n];
-
-        for i in 0..n {
-            grad_input[i] = grad_output.storage.data[i] * self.sign.storage.data[i];
-        }
-
-        vec![Tensor::new(grad_input, grad_output.shape())]
+        vec![grad_output * &self.sign]
     }
 }
 
@@ -77,59 +63,82 @@ impl ClampGradFn {
 
 impl GradFn for ClampGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let n = grad_output.storage.data.len();
-        let mut grad_input = vec![0.0; n];
-
-        for i in 0..n {
-            grad_input[i] = grad_output.storage.data[i] * self.mask.storage.data[i];
-        }
-
-        vec![Tensor::new(grad_input, grad_output.shape())]
+        vec![grad_output * &self.mask]
     }
 }
 
 pub(crate) struct LogGradFn {
-    input: Tensor,
+    input: SavedTensor,
 }
 
 impl LogGradFn {
     pub fn new(input: Tensor) -> Self {
-        Self { input }
+        Self {
+            input: SavedTensor::new(input),
+        }
     }
 }
 
 impl GradFn for LogGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let n = self.input.storage.data.len();
-        let mut grad_input = vec![0.0; n];
-
-        for i in 0..n {
-            grad_input[i] = grad_output.storage.data[i] / self.input.storage.data[i];
-        }
+        vec![grad_output / &*self.input]
+    }
 
-        vec![Tensor::new(grad_input, self.input.shape())]
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.input.entry()]
     }
 }
 
 pub(crate) struct ExpGradFn {
-    output: Tensor,
+    input: SavedTensor,
+    output: SavedTensor,
 }
 
 impl ExpGradFn {
-    pub fn new(output: Tensor) -> Self {
-        Self { output }
+    pub fn new(input: Tensor, output: Tensor) -> Self {
+        Self {
+            input: SavedTensor::new(input),
+            output: SavedTensor::new(output),
+        }
     }
 }
 
 impl GradFn for ExpGradFn {
     fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        let n = self.output.storage.data.len();
-        let mut grad_input = vec![0.0; n];
-
-        for i in 0..n {
-            grad_input[i] = grad_output.storage.data[i] * self.output.storage.data[i];
+        // The saved output has no history, so it is recomputed when the gradient records a graph
+        if self.input.records_grad() {
+            return vec![grad_output * &self.input.exp()];
         }
+        vec![grad_output * &*self.output]
+    }
+
+    fn saved_tensors(&self) -> Vec<(&Tensor, usize)> {
+        vec![self.input.entry(), self.output.entry()]
+    }
+}
+
+/// Gradient for dtype conversions, casts the gradient back to the input dtype
+pub(crate) struct CastGradFn {
+    input_dtype: DType,
+}
+
+impl CastGradFn {
+    pub fn new(input_dtype: DType) -> Self {
+        Self { input_dtype }
+    }
+}
+
+impl GradFn for CastGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        vec![grad_output.to_dtype(self.input_dtype)]
+    }
+}
 
-        vec![Tensor::new(grad_input, self.output.shape())]
+/// Gradient for sign, which is zero everywhere it is defined
+pub(crate) struct SignGradFn;
+
+impl GradFn for SignGradFn {
+    fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        vec![Tensor::zeros(grad_output.shape()).to_dtype(grad_output.dtype())]
     }
 }
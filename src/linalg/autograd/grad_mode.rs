@@ -0,0 +1,89 @@
+//! Scoped switches for the recording of computation graphs.
+//!
+//! Operations only record the graph needed by `backward` while gradients are enabled. The modes
+//! are per thread and set by guards, which restore the previous mode when dropped:
+//! ```rust
+//! use nn_rs::linalg::autograd::grad_mode::no_grad;
+//! use nn_rs::linalg::tensor::Tensor;
+//! let w = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+//! {
+//!     let _guard = no_grad();
+//!     assert!(!(&w * 2.0).requires_grad());
+//! }
+//! assert!((&w * 2.0).requires_grad());
+//! ```
+
+use crate::linalg::tensor::InternalTensor;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+thread_local! {
+    static GRAD_ENABLED: Cell<bool> = const { Cell::new(true) };
+    static INFERENCE_MODE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Restores the grad mode of the thread that existed before it was created when dropped.
+#[must_use = "the grad mode is restored as soon as the guard is dropped"]
+pub struct GradModeGuard {
+    grad_enabled: bool,
+    inference_mode: bool,
+    // The modes belong to the thread that created the guard
+    _not_send: PhantomData<*const ()>,
+}
+
+impl GradModeGuard {
+    fn set(grad_enabled: bool, inference_mode: bool) -> Self {
+        let guard = GradModeGuard {
+            grad_enabled: GRAD_ENABLED.get(),
+            inference_mode: INFERENCE_MODE.get(),
+            _not_send: PhantomData,
+        };
+        GRAD_ENABLED.set(grad_enabled);
+        INFERENCE_MODE.set(inference_mode);
+        guard
+    }
+}
+
+impl Drop for GradModeGuard {
+    fn drop(&mut self) {
+        GRAD_ENABLED.set(self.grad_enabled);
+        INFERENCE_MODE.set(self.inference_mode);
+    }
+}
+
+/// Stops recording computation graphs on this thread until the guard is dropped.
+/// Results of operations do not require gradients, even when their inputs do.
+pub fn no_grad() -> GradModeGuard {
+    GradModeGuard::set(false, INFERENCE_MODE.get())
+}
+
+/// Records computation graphs again on this thread until the guard is dropped, for example to
+/// differentiate a function called within [`no_grad`]. Has no effect in [`inference_mode`].
+pub fn enable_grad() -> GradModeGuard {
+    GradModeGuard::set(true, INFERENCE_MODE.get())
+}
+
+/// Stops recording computation graphs on this thread until the guard is dropped, like
+/// [`no_grad`]. Unlike it, the mode cannot be lifted by [`enable_grad`] within its scope, so code
+/// run for inference never records a graph.
+pub fn inference_mode() -> GradModeGuard {
+    GradModeGuard::set(false, true)
+}
+
+/// Returns true if operations on this thread record computation graphs.
+pub fn is_grad_enabled() -> bool {
+    GRAD_ENABLED.get() && !INFERENCE_MODE.get()
+}
+
+/// Returns true if this thread is in [`inference_mode`].
+pub fn is_inference_mode_enabled() -> bool {
+    INFERENCE_MODE.get()
+}
+
+impl InternalTensor {
+    /// Returns true if operations on the tensor must record a graph node, which is the case when
+    /// it requires gradients and gradients are enabled.
+    pub(crate) fn records_grad(&self) -> bool {
+        self.requires_grad && is_grad_enabled()
+    }
+}
@@ -1,2 +1,6 @@
 mod backward;
+pub mod forward_ad;
+pub mod function;
+pub mod functional;
 pub(crate) mod grad_fn;
+pub mod grad_mode;
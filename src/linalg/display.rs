@@ -0,0 +1,198 @@
+//! Human-readable formatting of tensor values.
+//!
+//! `Display` prints the values of a tensor as nested rows in the style of NumPy, following its
+//! strides and offset. Large tensors are summarized by their first and last elements along every
+//! dimension, and the formatting can be tuned with [`set_print_options`].
+
+use crate::dispatch_all;
+use crate::linalg::dtype::Element;
+use crate::linalg::tensor::Tensor;
+use std::fmt::{Display, Formatter, Write};
+use std::sync::{PoisonError, RwLock};
+
+static OPTIONS: RwLock<PrintOptions> = RwLock::new(PrintOptions::DEFAULT);
+
+/// Settings controlling how tensors are displayed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrintOptions {
+    /// The number of digits printed after the decimal point of floating point values. A precision
+    /// given in the format string, as in `{:.2}`, takes precedence.
+    pub precision: usize,
+    /// The number of elements above which a tensor is summarized.
+    pub threshold: usize,
+    /// The number of elements printed at the start and at the end of every summarized dimension.
+    pub edge_items: usize,
+}
+
+impl PrintOptions {
+    const DEFAULT: PrintOptions = PrintOptions {
+        precision: 4,
+        threshold: 1000,
+        edge_items: 3,
+    };
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        PrintOptions::DEFAULT
+    }
+}
+
+/// Replaces the settings used to display tensors.
+/// # Example
+/// ```rust
+/// use nn_rs::linalg::display::{PrintOptions, print_options, set_print_options};
+/// use nn_rs::linalg::tensor::Tensor;
+/// set_print_options(PrintOptions {
+///     precision: 1,
+///     ..print_options()
+/// });
+/// assert_eq!(Tensor::new(vec![0.25, 1.0], &[2]).to_string(), "[0.2 1.0]");
+/// set_print_options(PrintOptions::default());
+/// ```
+pub fn set_print_options(options: PrintOptions) {
+    *OPTIONS.write().unwrap_or_else(PoisonError::into_inner) = options;
+}
+
+/// Returns the settings currently used to display tensors.
+pub fn print_options() -> PrintOptions {
+    *OPTIONS.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the indices printed along a dimension of size `size`, where `None` stands for the
+/// elided middle of a summarized dimension.
+fn shown_indices(size: usize, summarize: bool, edge_items: usize) -> Vec<Option<usize>> {
+    if summarize && size > 2 * edge_items {
+        (0..edge_items)
+            .map(Some)
+            .chain(std::iter::once(None))
+            .chain((size - edge_items..size).map(Some))
+            .collect()
+    } else {
+        (0..size).map(Some).collect()
+    }
+}
+
+/// Lays out formatted elements as nested rows.
+struct Printer<'a> {
+    shown: Vec<Vec<Option<usize>>>,
+    elements: std::slice::Iter<'a, String>,
+    width: usize,
+}
+
+impl Printer<'_> {
+    fn write(&mut self, out: &mut String, dim: usize) {
+        let rank = self.shown.len();
+        out.push('[');
+        for (i, index) in self.shown[dim].clone().into_iter().enumerate() {
+            if i > 0 {
+                if dim + 1 == rank {
+                    out.push(' ');
+                } else {
+                    // Sub-blocks are separated by one empty line per remaining dimension, and
+                    // aligned after the opening brackets
+                    out.push_str(&"\n".repeat(rank - dim - 1));
+                    out.push_str(&" ".repeat(dim + 1));
+                }
+            }
+            match index {
+                None => out.push_str("..."),
+                Some(_) if dim + 1 == rank => {
+                    let element = self
+                        .elements
+                        .next()
+                        .expect("every shown element is formatted");
+                    write!(out, "{element:>width$}", width = self.width)
+                        .expect("writing to a String");
+                }
+                Some(_) => self.write(out, dim + 1),
+            }
+        }
+        out.push(']');
+    }
+}
+
+/// Formats the element at every position in `positions`, given as indices along each dimension.
+fn format_elements<T: Element>(
+    tensor: &Tensor,
+    positions: &[Vec<usize>],
+    precision: usize,
+) -> Vec<String> {
+    let data = tensor.data::<T>();
+    let values = positions.iter().map(|position| {
+        let index = position
+            .iter()
+            .zip(&tensor.strides)
+            .fold(tensor.offset, |index, (i, stride)| index + i * stride);
+        data[index]
+    });
+    if !T::DTYPE.is_floating_point() {
+        return values.map(|value| format!("{value:?}")).collect();
+    }
+    let values = values.map(Element::to_f64).collect::<Vec<f64>>();
+    // Like NumPy, switch to scientific notation when fixed notation would hide the magnitudes
+    let magnitudes = values
+        .iter()
+        .map(|value| value.abs())
+        .filter(|value| value.is_finite() && *value > 0.0);
+    let (min, max) = magnitudes.fold((f64::INFINITY, 0.0f64), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+    let scientific = max >= 1e8 || min < 10f64.powi(-(precision as i32));
+    values
+        .iter()
+        .map(|value| match value {
+            value if !value.is_finite() => format!("{value}"),
+            value if scientific => format!("{value:.precision$e}"),
+            value => format!("{value:.precision$}"),
+        })
+        .collect()
+}
+
+impl Display for Tensor {
+    /// Prints the values of the tensor as nested rows, e.g. `[[1.0000 2.0000]\n [3.0000 4.0000]]`
+    /// for a 2x2 matrix. Dimensions are summarized with `...` when the tensor holds more elements
+    /// than the threshold of the [`PrintOptions`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let options = print_options();
+        let precision = f.precision().unwrap_or(options.precision);
+        if self.numel() == 0 {
+            return f.write_str("[]");
+        }
+
+        let summarize = self.numel() > options.threshold;
+        let shown = self
+            .shape
+            .iter()
+            .map(|&size| shown_indices(size, summarize, options.edge_items))
+            .collect::<Vec<_>>();
+        let mut positions = vec![Vec::new()];
+        for indices in &shown {
+            positions = positions
+                .into_iter()
+                .flat_map(|position: Vec<usize>| {
+                    indices.iter().flatten().map(move |&i| {
+                        let mut position = position.clone();
+                        position.push(i);
+                        position
+                    })
+                })
+                .collect();
+        }
+        let elements = dispatch_all!(self.dtype(), T => {
+            format_elements::<T>(self, &positions, precision)
+        });
+        if shown.is_empty() {
+            return f.write_str(&elements[0]);
+        }
+
+        let mut printer = Printer {
+            shown,
+            width: elements.iter().map(String::len).max().unwrap_or(0),
+            elements: elements.iter(),
+        };
+        let mut out = String::new();
+        printer.write(&mut out, 0);
+        f.write_str(&out)
+    }
+}
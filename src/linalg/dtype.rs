@@ -0,0 +1,347 @@
+use std::fmt::Debug;
+
+/// Element type of a tensor.
+/// Variants are declared from the lowest to the highest promotion rank, so the derived `Ord`
+/// matches the promotion rules used by the binary kernels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DType {
+    Bool,
+    U8,
+    I64,
+    F32,
+    F64,
+}
+
+impl DType {
+    /// Returns true if the dtype is a floating point type (and can therefore carry gradients).
+    pub fn is_floating_point(self) -> bool {
+        matches!(self, DType::F32 | DType::F64)
+    }
+
+    /// Returns the dtype both operands are converted to before a binary operation.
+    /// # Arguments
+    /// * `other` - The dtype of the other operand.
+    /// # Returns
+    /// The widest of the two dtypes.
+    pub fn promote(self, other: DType) -> DType {
+        self.max(other)
+    }
+
+    /// Returns the dtype used when a tensor of this dtype is combined with a `Scalar` constant.
+    /// Floating point tensors keep their dtype, everything else is promoted to the default float.
+    pub fn promote_scalar(self) -> DType {
+        if self.is_floating_point() {
+            self
+        } else {
+            DType::F32
+        }
+    }
+
+    /// Returns the size in bytes of a single element.
+    pub fn size_of(self) -> usize {
+        match self {
+            DType::Bool | DType::U8 => 1,
+            DType::I64 | DType::F64 => 8,
+            DType::F32 => 4,
+        }
+    }
+}
+
+/// Typed buffer backing a tensor storage.
+#[doc(hidden)]
+#[derive(Clone)]
+pub enum Buffer {
+    Bool(Vec<bool>),
+    U8(Vec<u8>),
+    I64(Vec<i64>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+impl Buffer {
+    pub(crate) fn dtype(&self) -> DType {
+        match self {
+            Buffer::Bool(_) => DType::Bool,
+            Buffer::U8(_) => DType::U8,
+            Buffer::I64(_) => DType::I64,
+            Buffer::F32(_) => DType::F32,
+            Buffer::F64(_) => DType::F64,
+        }
+    }
+}
+
+pub(crate) mod sealed {
+    use super::Buffer;
+
+    /// Conversions between element vectors and typed buffers, restricted to the supported dtypes.
+    pub trait Sealed: Sized {
+        fn into_buffer(data: Vec<Self>) -> Buffer;
+        fn slice(buffer: &Buffer) -> Option<&[Self]>;
+        fn slice_mut(buffer: &mut Buffer) -> Option<&mut [Self]>;
+    }
+}
+
+/// A type that can be stored in a tensor.
+pub trait Element:
+    sealed::Sealed + Copy + Default + PartialOrd + Debug + Send + Sync + 'static
+{
+    const DTYPE: DType;
+
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+/// An element type supporting arithmetic. Integer operations wrap on overflow, and integer
+/// division by zero panics, so tensor operations reject zero divisors before dividing.
+pub trait Numeric: Element {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn div(self, other: Self) -> Self;
+    fn neg(self) -> Self;
+}
+
+/// A floating point element type.
+pub trait Float: Numeric {
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, exponent: Self) -> Self;
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn neg_infinity() -> Self;
+}
+
+macro_rules! impl_element {
+    ($t:ty, $variant:ident) => {
+        impl Element for $t {
+            const DTYPE: DType = DType::$variant;
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+        }
+
+        impl sealed::Sealed for $t {
+            fn into_buffer(data: Vec<Self>) -> Buffer {
+                Buffer::$variant(data)
+            }
+            fn slice(buffer: &Buffer) -> Option<&[Self]> {
+                match buffer {
+                    Buffer::$variant(v) => Some(v),
+                    _ => None,
+                }
+            }
+            fn slice_mut(buffer: &mut Buffer) -> Option<&mut [Self]> {
+                match buffer {
+                    Buffer::$variant(v) => Some(v),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_element!(u8, U8);
+impl_element!(i64, I64);
+impl_element!(f32, F32);
+impl_element!(f64, F64);
+
+impl Element for bool {
+    const DTYPE: DType = DType::Bool;
+
+    fn to_f64(self) -> f64 {
+        if self { 1.0 } else { 0.0 }
+    }
+    fn from_f64(value: f64) -> Self {
+        value != 0.0
+    }
+}
+
+impl sealed::Sealed for bool {
+    fn into_buffer(data: Vec<Self>) -> Buffer {
+        Buffer::Bool(data)
+    }
+    fn slice(buffer: &Buffer) -> Option<&[Self]> {
+        match buffer {
+            Buffer::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+    fn slice_mut(buffer: &mut Buffer) -> Option<&mut [Self]> {
+        match buffer {
+            Buffer::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_numeric_int {
+    ($t:ty) => {
+        impl Numeric for $t {
+            fn zero() -> Self {
+                0
+            }
+            fn one() -> Self {
+                1
+            }
+            fn add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+            fn sub(self, other: Self) -> Self {
+                self.wrapping_sub(other)
+            }
+            fn mul(self, other: Self) -> Self {
+                self.wrapping_mul(other)
+            }
+            fn div(self, other: Self) -> Self {
+                self.wrapping_div(other)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+    };
+}
+
+impl_numeric_int!(u8);
+impl_numeric_int!(i64);
+
+macro_rules! impl_float {
+    ($t:ty) => {
+        impl Numeric for $t {
+            fn zero() -> Self {
+                0.0
+            }
+            fn one() -> Self {
+                1.0
+            }
+            fn add(self, other: Self) -> Self {
+                self + other
+            }
+            fn sub(self, other: Self) -> Self {
+                self - other
+            }
+            fn mul(self, other: Self) -> Self {
+                self * other
+            }
+            fn div(self, other: Self) -> Self {
+                self / other
+            }
+            fn neg(self) -> Self {
+                -self
+            }
+        }
+
+        impl Float for $t {
+            fn exp(self) -> Self {
+                <$t>::exp(self)
+            }
+            fn ln(self) -> Self {
+                <$t>::ln(self)
+            }
+            fn powf(self, exponent: Self) -> Self {
+                <$t>::powf(self, exponent)
+            }
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+            fn signum(self) -> Self {
+                if self == 0.0 { 0.0 } else { <$t>::signum(self) }
+            }
+            fn max(self, other: Self) -> Self {
+                <$t>::max(self, other)
+            }
+            fn min(self, other: Self) -> Self {
+                <$t>::min(self, other)
+            }
+            fn neg_infinity() -> Self {
+                <$t>::NEG_INFINITY
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// Expands `$body` once per floating point dtype with `$T` bound to the matching Rust type.
+/// Panics for non floating point dtypes.
+#[macro_export]
+macro_rules! dispatch_float {
+    ($dtype:expr, $T:ident => $body:expr) => {
+        match $dtype {
+            $crate::linalg::dtype::DType::F32 => {
+                type $T = f32;
+                $body
+            }
+            $crate::linalg::dtype::DType::F64 => {
+                type $T = f64;
+                $body
+            }
+            dtype => panic!("Operation requires a floating point tensor, got {dtype:?}"),
+        }
+    };
+}
+
+/// Expands `$body` once per numeric dtype with `$T` bound to the matching Rust type.
+/// Panics for boolean tensors.
+#[macro_export]
+macro_rules! dispatch_numeric {
+    ($dtype:expr, $T:ident => $body:expr) => {
+        match $dtype {
+            $crate::linalg::dtype::DType::U8 => {
+                type $T = u8;
+                $body
+            }
+            $crate::linalg::dtype::DType::I64 => {
+                type $T = i64;
+                $body
+            }
+            $crate::linalg::dtype::DType::F32 => {
+                type $T = f32;
+                $body
+            }
+            $crate::linalg::dtype::DType::F64 => {
+                type $T = f64;
+                $body
+            }
+            dtype => panic!("Operation requires a numeric tensor, got {dtype:?}"),
+        }
+    };
+}
+
+/// Expands `$body` once per dtype with `$T` bound to the matching Rust type.
+#[macro_export]
+macro_rules! dispatch_all {
+    ($dtype:expr, $T:ident => $body:expr) => {
+        match $dtype {
+            $crate::linalg::dtype::DType::Bool => {
+                type $T = bool;
+                $body
+            }
+            $crate::linalg::dtype::DType::U8 => {
+                type $T = u8;
+                $body
+            }
+            $crate::linalg::dtype::DType::I64 => {
+                type $T = i64;
+                $body
+            }
+            $crate::linalg::dtype::DType::F32 => {
+                type $T = f32;
+                $body
+            }
+            $crate::linalg::dtype::DType::F64 => {
+                type $T = f64;
+                $body
+            }
+        }
+    };
+}
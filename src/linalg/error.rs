@@ -0,0 +1,110 @@
+use crate::linalg::dtype::DType;
+use std::fmt::{Display, Formatter};
+
+/// Errors returned by the fallible (`try_*`) tensor operations.
+#[derive(Debug)]
+pub enum TensorError {
+    /// The shapes of the operands are incompatible for the operation.
+    ShapeMismatch {
+        op: &'static str,
+        lhs: Vec<usize>,
+        rhs: Vec<usize>,
+    },
+    /// The operand does not have the number of dimensions expected by the operation.
+    Rank {
+        op: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// An index (or axis) is outside of the valid range.
+    IndexOutOfBounds {
+        op: &'static str,
+        index: usize,
+        bound: usize,
+    },
+    /// The dtype of the operand is not supported by the operation.
+    DType { op: &'static str, dtype: DType },
+    /// Any other invalid argument.
+    InvalidArgument(String),
+    /// An I/O error occurred while reading or writing a model.
+    Io(std::io::Error),
+    /// A file does not follow the expected format.
+    Format(String),
+}
+
+pub type Result<T> = std::result::Result<T, TensorError>;
+
+impl Display for TensorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TensorError::ShapeMismatch { op, lhs, rhs } => {
+                write!(f, "{op}: shape mismatch: {lhs:?} vs {rhs:?}")
+            }
+            TensorError::Rank {
+                op,
+                expected,
+                actual,
+            } => write!(f, "{op}: expected a {expected}D tensor, got {actual}D"),
+            TensorError::IndexOutOfBounds { op, index, bound } => {
+                write!(f, "{op}: index {index} out of bounds for size {bound}")
+            }
+            TensorError::DType { op, dtype } => write!(f, "{op}: unsupported dtype {dtype:?}"),
+            TensorError::InvalidArgument(message) => write!(f, "{message}"),
+            TensorError::Io(error) => write!(f, "I/O error: {error}"),
+            TensorError::Format(message) => write!(f, "Invalid format: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TensorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TensorError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TensorError {
+    fn from(error: std::io::Error) -> Self {
+        TensorError::Io(error)
+    }
+}
+
+/// Unwraps the result of a fallible operation, panicking with the error message.
+/// Used by the panicking operations, which are thin wrappers around their `try_*` variant.
+pub(crate) trait OrPanic<T> {
+    fn or_panic(self) -> T;
+}
+
+impl<T> OrPanic<T> for Result<T> {
+    #[track_caller]
+    fn or_panic(self) -> T {
+        match self {
+            Ok(value) => value,
+            Err(error) => panic!("{error}"),
+        }
+    }
+}
+
+/// Checks that `index` is strictly lower than `bound`.
+pub(crate) fn check_index(op: &'static str, index: usize, bound: usize) -> Result<()> {
+    if index < bound {
+        Ok(())
+    } else {
+        Err(TensorError::IndexOutOfBounds { op, index, bound })
+    }
+}
+
+/// Checks that `dtype` satisfies `predicate`.
+pub(crate) fn check_dtype(
+    op: &'static str,
+    dtype: DType,
+    predicate: impl Fn(DType) -> bool,
+) -> Result<()> {
+    if predicate(dtype) {
+        Ok(())
+    } else {
+        Err(TensorError::DType { op, dtype })
+    }
+}
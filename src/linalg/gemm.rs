@@ -0,0 +1,192 @@
+use crate::linalg::dtype::Numeric;
+
+/// Rows of the register block computed by one call of the micro-kernel.
+const MR: usize = 4;
+/// Columns of the register block computed by one call of the micro-kernel.
+const NR: usize = 8;
+/// Rows of `a` packed at once, sized so that a packed block of `a` stays in the L2 cache.
+const MC: usize = 64;
+/// Depth of the packed blocks, sized so that a micro-panel of `b` stays in the L1 cache.
+const KC: usize = 256;
+/// Columns of `b` packed at once, sized so that a packed block of `b` stays in the L3 cache.
+const NC: usize = 512;
+
+/// A strided matrix stored in a buffer, read without copying.
+#[derive(Clone, Copy)]
+pub(crate) struct MatRef<'a, T> {
+    pub(crate) data: &'a [T],
+    pub(crate) offset: usize,
+    pub(crate) row_stride: usize,
+    pub(crate) col_stride: usize,
+}
+
+impl<T: Copy> MatRef<'_, T> {
+    fn at(&self, row: usize, col: usize) -> T {
+        self.data[self.offset + row * self.row_stride + col * self.col_stride]
+    }
+}
+
+/// Element types supported by [`gemm`]. Types with a SIMD micro-kernel override
+/// [`Gemm::micro_kernel`], the others use the portable scalar one.
+pub(crate) trait Gemm: Numeric {
+    /// Accumulates the product of a packed `MR x kc` micro-panel of `a` and a packed `kc x NR`
+    /// micro-panel of `b` into the row-major `MR x NR` register block `acc`.
+    fn micro_kernel(kc: usize, a: &[Self], b: &[Self], acc: &mut [Self; MR * NR]) {
+        scalar_micro_kernel(kc, a, b, acc);
+    }
+}
+
+impl Gemm for u8 {}
+impl Gemm for i64 {}
+impl Gemm for f64 {}
+
+impl Gemm for f32 {
+    fn micro_kernel(kc: usize, a: &[f32], b: &[f32], acc: &mut [f32; MR * NR]) {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx") && is_x86_feature_detected!("fma") {
+            // SAFETY: the CPU supports the features the kernel is compiled for
+            unsafe { avx::micro_kernel_f32(kc, a, b, acc) };
+            return;
+        }
+        scalar_micro_kernel(kc, a, b, acc);
+    }
+}
+
+fn scalar_micro_kernel<T: Numeric>(kc: usize, a: &[T], b: &[T], acc: &mut [T; MR * NR]) {
+    for (a, b) in a.chunks_exact(MR).zip(b.chunks_exact(NR)).take(kc) {
+        for (r, &a) in a.iter().enumerate() {
+            for (c, &b) in b.iter().enumerate() {
+                acc[r * NR + c] = acc[r * NR + c].add(a.mul(b));
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    use super::{MR, NR};
+    use std::arch::x86_64::*;
+
+    /// AVX/FMA version of the micro-kernel, holding one 8-lane register per row of the block.
+    #[target_feature(enable = "avx,fma")]
+    pub(super) unsafe fn micro_kernel_f32(
+        kc: usize,
+        a: &[f32],
+        b: &[f32],
+        acc: &mut [f32; MR * NR],
+    ) {
+        assert!(a.len() >= kc * MR && b.len() >= kc * NR);
+        let mut rows = [_mm256_setzero_ps(); MR];
+        for p in 0..kc {
+            // SAFETY: `p * NR + NR <= b.len()` and `p * MR + r < a.len()` by the assertion above
+            unsafe {
+                let b = _mm256_loadu_ps(b.as_ptr().add(p * NR));
+                for (r, row) in rows.iter_mut().enumerate() {
+                    let a = _mm256_set1_ps(*a.get_unchecked(p * MR + r));
+                    *row = _mm256_fmadd_ps(a, b, *row);
+                }
+            }
+        }
+        for (r, row) in rows.iter().enumerate() {
+            let mut block = [0.0f32; NR];
+            // SAFETY: `block` holds exactly one register
+            unsafe { _mm256_storeu_ps(block.as_mut_ptr(), *row) };
+            for (acc, value) in acc[r * NR..(r + 1) * NR].iter_mut().zip(block) {
+                *acc += value;
+            }
+        }
+    }
+}
+
+/// Accumulates the product of the `m x k` matrix `a` and the `k x n` matrix `b` into the
+/// row-major `m x n` buffer `out`.
+///
+/// Both operands are copied block by block into packed buffers: `a` as micro-panels of `MR` rows
+/// and `b` transposed into micro-panels of `NR` columns, each laid out in the order the
+/// micro-kernel reads them. This makes the inner loop read contiguous memory whatever the strides
+/// of the operands, so transposed views cost nothing more than contiguous tensors.
+pub(crate) fn gemm<T: Gemm>(
+    m: usize,
+    n: usize,
+    k: usize,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    out: &mut [T],
+) {
+    let kc_max = KC.min(k);
+    let mut packed_a = vec![T::zero(); MC.min(m.next_multiple_of(MR)) * kc_max];
+    let mut packed_b = vec![T::zero(); NC.min(n.next_multiple_of(NR)) * kc_max];
+
+    for jc in (0..n).step_by(NC) {
+        let nc = NC.min(n - jc);
+        for pc in (0..k).step_by(KC) {
+            let kc = KC.min(k - pc);
+            pack_b(b, pc, jc, kc, nc, &mut packed_b);
+            for ic in (0..m).step_by(MC) {
+                let mc = MC.min(m - ic);
+                pack_a(a, ic, pc, mc, kc, &mut packed_a);
+                for jr in (0..nc).step_by(NR) {
+                    for ir in (0..mc).step_by(MR) {
+                        let mut acc = [T::zero(); MR * NR];
+                        T::micro_kernel(kc, &packed_a[ir * kc..], &packed_b[jr * kc..], &mut acc);
+                        // Only the part of the block inside the matrix is written back
+                        for r in 0..MR.min(mc - ir) {
+                            let row = (ic + ir + r) * n + jc + jr;
+                            for c in 0..NR.min(nc - jr) {
+                                out[row + c] = out[row + c].add(acc[r * NR + c]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Packs the `mc x kc` block of `a` starting at `(row, depth)` into micro-panels of `MR` rows,
+/// padding the last one with zeros.
+fn pack_a<T: Numeric>(
+    a: MatRef<T>,
+    row: usize,
+    depth: usize,
+    mc: usize,
+    kc: usize,
+    packed: &mut [T],
+) {
+    for (panel, ir) in (0..mc).step_by(MR).enumerate() {
+        let panel = &mut packed[panel * MR * kc..(panel + 1) * MR * kc];
+        for p in 0..kc {
+            for r in 0..MR {
+                panel[p * MR + r] = if ir + r < mc {
+                    a.at(row + ir + r, depth + p)
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+/// Packs the `kc x nc` block of `b` starting at `(depth, col)` into micro-panels of `NR` columns,
+/// padding the last one with zeros.
+fn pack_b<T: Numeric>(
+    b: MatRef<T>,
+    depth: usize,
+    col: usize,
+    kc: usize,
+    nc: usize,
+    packed: &mut [T],
+) {
+    for (panel, jr) in (0..nc).step_by(NR).enumerate() {
+        let panel = &mut packed[panel * NR * kc..(panel + 1) * NR * kc];
+        for p in 0..kc {
+            for c in 0..NR {
+                panel[p * NR + c] = if jr + c < nc {
+                    b.at(depth + p, col + jr + c)
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
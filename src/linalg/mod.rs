@@ -1,3 +1,10 @@
-mod autograd;
+pub mod autograd;
+pub mod display;
+pub mod dtype;
+pub mod error;
+mod gemm;
 pub mod ops;
+pub mod parallel;
+pub mod random;
+mod strided;
 pub mod tensor;
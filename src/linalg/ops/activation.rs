@@ -1,114 +1,104 @@
 use crate::linalg::autograd::grad_fn::activation::{ReLUGradFn, SigmoidGradFn};
-use crate::linalg::tensor::{InternalTensor, Scalar, Storage, Tensor};
-use crate::not_implemented_grad_fn;
-use std::cell::RefCell;
-use std::rc::Rc;
+use crate::linalg::dtype::Numeric;
+use crate::linalg::error::{OrPanic, Result};
+use crate::linalg::tensor::{InternalTensor, Storage, Tensor};
+use crate::{dispatch_float, dispatch_numeric};
+use std::sync::Arc;
+use std::sync::Mutex;
 
 impl Tensor {
     /// Computes the sigmoid of the tensor
     /// # Returns
     /// A tensor containing the sigmoid values
     pub fn sigmoid(&self) -> Tensor {
-        let mut result_data = Vec::with_capacity(self.shape.iter().product());
-        for i in 0..self.storage.data.len() {
-            let val = self.storage.data[i];
-            result_data.push(1.0 / (1.0 + (-val).exp()));
-        }
+        let input = self.to_float();
+        let storage = dispatch_float!(input.dtype(), T => {
+            Storage::new(input.map_data::<T, T>(|val| T::one().div(T::one().add(val.neg().exp()))))
+        });
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = input.records_grad();
 
         let mut out: Tensor = InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            storage: Arc::new(storage),
+            shape: input.shape.clone(),
+            strides: Tensor::compute_strides(&input.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: None,
             parents: Vec::new(),
             requires_grad,
         }
         .into();
         if requires_grad {
-            out.set_grad_metadata(Rc::new(SigmoidGradFn::new(out.clone())), vec![self.clone()]);
+            let output = out.detached();
+            out.set_grad_metadata(
+                Arc::new(SigmoidGradFn::new(input.clone(), output)),
+                vec![input.clone()],
+            );
         }
 
         out
     }
 
-    /// Computes the softmax of the tensor
+    /// Computes the softmax of the tensor along `axis`, so that every slice along `axis` sums to one
+    /// # Arguments
+    /// * `axis` - The axis to normalize over, typically the class axis
     /// # Returns
-    /// A tensor containing the softmax values
-    pub fn softmax(&self) -> Tensor {
-        let max = self.max();
-        let exp_data = (self - &max).exp();
-        let sum_exp = exp_data.sum();
-        &exp_data / &sum_exp
+    /// A tensor of the same shape containing the softmax values
+    pub fn softmax(&self, axis: usize) -> Tensor {
+        self.try_softmax(axis).or_panic()
     }
 
-    /// Computes the log-softmax of the tensor
-    /// # Returns
-    /// A tensor containing the log-softmax values
-    pub fn log_softmax(&self) -> Tensor {
-        let max_val = self
-            .storage
-            .data
-            .iter()
-            .cloned()
-            .fold(Scalar::NEG_INFINITY, Scalar::max);
-        let mut exp_sum = 0.0;
-        for i in 0..self.storage.data.len() {
-            exp_sum += (self.storage.data[i] - max_val).exp();
-        }
-        let log_exp_sum = exp_sum.ln() + max_val;
-
-        let mut result_data = Vec::with_capacity(self.shape.iter().product());
-        for index in 0..self.storage.data.len() {
-            result_data.push(self.storage.data[index] - log_exp_sum);
-        }
+    /// Computes the softmax of the tensor along `axis`.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_softmax(&self, axis: usize) -> Result<Tensor> {
+        // Exponentiating the log-softmax keeps large inputs from overflowing
+        Ok(self.try_log_softmax(axis)?.exp())
+    }
 
-        let requires_grad = self.requires_grad;
+    /// Computes the log-softmax of the tensor along `axis`
+    /// # Arguments
+    /// * `axis` - The axis to normalize over, typically the class axis
+    /// # Returns
+    /// A tensor of the same shape containing the log-softmax values
+    pub fn log_softmax(&self, axis: usize) -> Tensor {
+        self.try_log_softmax(axis).or_panic()
+    }
 
-        InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: self.shape.clone(),
-            strides: self.strides.clone(),
-            offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: not_implemented_grad_fn!("LogSoftmax"),
-            parents: if requires_grad {
-                vec![self.clone()]
-            } else {
-                Vec::new()
-            },
-            requires_grad,
-        }
-        .into()
+    /// Computes the log-softmax of the tensor along `axis`.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_log_softmax(&self, axis: usize) -> Result<Tensor> {
+        let input = self.to_float();
+        let log_sum_exp = input.try_logsumexp(axis, true)?;
+        Ok(&input - &log_sum_exp)
     }
 
     pub fn relu(&self) -> Tensor {
-        let mut result_data = Vec::with_capacity(self.shape.iter().product());
-        let mut mask = Vec::with_capacity(self.shape.iter().product());
-        for i in 0..self.storage.data.len() {
-            let val = self.storage.data[i];
-            if val < 0.0 {
-                result_data.push(0.0);
-                mask.push(0.0);
-            } else {
-                result_data.push(val);
-                mask.push(1.0);
+        let (storage, mask) = dispatch_numeric!(self.dtype(), T => {
+            let mut result_data = Vec::with_capacity(self.numel());
+            let mut mask = Vec::with_capacity(self.numel());
+            for val in self.values::<T>() {
+                if val < T::zero() {
+                    result_data.push(T::zero());
+                    mask.push(T::zero());
+                } else {
+                    result_data.push(val);
+                    mask.push(T::one());
+                }
             }
-        }
+            (Storage::new(result_data), Tensor::from_vec(mask, self.shape()))
+        });
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = self.records_grad();
 
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
+            storage: Arc::new(storage),
             shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            strides: Tensor::compute_strides(&self.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: if requires_grad {
-                Some(Rc::new(ReLUGradFn::new(Tensor::new(mask, self.shape()))))
+                Some(Arc::new(ReLUGradFn::new(mask)))
             } else {
                 None
             },
@@ -1,3 +1,5 @@
 mod broadcast;
+mod compare;
+mod condition;
 mod kernels;
 mod traits;
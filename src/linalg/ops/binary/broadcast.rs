@@ -1,82 +1,143 @@
-use crate::linalg::autograd::grad_fn::binary::AddGradFn;
+use crate::dispatch_all;
+use crate::linalg::autograd::grad_fn::binary::BroadcastToGradFn;
+use crate::linalg::error::{OrPanic, Result, TensorError};
+use crate::linalg::ops::binary::kernels;
+use crate::linalg::strided::StridedIter;
 use crate::linalg::tensor::{InternalTensor, Storage, Tensor};
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
 
 impl Tensor {
+    /// Computes the shape resulting from broadcasting `lhs` and `rhs` together.
+    /// Shapes are aligned on their trailing dimensions, and each pair of dimensions must either
+    /// be equal or contain a one.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// assert_eq!(Tensor::broadcast_shape(&[2, 1, 3], &[4, 1]).unwrap(), vec![2, 4, 3]);
+    /// assert!(Tensor::broadcast_shape(&[2, 3], &[2]).is_err());
+    /// ```
+    pub fn broadcast_shape(lhs: &[usize], rhs: &[usize]) -> Result<Vec<usize>> {
+        let rank = lhs.len().max(rhs.len());
+        let dim = |shape: &[usize], d: usize| {
+            (d + shape.len())
+                .checked_sub(rank)
+                .map_or(1, |index| shape[index])
+        };
+        (0..rank)
+            .map(|d| match (dim(lhs, d), dim(rhs, d)) {
+                (l, r) if l == r || r == 1 => Ok(l),
+                (1, r) => Ok(r),
+                _ => Err(TensorError::ShapeMismatch {
+                    op: "broadcast",
+                    lhs: lhs.to_vec(),
+                    rhs: rhs.to_vec(),
+                }),
+            })
+            .collect()
+    }
+
+    /// Returns the strides to read this tensor as if it had the (broadcast) shape `shape`.
+    /// Broadcast dimensions get a zero stride, so the same element is read repeatedly.
+    pub(crate) fn broadcast_strides(&self, shape: &[usize]) -> Vec<usize> {
+        let padding = shape.len() - self.shape.len();
+        let mut strides = vec![0; shape.len()];
+        for (d, (&dim, &stride)) in self.shape.iter().zip(&self.strides).enumerate() {
+            if dim != 1 {
+                strides[padding + d] = stride;
+            }
+        }
+        strides
+    }
+
     /// Broadcast addition of a tensor along last dimensions
     /// # Arguments
     /// * `other` - The tensor to add, must be broadcastable to self
     /// # Returns
     /// A new tensor containing the result of the broadcast addition
     pub fn broadcast_add(&self, other: &Tensor) -> Tensor {
-        let self_shape = self.shape();
-        let self_strides = &self.strides;
-        let self_rank = self_shape.len();
-
-        let other_shape = other.shape();
-        let other_rank = other_shape.len();
-
-        assert!(self_rank >= other_rank);
-
-        // Pad other shape and strides
-        let mut padded_other_shape = vec![1; self_rank - other_rank];
-        padded_other_shape.extend_from_slice(other_shape);
-
-        let mut padded_other_strides = vec![0; self_rank - other_rank];
-        padded_other_strides.extend_from_slice(&other.strides);
+        self.try_broadcast_add(other).or_panic()
+    }
 
-        // Validate broadcast and set stride to 0 for broadcast dimensions
-        for d in 0..self_rank {
-            if padded_other_shape[d] == 1 {
-                padded_other_strides[d] = 0;
-            } else {
-                assert_eq!(
-                    padded_other_shape[d], self_shape[d],
-                    "Broadcast mismatch at dim {d}"
-                );
-            }
-        }
+    /// Broadcast addition of a tensor along last dimensions
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_broadcast_add(&self, other: &Tensor) -> Result<Tensor> {
+        kernels::try_add_tt(self, other)
+    }
 
-        let a = &self.storage.data;
-        let b = &other.storage.data;
-        let n = a.len();
-        let mut out = vec![0.0; n];
+    /// Materializes the tensor broadcast to the given shape.
+    /// # Arguments
+    /// * `shape` - The target shape, which the shape of the tensor must be broadcastable to
+    /// # Returns
+    /// A new contiguous tensor of shape `shape`
+    pub fn broadcast_to(&self, shape: &[usize]) -> Tensor {
+        self.try_broadcast_to(shape).or_panic()
+    }
 
-        // Pre-compute division factors to avoid repeated division
-        let mut div_factors = vec![1; self_rank];
-        for d in (0..self_rank - 1).rev() {
-            div_factors[d] = div_factors[d + 1] * self_shape[d + 1];
+    /// Materializes the tensor broadcast to the given shape.
+    /// Returns an error if the shape of the tensor is not broadcastable to `shape`.
+    pub fn try_broadcast_to(&self, shape: &[usize]) -> Result<Tensor> {
+        if Tensor::broadcast_shape(&self.shape, shape)? != shape {
+            return Err(TensorError::ShapeMismatch {
+                op: "broadcast_to",
+                lhs: self.shape.clone(),
+                rhs: shape.to_vec(),
+            });
         }
+        let strides = self.broadcast_strides(shape);
 
-        for linear_idx in 0..n {
-            let mut b_offset = 0;
-            for d in 0..self_rank {
-                let coord = (linear_idx / div_factors[d]) % self_shape[d];
-                b_offset += coord * padded_other_strides[d];
-            }
+        let storage = dispatch_all!(self.dtype(), T => {
+            let data = self.data::<T>();
+            let indices = StridedIter::new(shape, &strides, self.offset);
+            Storage::new(indices.map(|index| data[index]).collect::<Vec<T>>())
+        });
 
-            out[linear_idx] = a[linear_idx] + b[b_offset];
-        }
+        let requires_grad = self.records_grad();
 
-        InternalTensor {
-            storage: Rc::new(Storage::new(out)),
-            shape: self_shape.to_vec(),
-            strides: self_strides.clone(),
+        Ok(InternalTensor {
+            storage: Arc::new(storage),
+            shape: shape.to_vec(),
+            strides: Tensor::compute_strides(shape),
             offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: if self.requires_grad || other.requires_grad {
-                Some(Rc::new(AddGradFn::new(vec![self.clone(), other.clone()])))
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(BroadcastToGradFn::new(self.shape.clone())))
             } else {
                 None
             },
-            parents: if self.requires_grad || other.requires_grad {
-                vec![self.clone(), other.clone()]
+            parents: if requires_grad {
+                vec![self.clone()]
             } else {
                 Vec::new()
             },
-            requires_grad: self.requires_grad || other.requires_grad,
+            requires_grad,
+        }
+        .into())
+    }
+
+    /// Sums the tensor to match the specified shape by summing over dimensions where the target shape has size 1.
+    /// This is the reverse of broadcasting: leading dimensions missing from the target shape are summed as well.
+    /// # Arguments
+    /// * `shape` - The target shape to sum to.
+    /// # Returns
+    /// A new tensor summed to the specified shape.
+    pub fn sum_to_shape(&self, shape: &[usize]) -> Tensor {
+        if self.shape == shape {
+            return self.clone();
         }
-        .into()
+        if self.numel() == shape.iter().product::<usize>() {
+            return self.clone().reshape(shape);
+        }
+        if Tensor::broadcast_shape(shape, &self.shape).or_panic() != self.shape {
+            panic!("sum_to_shape: cannot sum {:?} to {:?}", self.shape, shape);
+        }
+
+        // Sum over the dimensions the target was broadcast along, which keeps the reduction
+        // deterministic and parallel like every other one
+        let padding = self.shape.len() - shape.len();
+        let axes = (0..self.shape.len())
+            .filter(|&d| d < padding || (shape[d - padding] == 1 && self.shape[d] != 1))
+            .collect::<Vec<usize>>();
+        self.sum_axes(&axes, true).reshape(shape)
     }
 }
@@ -0,0 +1,160 @@
+use crate::dispatch_all;
+use crate::linalg::dtype::DType;
+use crate::linalg::error::{OrPanic, Result};
+use crate::linalg::ops::binary::kernels::zip_map;
+use crate::linalg::tensor::Tensor;
+use std::cmp::Ordering;
+
+/// Compares `a` and `b` element-wise in their promoted dtype, broadcasting both operands.
+/// `f` receives the ordering of each pair of elements, which is `None` when one of them is NaN.
+fn compare(a: &Tensor, b: &Tensor, f: impl Fn(Option<Ordering>) -> bool + Sync) -> Result<Tensor> {
+    let shape = Tensor::broadcast_shape(a.shape(), b.shape())?;
+    let dtype = a.dtype().promote(b.dtype());
+    let (a, b) = (&a.to_dtype(dtype), &b.to_dtype(dtype));
+    let data = dispatch_all!(dtype, T => {
+        zip_map::<T, bool>(a, b, &shape, |x, y| f(x.partial_cmp(&y)))
+    });
+    Ok(Tensor::from_vec(data, &shape))
+}
+
+/// Combines the truth values of `a` and `b` element-wise, broadcasting both operands.
+fn logical(a: &Tensor, b: &Tensor, f: impl Fn(bool, bool) -> bool + Sync) -> Result<Tensor> {
+    let shape = Tensor::broadcast_shape(a.shape(), b.shape())?;
+    let (a, b) = (&a.to_dtype(DType::Bool), &b.to_dtype(DType::Bool));
+    Ok(Tensor::from_vec(zip_map(a, b, &shape, f), &shape))
+}
+
+impl Tensor {
+    /// Tests the elements of the two tensors for equality, broadcasting them together.
+    /// Both operands are compared in their promoted dtype.
+    /// # Arguments
+    /// * `other` - The tensor to compare with
+    /// # Returns
+    /// A boolean tensor, true where the elements are equal
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let a = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    /// let mask = a.eq(&Tensor::from_scalar(2.0));
+    /// assert_eq!(mask.as_typed_slice::<bool>(), &[false, true, false]);
+    /// ```
+    pub fn eq(&self, other: &Tensor) -> Tensor {
+        self.try_eq(other).or_panic()
+    }
+
+    /// Tests the elements of the two tensors for equality, broadcasting them together.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_eq(&self, other: &Tensor) -> Result<Tensor> {
+        compare(self, other, |ordering| ordering == Some(Ordering::Equal))
+    }
+
+    /// Tests the elements of the two tensors for inequality, broadcasting them together.
+    /// NaN is different from every value, including itself.
+    /// # Returns
+    /// A boolean tensor, true where the elements differ
+    pub fn ne(&self, other: &Tensor) -> Tensor {
+        self.try_ne(other).or_panic()
+    }
+
+    /// Tests the elements of the two tensors for inequality, broadcasting them together.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_ne(&self, other: &Tensor) -> Result<Tensor> {
+        compare(self, other, |ordering| ordering != Some(Ordering::Equal))
+    }
+
+    /// Tests whether the elements of the tensor are less than those of `other`,
+    /// broadcasting them together.
+    /// # Returns
+    /// A boolean tensor, true where `self < other`
+    pub fn lt(&self, other: &Tensor) -> Tensor {
+        self.try_lt(other).or_panic()
+    }
+
+    /// Tests whether the elements of the tensor are less than those of `other`.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_lt(&self, other: &Tensor) -> Result<Tensor> {
+        compare(self, other, |ordering| ordering == Some(Ordering::Less))
+    }
+
+    /// Tests whether the elements of the tensor are less than or equal to those of `other`,
+    /// broadcasting them together.
+    /// # Returns
+    /// A boolean tensor, true where `self <= other`
+    pub fn le(&self, other: &Tensor) -> Tensor {
+        self.try_le(other).or_panic()
+    }
+
+    /// Tests whether the elements of the tensor are less than or equal to those of `other`.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_le(&self, other: &Tensor) -> Result<Tensor> {
+        compare(self, other, |ordering| {
+            matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+        })
+    }
+
+    /// Tests whether the elements of the tensor are greater than those of `other`,
+    /// broadcasting them together.
+    /// # Returns
+    /// A boolean tensor, true where `self > other`
+    pub fn gt(&self, other: &Tensor) -> Tensor {
+        self.try_gt(other).or_panic()
+    }
+
+    /// Tests whether the elements of the tensor are greater than those of `other`.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_gt(&self, other: &Tensor) -> Result<Tensor> {
+        compare(self, other, |ordering| ordering == Some(Ordering::Greater))
+    }
+
+    /// Tests whether the elements of the tensor are greater than or equal to those of `other`,
+    /// broadcasting them together.
+    /// # Returns
+    /// A boolean tensor, true where `self >= other`
+    pub fn ge(&self, other: &Tensor) -> Tensor {
+        self.try_ge(other).or_panic()
+    }
+
+    /// Tests whether the elements of the tensor are greater than or equal to those of `other`.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_ge(&self, other: &Tensor) -> Result<Tensor> {
+        compare(self, other, |ordering| {
+            matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+        })
+    }
+
+    /// Computes the logical AND of the two tensors, broadcasting them together.
+    /// Non-zero elements are true.
+    /// # Returns
+    /// A boolean tensor, true where both elements are true
+    pub fn logical_and(&self, other: &Tensor) -> Tensor {
+        self.try_logical_and(other).or_panic()
+    }
+
+    /// Computes the logical AND of the two tensors, broadcasting them together.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_logical_and(&self, other: &Tensor) -> Result<Tensor> {
+        logical(self, other, |a, b| a && b)
+    }
+
+    /// Computes the logical OR of the two tensors, broadcasting them together.
+    /// Non-zero elements are true.
+    /// # Returns
+    /// A boolean tensor, true where at least one of the elements is true
+    pub fn logical_or(&self, other: &Tensor) -> Tensor {
+        self.try_logical_or(other).or_panic()
+    }
+
+    /// Computes the logical OR of the two tensors, broadcasting them together.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_logical_or(&self, other: &Tensor) -> Result<Tensor> {
+        logical(self, other, |a, b| a || b)
+    }
+
+    /// Computes the logical NOT of the tensor. Non-zero elements are true.
+    /// # Returns
+    /// A boolean tensor, true where the element is zero or false
+    pub fn logical_not(&self) -> Tensor {
+        let input = self.to_dtype(DType::Bool);
+        Tensor::from_vec(input.map_data::<bool, bool>(|x| !x), &self.shape)
+    }
+}
@@ -0,0 +1,133 @@
+use crate::dispatch_all;
+use crate::linalg::autograd::grad_fn::binary::WhereGradFn;
+use crate::linalg::dtype::{DType, Element};
+use crate::linalg::error::{OrPanic, Result, TensorError};
+use crate::linalg::parallel;
+use crate::linalg::strided::StridedIter;
+use crate::linalg::tensor::{InternalTensor, Scalar, Storage, Tensor};
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Picks the element of `a` where `condition` holds and the element of `b` elsewhere, reading
+/// the three operands broadcast to `shape`. `a` and `b` must share the dtype `T`.
+fn select_where<T: Element>(condition: &Tensor, a: &Tensor, b: &Tensor, shape: &[usize]) -> Vec<T> {
+    // A boolean condition may share the storage of the branches, which is locked once for them,
+    // so it is then copied out instead of being locked again
+    let shares_storage = |t: &Tensor| Arc::ptr_eq(&condition.storage, &t.storage);
+    let condition = if shares_storage(a) || shares_storage(b) {
+        Tensor::from_vec(condition.values::<bool>().collect(), condition.shape())
+    } else {
+        condition.clone()
+    };
+    let condition_data = condition.data::<bool>();
+    let indices = |t: &Tensor| StridedIter::new(shape, &t.broadcast_strides(shape), t.offset);
+    let (condition_indices, a_indices, b_indices) = (indices(&condition), indices(a), indices(b));
+
+    let mut result = vec![T::default(); shape.iter().product()];
+    Tensor::with_data::<T, _>(&[a, b], |data| {
+        let (a_data, b_data) = (data[0], data[1]);
+        parallel::fill_with(&mut result, |range| {
+            let condition_indices = condition_indices.clone().starting_at(range.start);
+            let a_indices = a_indices.clone().starting_at(range.start);
+            let b_indices = b_indices.clone().starting_at(range.start);
+            condition_indices
+                .zip(a_indices.zip(b_indices))
+                .take(range.len())
+                .map(|(index, (idx_a, idx_b))| {
+                    if condition_data[index] {
+                        a_data[idx_a]
+                    } else {
+                        b_data[idx_b]
+                    }
+                })
+        });
+    });
+    result
+}
+
+impl Tensor {
+    /// Selects elements from `a` where `condition` is true and from `b` elsewhere, broadcasting the
+    /// three tensors together. Non-zero elements of `condition` are true, and `a` and `b` are
+    /// converted to their promoted dtype. Gradients flow to the branch each element was taken from.
+    /// # Arguments
+    /// * `condition` - The mask choosing between the two tensors
+    /// * `a` - The values used where `condition` is true
+    /// * `b` - The values used where `condition` is false
+    /// # Returns
+    /// A new tensor of the broadcast shape
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let x = Tensor::new(vec![-1.0, 2.0, -3.0], &[3]);
+    /// let zero = Tensor::from_scalar(0.0);
+    /// let relu = Tensor::where_(&x.gt(&zero), &x, &zero);
+    /// assert_eq!(relu.as_slice(), &[0.0, 2.0, 0.0]);
+    /// ```
+    pub fn where_(condition: &Tensor, a: &Tensor, b: &Tensor) -> Tensor {
+        Tensor::try_where_(condition, a, b).or_panic()
+    }
+
+    /// Selects elements from `a` where `condition` is true and from `b` elsewhere.
+    /// Returns an error if the three shapes cannot be broadcast together.
+    pub fn try_where_(condition: &Tensor, a: &Tensor, b: &Tensor) -> Result<Tensor> {
+        let shape = Tensor::broadcast_shape(a.shape(), b.shape())?;
+        let shape = Tensor::broadcast_shape(condition.shape(), &shape)?;
+        let dtype = a.dtype().promote(b.dtype());
+        let (a, b) = (&a.to_dtype(dtype), &b.to_dtype(dtype));
+        let condition = &condition.to_dtype(DType::Bool);
+        let storage = dispatch_all!(dtype, T => {
+            Storage::new(select_where::<T>(condition, a, b, &shape))
+        });
+
+        let requires_grad = a.records_grad() || b.records_grad();
+
+        Ok(InternalTensor {
+            storage: Arc::new(storage),
+            shape: shape.clone(),
+            strides: Tensor::compute_strides(&shape),
+            offset: 0,
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(WhereGradFn::new(
+                    condition.clone(),
+                    a.requires_grad.then(|| a.shape().to_vec()),
+                    b.requires_grad.then(|| b.shape().to_vec()),
+                )))
+            } else {
+                None
+            },
+            parents: if requires_grad {
+                vec![a.clone(), b.clone()]
+            } else {
+                Vec::new()
+            },
+            requires_grad,
+        }
+        .into())
+    }
+
+    /// Replaces the elements of the tensor where `mask` is true with `value`.
+    /// The gradient is zero for the replaced elements.
+    /// # Arguments
+    /// * `mask` - A tensor broadcastable to the shape of the tensor; non-zero elements are true
+    /// * `value` - The value written where `mask` is true
+    /// # Returns
+    /// A new tensor of the same shape and dtype
+    pub fn masked_fill(&self, mask: &Tensor, value: Scalar) -> Tensor {
+        self.try_masked_fill(mask, value).or_panic()
+    }
+
+    /// Replaces the elements of the tensor where `mask` is true with `value`.
+    /// Returns an error if `mask` cannot be broadcast to the shape of the tensor.
+    pub fn try_masked_fill(&self, mask: &Tensor, value: Scalar) -> Result<Tensor> {
+        if Tensor::broadcast_shape(mask.shape(), &self.shape)? != self.shape {
+            return Err(TensorError::ShapeMismatch {
+                op: "masked_fill",
+                lhs: self.shape.clone(),
+                rhs: mask.shape().to_vec(),
+            });
+        }
+        let value = Tensor::from_scalar(value).to_dtype(self.dtype());
+        Tensor::try_where_(mask, &value, self)
+    }
+}
@@ -1,57 +1,81 @@
+use crate::dispatch_float;
+use crate::dispatch_numeric;
 use crate::linalg::autograd::grad_fn::binary::{AddGradFn, DivGradFn, EWSMultGradFn, SubGradFn};
+use crate::linalg::dtype::{DType, Element, Numeric};
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype};
+use crate::linalg::parallel;
+use crate::linalg::strided::StridedIter;
 use crate::linalg::tensor::{InternalTensor, Scalar, Storage, Tensor};
-use std::cell::RefCell;
-use std::rc::Rc;
-
-pub fn add_tt(a: &Tensor, b: &Tensor) -> Tensor {
-    let mut out = None;
-    if a.is_scalar() {
-        out = Some(add_ts(b, a.storage.data[0]));
-    }
-    if b.is_scalar() {
-        out = Some(add_ts(a, b.storage.data[0]));
-    }
-    if let Some(mut out) = out {
-        if a.requires_grad || b.requires_grad {
-            out.set_grad_metadata(
-                Rc::new(AddGradFn::new(vec![a.clone(), b.clone()])),
-                vec![a.clone(), b.clone()],
-            )
-        }
-        return out;
-    }
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Converts both operands to their promoted dtype, which must support arithmetic.
+fn promote(a: &Tensor, b: &Tensor, op: &'static str) -> Result<(Tensor, Tensor)> {
+    let dtype = a.dtype().promote(b.dtype());
+    check_dtype(op, dtype, |dtype| dtype != DType::Bool)?;
+    Ok((a.to_dtype(dtype), b.to_dtype(dtype)))
+}
 
-    assert_eq!(
-        a.shape(),
-        b.shape(),
-        "Shape mismatch: {:?} vs {:?}. Broadcasting not yet implemented.",
-        a.shape(),
-        b.shape()
-    );
+/// Applies `f` element-wise to `a` and `b`, which must share the dtype `T`, broadcasting both
+/// operands to `shape`. The result is laid out contiguously.
+pub(super) fn zip_map<T: Element, U: Element>(
+    a: &Tensor,
+    b: &Tensor,
+    shape: &[usize],
+    f: impl Fn(T, T) -> U + Sync,
+) -> Vec<U> {
+    let a_indices = StridedIter::new(shape, &a.broadcast_strides(shape), a.offset);
+    let b_indices = StridedIter::new(shape, &b.broadcast_strides(shape), b.offset);
+
+    let mut result = vec![U::default(); shape.iter().product()];
+    Tensor::with_data::<T, _>(&[a, b], |data| {
+        let (a_data, b_data) = (data[0], data[1]);
+        parallel::fill_with(&mut result, |range| {
+            let a_indices = a_indices.clone().starting_at(range.start);
+            let b_indices = b_indices.clone().starting_at(range.start);
+            a_indices
+                .zip(b_indices)
+                .take(range.len())
+                .map(|(idx_a, idx_b)| f(a_data[idx_a], b_data[idx_b]))
+        });
+    });
+    result
+}
 
-    let total_elements: usize = a.shape().iter().product();
-    let mut result_data = Vec::with_capacity(total_elements);
+/// Applies `f` to every element of `a`, which must have the dtype `T`.
+fn map_scalar<T: Numeric>(a: &Tensor, f: impl Fn(T) -> T + Sync) -> Vec<T> {
+    a.map_data(f)
+}
 
-    let mut indices = vec![0; a.shape().len()];
-    for _ in 0..total_elements {
-        let idx_a = a.compute_flat_index(&indices);
-        let idx_b = b.compute_flat_index(&indices);
+/// Base of the output of an element-wise operation, contiguous with the shape of `a`.
+fn output(storage: Storage, a: &Tensor) -> InternalTensor {
+    InternalTensor {
+        storage: Arc::new(storage),
+        shape: a.shape.clone(),
+        strides: Tensor::compute_strides(&a.shape),
+        offset: 0,
+        grad: Mutex::new(None),
+        grad_fn: None,
+        parents: Vec::new(),
+        requires_grad: false,
+    }
+}
 
-        result_data.push(a.storage.data[idx_a] + b.storage.data[idx_b]);
+pub fn add_tt(a: &Tensor, b: &Tensor) -> Tensor {
+    try_add_tt(a, b).or_panic()
+}
 
-        Tensor::increment_indices(&mut indices, a.shape());
-    }
+pub fn try_add_tt(a: &Tensor, b: &Tensor) -> Result<Tensor> {
+    let (a, b) = &promote(a, b, "element-wise addition")?;
+    let shape = Tensor::broadcast_shape(a.shape(), b.shape())?;
+    let storage =
+        dispatch_numeric!(a.dtype(), T => Storage::new(zip_map::<T, T>(a, b, &shape, T::add)));
 
-    let requires_grad = a.requires_grad || b.requires_grad;
+    let requires_grad = a.records_grad() || b.records_grad();
 
-    InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().to_vec(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
+    Ok(InternalTensor {
         grad_fn: if requires_grad {
-            Some(Rc::new(AddGradFn::new(vec![a.clone(), b.clone()])))
+            Some(Arc::new(AddGradFn::new(vec![a.clone(), b.clone()])))
         } else {
             None
         },
@@ -61,24 +85,23 @@ pub fn add_tt(a: &Tensor, b: &Tensor) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        shape: shape.to_vec(),
+        strides: Tensor::compute_strides(&shape),
+        ..output(storage, a)
     }
-    .into()
+    .into())
 }
 
 pub fn add_ts(a: &Tensor, b: Scalar) -> Tensor {
-    let mut result_data = Vec::with_capacity(a.shape().iter().product());
-    for i in 0..a.storage.data.len() {
-        result_data.push(a.storage.data[i] + b);
-    }
-    let requires_grad = a.requires_grad;
+    let a = &a.to_dtype(a.dtype().promote_scalar());
+    let storage = dispatch_float!(a.dtype(), T => {
+        let b = T::from_f64(b as f64);
+        Storage::new(map_scalar::<T>(a, |x| x.add(b)))
+    });
+    let requires_grad = a.records_grad();
     InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().to_vec(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
         grad_fn: if requires_grad {
-            Some(Rc::new(AddGradFn::new(vec![a.clone()])))
+            Some(Arc::new(AddGradFn::new(vec![a.clone()])))
         } else {
             None
         },
@@ -88,52 +111,28 @@ pub fn add_ts(a: &Tensor, b: Scalar) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        ..output(storage, a)
     }
     .into()
 }
-pub fn sub_tt(a: &Tensor, b: &Tensor) -> Tensor {
-    let mut out = None;
-    if a.is_scalar() {
-        out = Some(sub_st(a.storage.data[0], b));
-    }
-    if b.is_scalar() {
-        out = Some(sub_ts(a, b.storage.data[0]));
-    }
 
-    if let Some(mut out) = out {
-        if a.requires_grad || b.requires_grad {
-            out.set_grad_metadata(
-                Rc::new(SubGradFn::new(true, true, vec![a.clone(), b.clone()])),
-                vec![a.clone(), b.clone()],
-            )
-        }
-        return out;
-    }
+pub fn sub_tt(a: &Tensor, b: &Tensor) -> Tensor {
+    try_sub_tt(a, b).or_panic()
+}
 
-    assert_eq!(
-        Tensor::reduce_shape(a.shape()),
-        Tensor::reduce_shape(b.shape()),
-        "Shapes must match for subtraction ({:?} != {:?})",
-        a.shape(),
-        b.shape()
-    );
-    let mut result_data = Vec::with_capacity(a.shape().iter().product());
-    for i in 0..a.storage.data.len() {
-        result_data.push(a.storage.data[i] - b.storage.data[i]);
-    }
+pub fn try_sub_tt(a: &Tensor, b: &Tensor) -> Result<Tensor> {
+    let (a, b) = &promote(a, b, "element-wise subtraction")?;
+    let shape = Tensor::broadcast_shape(a.shape(), b.shape())?;
+    let storage =
+        dispatch_numeric!(a.dtype(), T => Storage::new(zip_map::<T, T>(a, b, &shape, T::sub)));
 
-    let requires_grad = a.requires_grad || b.requires_grad;
+    let requires_grad = a.records_grad() || b.records_grad();
 
-    InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().to_vec(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
+    Ok(InternalTensor {
         grad_fn: if requires_grad {
-            Some(Rc::new(SubGradFn::new(
-                true,
-                true,
+            Some(Arc::new(SubGradFn::new(
+                a.requires_grad,
+                b.requires_grad,
                 vec![a.clone(), b.clone()],
             )))
         } else {
@@ -145,26 +144,24 @@ pub fn sub_tt(a: &Tensor, b: &Tensor) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        shape: shape.to_vec(),
+        strides: Tensor::compute_strides(&shape),
+        ..output(storage, a)
     }
-    .into()
+    .into())
 }
+
 pub fn sub_ts(a: &Tensor, b: Scalar) -> Tensor {
-    let result_data = a
-        .storage
-        .data
-        .iter()
-        .map(|x| x - b)
-        .collect::<Vec<Scalar>>();
-    let requires_grad = a.requires_grad;
+    let a = &a.to_dtype(a.dtype().promote_scalar());
+    let storage = dispatch_float!(a.dtype(), T => {
+        let b = T::from_f64(b as f64);
+        Storage::new(map_scalar::<T>(a, |x| x.sub(b)))
+    });
+    let requires_grad = a.records_grad();
 
     InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().to_vec(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
         grad_fn: if requires_grad {
-            Some(Rc::new(SubGradFn::new(true, false, vec![a.clone()])))
+            Some(Arc::new(SubGradFn::new(true, false, vec![a.clone()])))
         } else {
             None
         },
@@ -174,26 +171,22 @@ pub fn sub_ts(a: &Tensor, b: Scalar) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        ..output(storage, a)
     }
     .into()
 }
+
 pub fn sub_st(a: Scalar, b: &Tensor) -> Tensor {
-    let result_data = b
-        .storage
-        .data
-        .iter()
-        .map(|x| a - x)
-        .collect::<Vec<Scalar>>();
-    let requires_grad = b.requires_grad;
+    let b = &b.to_dtype(b.dtype().promote_scalar());
+    let storage = dispatch_float!(b.dtype(), T => {
+        let a = T::from_f64(a as f64);
+        Storage::new(map_scalar::<T>(b, |x| a.sub(x)))
+    });
+    let requires_grad = b.records_grad();
 
     InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: b.shape().to_vec(),
-        strides: b.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
         grad_fn: if requires_grad {
-            Some(Rc::new(SubGradFn::new(false, true, vec![b.clone()])))
+            Some(Arc::new(SubGradFn::new(false, true, vec![b.clone()])))
         } else {
             None
         },
@@ -203,26 +196,23 @@ pub fn sub_st(a: Scalar, b: &Tensor) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        ..output(storage, b)
     }
     .into()
 }
 
 pub fn mul_ts(a: &Tensor, b: Scalar) -> Tensor {
-    let mut result_data = Vec::with_capacity(a.shape().iter().product());
-    for i in 0..a.storage.data.len() {
-        result_data.push(a.storage.data[i] * b);
-    }
+    let a = &a.to_dtype(a.dtype().promote_scalar());
+    let storage = dispatch_float!(a.dtype(), T => {
+        let b = T::from_f64(b as f64);
+        Storage::new(map_scalar::<T>(a, |x| x.mul(b)))
+    });
 
-    let requires_grad = a.requires_grad;
+    let requires_grad = a.records_grad();
 
     InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().to_vec(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
         grad_fn: if requires_grad {
-            Some(Rc::new(EWSMultGradFn::new(a.clone(), Some(b), None)))
+            Some(Arc::new(EWSMultGradFn::new(a.clone(), Some(b), None)))
         } else {
             None
         },
@@ -232,61 +222,27 @@ pub fn mul_ts(a: &Tensor, b: Scalar) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        ..output(storage, a)
     }
     .into()
 }
 
 // element-wise multiplication
 pub fn mul_tt_ews(a: &Tensor, b: &Tensor) -> Tensor {
-    let mut out = None;
-
-    if a.is_scalar() {
-        out = Some(mul_ts(b, a.storage.data[0]));
-    }
-    if b.is_scalar() {
-        out = Some(mul_ts(a, b.storage.data[0]));
-    }
-    if let Some(mut out) = out {
-        if a.requires_grad || b.requires_grad {
-            out.set_grad_metadata(
-                Rc::new(EWSMultGradFn::new(a.clone(), None, b.clone().into())),
-                vec![a.clone(), b.clone()],
-            )
-        };
-        return out;
-    }
-
-    assert_eq!(
-        Tensor::reduce_shape(a.shape()),
-        Tensor::reduce_shape(b.shape()),
-        "Shape mismatch for element-wise multiplication: {:?} vs {:?}",
-        a.shape,
-        b.shape
-    );
-
-    let total_elements: usize = a.shape().iter().product();
-    let mut result_data = Vec::with_capacity(total_elements);
+    try_mul_tt_ews(a, b).or_panic()
+}
 
-    // Use strides
-    let mut a_indices = vec![0; a.shape().len()];
-    let mut b_indices = vec![0; b.shape().len()];
-    for _ in 0..total_elements {
-        let idx_a = a.compute_flat_index(&a_indices);
-        let idx_b = b.compute_flat_index(&b_indices);
+pub fn try_mul_tt_ews(a: &Tensor, b: &Tensor) -> Result<Tensor> {
+    let (a, b) = &promote(a, b, "element-wise multiplication")?;
+    let shape = Tensor::broadcast_shape(a.shape(), b.shape())?;
+    let storage =
+        dispatch_numeric!(a.dtype(), T => Storage::new(zip_map::<T, T>(a, b, &shape, T::mul)));
 
-        result_data.push(a.storage.data[idx_a] * b.storage.data[idx_b]);
+    let requires_grad = a.records_grad() || b.records_grad();
 
-        Tensor::increment_indices(&mut a_indices, a.shape());
-        Tensor::increment_indices(&mut b_indices, b.shape());
-    }
-    InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().into(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
-        grad_fn: if a.requires_grad || b.requires_grad {
-            Some(Rc::new(EWSMultGradFn::new(
+    Ok(InternalTensor {
+        grad_fn: if requires_grad {
+            Some(Arc::new(EWSMultGradFn::new(
                 a.clone(),
                 None,
                 b.clone().into(),
@@ -294,32 +250,31 @@ pub fn mul_tt_ews(a: &Tensor, b: &Tensor) -> Tensor {
         } else {
             None
         },
-        parents: if a.requires_grad || b.requires_grad {
+        parents: if requires_grad {
             vec![a.clone(), b.clone()]
         } else {
             Vec::new()
         },
-        requires_grad: a.requires_grad || b.requires_grad,
+        requires_grad,
+        shape: shape.to_vec(),
+        strides: Tensor::compute_strides(&shape),
+        ..output(storage, a)
     }
-    .into()
+    .into())
 }
 
 pub fn div_ts(a: &Tensor, b: Scalar) -> Tensor {
-    let mut result_data = Vec::with_capacity(a.shape().iter().product());
-    for i in 0..a.storage.data.len() {
-        result_data.push(a.storage.data[i] / b);
-    }
+    let a = &a.to_dtype(a.dtype().promote_scalar());
+    let storage = dispatch_float!(a.dtype(), T => {
+        let b = T::from_f64(b as f64);
+        Storage::new(map_scalar::<T>(a, |x| x.div(b)))
+    });
 
-    let requires_grad = a.requires_grad;
+    let requires_grad = a.records_grad();
 
     InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().to_vec(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
         grad_fn: if requires_grad {
-            Some(Rc::new(DivGradFn::new(
+            Some(Arc::new(DivGradFn::new(
                 None,
                 Some(b),
                 Some(a.clone()),
@@ -334,26 +289,23 @@ pub fn div_ts(a: &Tensor, b: Scalar) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        ..output(storage, a)
     }
     .into()
 }
 
 pub fn div_st(a: Scalar, b: &Tensor) -> Tensor {
-    let mut result_data = Vec::with_capacity(b.shape().iter().product());
-    for i in 0..b.storage.data.len() {
-        result_data.push(a / b.storage.data[i]);
-    }
+    let b = &b.to_dtype(b.dtype().promote_scalar());
+    let storage = dispatch_float!(b.dtype(), T => {
+        let a = T::from_f64(a as f64);
+        Storage::new(map_scalar::<T>(b, |x| a.div(x)))
+    });
 
-    let requires_grad = b.requires_grad;
+    let requires_grad = b.records_grad();
 
     InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: b.shape().to_vec(),
-        strides: b.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
         grad_fn: if requires_grad {
-            Some(Rc::new(DivGradFn::new(
+            Some(Arc::new(DivGradFn::new(
                 Some(a),
                 None,
                 None,
@@ -368,67 +320,35 @@ pub fn div_st(a: Scalar, b: &Tensor) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        ..output(storage, b)
     }
     .into()
 }
 
 // element-wise division
 pub fn div_tt_ews(a: &Tensor, b: &Tensor) -> Tensor {
-    let mut out = None;
-    if a.is_scalar() {
-        out = div_st(a.storage.data[0], b).into();
-    }
-
-    if b.is_scalar() {
-        out = div_ts(a, b.storage.data[0]).into();
-    }
-
-    if let Some(mut out) = out {
-        if a.requires_grad || b.requires_grad {
-            out.set_grad_metadata(
-                Rc::new(DivGradFn::new(
-                    None,
-                    None,
-                    a.clone().into(),
-                    b.clone().into(),
-                )),
-                vec![a.clone(), b.clone()],
-            )
-        };
-        return out;
-    }
-
-    assert_eq!(
-        a.shape(),
-        b.shape(),
-        "Shape mismatch: {:?} vs {:?}. Broadcasting not yet implemented.",
-        a.shape(),
-        b.shape()
-    );
-
-    let total_elements: usize = a.shape().iter().product();
-    let mut result_data = Vec::with_capacity(total_elements);
-
-    let mut indices = vec![0; a.shape().len()];
-    for _ in 0..total_elements {
-        let idx_a = a.compute_flat_index(&indices);
-        let idx_b = b.compute_flat_index(&indices);
-
-        result_data.push(a.storage.data[idx_a] / b.storage.data[idx_b]);
+    try_div_tt_ews(a, b).or_panic()
+}
 
-        Tensor::increment_indices(&mut indices, a.shape());
+pub fn try_div_tt_ews(a: &Tensor, b: &Tensor) -> Result<Tensor> {
+    let (a, b) = &promote(a, b, "element-wise division")?;
+    let shape = Tensor::broadcast_shape(a.shape(), b.shape())?;
+    if !b.dtype().is_floating_point() {
+        let has_zero = dispatch_numeric!(b.dtype(), T => b.values::<T>().any(|x| x == T::zero()));
+        if has_zero {
+            return Err(TensorError::InvalidArgument(
+                "element-wise division: integer division by zero".to_string(),
+            ));
+        }
     }
+    let storage =
+        dispatch_numeric!(a.dtype(), T => Storage::new(zip_map::<T, T>(a, b, &shape, T::div)));
 
-    let requires_grad = a.requires_grad || b.requires_grad;
+    let requires_grad = a.records_grad() || b.records_grad();
 
-    InternalTensor {
-        storage: Rc::new(Storage::new(result_data)),
-        shape: a.shape().to_vec(),
-        strides: a.strides.clone(),
-        offset: 0,
-        grad: RefCell::new(None),
+    Ok(InternalTensor {
         grad_fn: if requires_grad {
-            Some(Rc::new(DivGradFn::new(
+            Some(Arc::new(DivGradFn::new(
                 None,
                 None,
                 a.clone().into(),
@@ -443,6 +363,9 @@ pub fn div_tt_ews(a: &Tensor, b: &Tensor) -> Tensor {
             Vec::new()
         },
         requires_grad,
+        shape: shape.to_vec(),
+        strides: Tensor::compute_strides(&shape),
+        ..output(storage, a)
     }
-    .into()
+    .into())
 }
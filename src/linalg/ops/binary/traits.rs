@@ -1,3 +1,4 @@
+use crate::linalg::error::Result;
 use crate::linalg::ops::binary::kernels;
 use crate::linalg::tensor::{Scalar, Tensor};
 use std::ops::{Add, Div, Mul, Sub};
@@ -177,3 +178,29 @@ impl Div<Tensor> for Scalar {
         self / &other
     }
 }
+
+/// Fallible implementations
+/// --------------------------------------
+impl Tensor {
+    /// Element-wise addition, returning an error instead of panicking if the operands are incompatible.
+    pub fn try_add(&self, other: &Tensor) -> Result<Tensor> {
+        kernels::try_add_tt(self, other)
+    }
+
+    /// Element-wise subtraction, returning an error instead of panicking if the operands are incompatible.
+    pub fn try_sub(&self, other: &Tensor) -> Result<Tensor> {
+        kernels::try_sub_tt(self, other)
+    }
+
+    /// Element-wise multiplication, returning an error instead of panicking if the operands are incompatible.
+    pub fn try_mul(&self, other: &Tensor) -> Result<Tensor> {
+        kernels::try_mul_tt_ews(self, other)
+    }
+
+    /// Element-wise division, returning an error instead of panicking if the operands are incompatible.
+    /// Integer tensors are divided with truncation, wrapping on overflow, and dividing one by zero
+    /// is an error.
+    pub fn try_div(&self, other: &Tensor) -> Result<Tensor> {
+        kernels::try_div_tt_ews(self, other)
+    }
+}
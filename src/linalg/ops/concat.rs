@@ -0,0 +1,184 @@
+use crate::dispatch_all;
+use crate::linalg::autograd::grad_fn::shape::CatGradFn;
+use crate::linalg::error::{OrPanic, Result, TensorError, check_index};
+use crate::linalg::tensor::{InternalTensor, Storage, Tensor};
+use std::sync::Arc;
+use std::sync::Mutex;
+
+impl Tensor {
+    /// Concatenates tensors along an existing axis.
+    /// The tensors must have the same shape except along `axis`, and are converted to their
+    /// promoted dtype. Gradients are split back into the piece each input contributed.
+    /// # Arguments
+    /// * `tensors` - The tensors to join, in order
+    /// * `axis` - The axis to join along
+    /// # Returns
+    /// A new contiguous tensor whose size along `axis` is the sum of the sizes of the inputs
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let a = Tensor::new(vec![1.0, 2.0], &[1, 2]);
+    /// let b = Tensor::new(vec![3.0, 4.0, 5.0, 6.0], &[2, 2]);
+    /// let joined = Tensor::cat(&[&a, &b], 0);
+    /// assert_eq!(joined.shape(), &[3, 2]);
+    /// assert_eq!(joined.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// ```
+    pub fn cat(tensors: &[&Tensor], axis: usize) -> Tensor {
+        Tensor::try_cat(tensors, axis).or_panic()
+    }
+
+    /// Concatenates tensors along an existing axis.
+    /// Returns an error if no tensor is given, if the axis is out of bounds or if the shapes
+    /// differ outside of `axis`.
+    pub fn try_cat(tensors: &[&Tensor], axis: usize) -> Result<Tensor> {
+        let first = tensors.first().ok_or_else(|| {
+            TensorError::InvalidArgument("cat: expected at least one tensor".to_string())
+        })?;
+        check_index("cat", axis, first.shape.len())?;
+        for tensor in tensors {
+            let matches = tensor.shape.len() == first.shape.len()
+                && (0..first.shape.len()).all(|d| d == axis || tensor.shape[d] == first.shape[d]);
+            if !matches {
+                return Err(TensorError::ShapeMismatch {
+                    op: "cat",
+                    lhs: first.shape.clone(),
+                    rhs: tensor.shape.clone(),
+                });
+            }
+        }
+
+        let dtype = tensors
+            .iter()
+            .map(|tensor| tensor.dtype())
+            .max()
+            .expect("cat has at least one tensor");
+        let inputs = tensors
+            .iter()
+            .map(|tensor| tensor.to_dtype(dtype))
+            .collect::<Vec<Tensor>>();
+        let mut shape = first.shape.clone();
+        shape[axis] = inputs.iter().map(|input| input.shape[axis]).sum();
+
+        // Every input contributes one block of contiguous elements per index before `axis`
+        let outer = shape[..axis].iter().product::<usize>();
+        let storage = dispatch_all!(dtype, T => {
+            // Inputs sharing their storage, such as a tensor concatenated with itself, are read
+            // through a single lock
+            let contiguous = inputs
+                .iter()
+                .map(|input| input.detached().contiguous())
+                .collect::<Vec<Tensor>>();
+            let mut result = Vec::with_capacity(shape.iter().product());
+            Tensor::with_data::<T, _>(&contiguous.iter().collect::<Vec<&Tensor>>(), |data| {
+                for o in 0..outer {
+                    for (input, data) in contiguous.iter().zip(data) {
+                        let block = input.numel() / outer;
+                        let start = input.offset + o * block;
+                        result.extend_from_slice(&data[start..start + block]);
+                    }
+                }
+            });
+            Storage::new(result)
+        });
+
+        let requires_grad = inputs.iter().any(|input| input.records_grad());
+        let sizes = inputs.iter().map(|input| input.shape[axis]).collect();
+        let inputs_requiring_grad = inputs.iter().map(|input| input.requires_grad).collect();
+
+        Ok(InternalTensor {
+            storage: Arc::new(storage),
+            strides: Tensor::compute_strides(&shape),
+            shape,
+            offset: 0,
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(CatGradFn::new(axis, sizes, inputs_requiring_grad)))
+            } else {
+                None
+            },
+            parents: if requires_grad { inputs } else { Vec::new() },
+            requires_grad,
+        }
+        .into())
+    }
+
+    /// Joins tensors of the same shape along a new axis.
+    /// # Arguments
+    /// * `tensors` - The tensors to join, in order
+    /// * `axis` - The position of the new axis, between `0` and `rank` (inclusive)
+    /// # Returns
+    /// A new tensor with one more dimension, of size `tensors.len()` along `axis`
+    pub fn stack(tensors: &[&Tensor], axis: usize) -> Tensor {
+        Tensor::try_stack(tensors, axis).or_panic()
+    }
+
+    /// Joins tensors of the same shape along a new axis.
+    /// Returns an error if no tensor is given, if the axis is out of bounds or if the shapes differ.
+    pub fn try_stack(tensors: &[&Tensor], axis: usize) -> Result<Tensor> {
+        let unsqueezed = tensors
+            .iter()
+            .map(|tensor| tensor.try_unsqueeze(axis))
+            .collect::<Result<Vec<Tensor>>>()?;
+        Tensor::try_cat(&unsqueezed.iter().collect::<Vec<&Tensor>>(), axis)
+    }
+
+    /// Splits the tensor along `axis` into pieces of the given sizes.
+    /// # Arguments
+    /// * `sizes` - The size of every piece along `axis`, which must add up to the size of the axis
+    /// * `axis` - The axis to split along
+    /// # Returns
+    /// One view per piece, sharing the storage of the tensor
+    pub fn split(&self, sizes: &[usize], axis: usize) -> Vec<Tensor> {
+        self.try_split(sizes, axis).or_panic()
+    }
+
+    /// Splits the tensor along `axis` into pieces of the given sizes.
+    /// Returns an error if the axis is out of bounds or if the sizes do not add up to its size.
+    pub fn try_split(&self, sizes: &[usize], axis: usize) -> Result<Vec<Tensor>> {
+        check_index("split", axis, self.shape.len())?;
+        if sizes.iter().sum::<usize>() != self.shape[axis] {
+            return Err(TensorError::InvalidArgument(format!(
+                "split: sizes {sizes:?} do not add up to {}, the size of axis {axis}",
+                self.shape[axis]
+            )));
+        }
+        let mut start = 0;
+        sizes
+            .iter()
+            .map(|&size| {
+                let piece = self.try_narrow(axis, start, size);
+                start += size;
+                piece
+            })
+            .collect()
+    }
+
+    /// Splits the tensor along `axis` into `chunks` pieces of equal size, the last one being
+    /// smaller if the size of the axis is not divisible by `chunks`. Fewer pieces are returned
+    /// when the axis is too small to fill them all.
+    /// # Arguments
+    /// * `chunks` - The number of pieces
+    /// * `axis` - The axis to split along
+    /// # Returns
+    /// One view per piece, sharing the storage of the tensor
+    pub fn chunk(&self, chunks: usize, axis: usize) -> Vec<Tensor> {
+        self.try_chunk(chunks, axis).or_panic()
+    }
+
+    /// Splits the tensor along `axis` into `chunks` pieces of equal size.
+    /// Returns an error if the axis is out of bounds or if `chunks` is zero.
+    pub fn try_chunk(&self, chunks: usize, axis: usize) -> Result<Vec<Tensor>> {
+        check_index("chunk", axis, self.shape.len())?;
+        if chunks == 0 {
+            return Err(TensorError::InvalidArgument(
+                "chunk: the number of chunks must be positive".to_string(),
+            ));
+        }
+        let size = self.shape[axis].div_ceil(chunks).max(1);
+        let sizes = (0..self.shape[axis])
+            .step_by(size)
+            .map(|start| size.min(self.shape[axis] - start))
+            .collect::<Vec<usize>>();
+        self.try_split(&sizes, axis)
+    }
+}
@@ -0,0 +1,186 @@
+use crate::dispatch_all;
+use crate::linalg::dtype::{DType, Element};
+use crate::linalg::error::{OrPanic, Result, TensorError};
+use crate::linalg::tensor::{Scalar, Tensor};
+
+/// Builds a boolean `[rows, cols]` mask, true where `keep` holds for the difference between the
+/// column and the row of the element.
+fn diagonal_mask(rows: usize, cols: usize, keep: impl Fn(i64) -> bool) -> Tensor {
+    let data = (0..rows * cols)
+        .map(|i| keep((i % cols) as i64 - (i / cols) as i64))
+        .collect();
+    Tensor::from_vec::<bool>(data, &[rows, cols])
+}
+
+impl Tensor {
+    /// Creates a 1D tensor of the values from `start` (included) to `end` (excluded), spaced by
+    /// `step`.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// assert_eq!(Tensor::arange(0.0, 2.0, 0.5).as_slice(), &[0.0, 0.5, 1.0, 1.5]);
+    /// assert_eq!(Tensor::arange(3.0, 0.0, -1.0).as_slice(), &[3.0, 2.0, 1.0]);
+    /// ```
+    pub fn arange(start: Scalar, end: Scalar, step: Scalar) -> Tensor {
+        Tensor::try_arange(start, end, step).or_panic()
+    }
+
+    /// Creates a 1D tensor of the values from `start` (included) to `end` (excluded), spaced by
+    /// `step`.
+    /// Returns an error if `step` is zero or if a bound is not finite.
+    pub fn try_arange(start: Scalar, end: Scalar, step: Scalar) -> Result<Tensor> {
+        if step == 0.0 || !(start.is_finite() && end.is_finite() && step.is_finite()) {
+            return Err(TensorError::InvalidArgument(format!(
+                "arange: invalid range from {start} to {end} by {step}"
+            )));
+        }
+        let len = ((end as f64 - start as f64) / step as f64).ceil().max(0.0) as usize;
+        let data = (0..len)
+            .map(|i| (start as f64 + i as f64 * step as f64) as Scalar)
+            .collect();
+        Tensor::try_new(data, &[len])
+    }
+
+    /// Creates a 1D tensor of `steps` values evenly spaced from `start` to `end`, both included.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// assert_eq!(Tensor::linspace(0.0, 1.0, 5).as_slice(), &[0.0, 0.25, 0.5, 0.75, 1.0]);
+    /// ```
+    pub fn linspace(start: Scalar, end: Scalar, steps: usize) -> Tensor {
+        let (start, end) = (start as f64, end as f64);
+        let step = (end - start) / steps.saturating_sub(1).max(1) as f64;
+        let data = (0..steps)
+            .map(|i| {
+                if i + 1 == steps && steps > 1 {
+                    end as Scalar
+                } else {
+                    (start + i as f64 * step) as Scalar
+                }
+            })
+            .collect();
+        Tensor::new(data, &[steps])
+    }
+
+    /// Creates a 1D tensor of `steps` values evenly spaced on a logarithmic scale, from
+    /// `base^start` to `base^end`, both included.
+    pub fn logspace(start: Scalar, end: Scalar, steps: usize, base: Scalar) -> Tensor {
+        let exponents = Tensor::linspace(start, end, steps);
+        let data = exponents
+            .as_slice()
+            .iter()
+            .map(|&exponent| (base as f64).powf(exponent as f64) as Scalar)
+            .collect();
+        Tensor::new(data, &[steps])
+    }
+
+    /// Creates an `[n, n]` identity matrix.
+    pub fn eye(n: usize) -> Tensor {
+        diagonal_mask(n, n, |d| d == 0).to_dtype(DType::F32)
+    }
+
+    /// Creates a tensor of the given shape with every element set to `value`.
+    pub fn full(shape: &[usize], value: Scalar) -> Tensor {
+        Tensor::new(vec![value; shape.iter().product()], shape)
+    }
+
+    /// Creates a tensor of zeros with the shape and dtype of this tensor.
+    pub fn zeros_like(&self) -> Tensor {
+        self.full_like(0.0)
+    }
+
+    /// Creates a tensor of ones with the shape and dtype of this tensor.
+    pub fn ones_like(&self) -> Tensor {
+        self.full_like(1.0)
+    }
+
+    /// Creates a tensor with the shape and dtype of this tensor, with every element set to
+    /// `value` converted to that dtype.
+    pub fn full_like(&self, value: Scalar) -> Tensor {
+        dispatch_all!(self.dtype(), T => {
+            let data = vec![T::from_f64(value as f64); self.numel()];
+            Tensor::from_vec(data, &self.shape)
+        })
+    }
+
+    /// Builds a diagonal matrix from a 1D tensor, or extracts the diagonal of a 2D tensor.
+    /// Gradients flow back to the diagonal elements.
+    /// # Returns
+    /// A square matrix whose diagonal holds the `n` elements of a 1D tensor, or a 1D view of the
+    /// diagonal of a 2D tensor
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let matrix = Tensor::new(vec![1.0, 2.0, 3.0], &[3]).diag();
+    /// assert_eq!(matrix.shape(), &[3, 3]);
+    /// assert_eq!(matrix.diag().contiguous().as_slice(), &[1.0, 2.0, 3.0]);
+    /// ```
+    pub fn diag(&self) -> Tensor {
+        self.try_diag().or_panic()
+    }
+
+    /// Builds a diagonal matrix from a 1D tensor, or extracts the diagonal of a 2D tensor.
+    /// Returns an error if the tensor is neither 1D nor 2D.
+    pub fn try_diag(&self) -> Result<Tensor> {
+        match self.shape.len() {
+            1 => {
+                let n = self.shape[0];
+                let zeros = Tensor::from_scalar(0.0).to_dtype(self.dtype());
+                Tensor::try_where_(&diagonal_mask(n, n, |d| d == 0), self, &zeros)
+            }
+            2 => Ok(self.view(|layout| {
+                layout.shape = vec![layout.shape[0].min(layout.shape[1])];
+                layout.strides = vec![layout.strides[0] + layout.strides[1]];
+            })),
+            rank => Err(TensorError::Rank {
+                op: "diag",
+                expected: 2,
+                actual: rank,
+            }),
+        }
+    }
+
+    /// Keeps the lower triangle of the matrices held in the last two dimensions, and zeroes the
+    /// other elements.
+    /// # Arguments
+    /// * `diagonal` - The last diagonal kept: `0` is the main diagonal, positive values are above
+    ///   it and negative values below it
+    pub fn tril(&self, diagonal: i64) -> Tensor {
+        self.try_tril(diagonal).or_panic()
+    }
+
+    /// Keeps the lower triangle of the matrices held in the last two dimensions.
+    /// Returns an error if the tensor has fewer than two dimensions.
+    pub fn try_tril(&self, diagonal: i64) -> Result<Tensor> {
+        self.triangle("tril", |d| d <= diagonal)
+    }
+
+    /// Keeps the upper triangle of the matrices held in the last two dimensions, and zeroes the
+    /// other elements.
+    /// # Arguments
+    /// * `diagonal` - The first diagonal kept: `0` is the main diagonal, positive values are above
+    ///   it and negative values below it
+    pub fn triu(&self, diagonal: i64) -> Tensor {
+        self.try_triu(diagonal).or_panic()
+    }
+
+    /// Keeps the upper triangle of the matrices held in the last two dimensions.
+    /// Returns an error if the tensor has fewer than two dimensions.
+    pub fn try_triu(&self, diagonal: i64) -> Result<Tensor> {
+        self.triangle("triu", |d| d >= diagonal)
+    }
+
+    fn triangle(&self, op: &'static str, keep: impl Fn(i64) -> bool) -> Result<Tensor> {
+        let rank = self.shape.len();
+        if rank < 2 {
+            return Err(TensorError::Rank {
+                op,
+                expected: 2,
+                actual: rank,
+            });
+        }
+        let mask = diagonal_mask(self.shape[rank - 2], self.shape[rank - 1], keep);
+        let zeros = Tensor::from_scalar(0.0).to_dtype(self.dtype());
+        Tensor::try_where_(&mask, self, &zeros)
+    }
+}
@@ -0,0 +1,352 @@
+use crate::linalg::error::{OrPanic, Result, TensorError};
+use crate::linalg::tensor::Tensor;
+
+/// Identifies a dimension of an einsum equation. The dimensions covered by an ellipsis get the
+/// labels from zero up, right-aligned across operands, and letters are offset by
+/// [`LETTER_BASE`] so that ellipsis dimensions come first in an implicit output.
+type Label = usize;
+
+const LETTER_BASE: Label = 256;
+
+/// An operand, with one label per dimension. An operand without labels has the shape `[1]`.
+struct Operand {
+    tensor: Tensor,
+    labels: Vec<Label>,
+}
+
+impl Operand {
+    fn size(&self, label: Label) -> usize {
+        let axis = self.axis(label).expect("label belongs to the operand");
+        self.tensor.shape()[axis]
+    }
+
+    fn axis(&self, label: Label) -> Option<usize> {
+        self.labels.iter().position(|&l| l == label)
+    }
+
+    /// Moves the dimensions to the order given by `labels`, which must be a permutation of the
+    /// labels of the operand.
+    fn permuted(&self, labels: &[Label]) -> Tensor {
+        if labels.is_empty() {
+            return self.tensor.clone();
+        }
+        let axes = labels
+            .iter()
+            .map(|&label| self.axis(label).expect("label belongs to the operand"))
+            .collect::<Vec<usize>>();
+        self.tensor.permute(&axes)
+    }
+
+    /// Takes the diagonal over every label repeated in the operand, so that labels are unique.
+    fn diagonal(self) -> Result<Operand> {
+        let mut labels = Vec::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (axis, &label) in self.labels.iter().enumerate() {
+            match labels.iter().position(|&l| l == label) {
+                Some(group) => groups[group].push(axis),
+                None => {
+                    labels.push(label);
+                    groups.push(vec![axis]);
+                }
+            }
+        }
+        if groups.len() == self.labels.len() {
+            return Ok(self);
+        }
+        let shape = self.tensor.shape();
+        for group in &groups {
+            if group.iter().any(|&axis| shape[axis] != shape[group[0]]) {
+                return Err(TensorError::InvalidArgument(format!(
+                    "einsum: repeated subscript with different sizes in an operand of shape {shape:?}"
+                )));
+            }
+        }
+        // Walking the diagonal moves along every repeated dimension at once
+        let tensor = self.tensor.view(|layout| {
+            layout.strides = groups
+                .iter()
+                .map(|group| group.iter().map(|&axis| layout.strides[axis]).sum())
+                .collect();
+            layout.shape = groups.iter().map(|group| layout.shape[group[0]]).collect();
+        });
+        Ok(Operand { tensor, labels })
+    }
+
+    /// Sums over the labels of the operand that are not in `keep`.
+    fn sum_except(self, keep: &[Label]) -> Operand {
+        let axes = (0..self.labels.len())
+            .filter(|&axis| !keep.contains(&self.labels[axis]))
+            .collect::<Vec<usize>>();
+        if axes.is_empty() {
+            return self;
+        }
+        Operand {
+            tensor: self.tensor.sum_axes(&axes, false),
+            labels: self
+                .labels
+                .into_iter()
+                .filter(|label| keep.contains(label))
+                .collect(),
+        }
+    }
+
+    /// Expands the dimensions of size one of the operand to the size they have in `other`.
+    fn expand_to(self, other: &Operand, labels: &[Label]) -> Operand {
+        let mut shape = self.tensor.shape().to_vec();
+        for &label in labels {
+            let axis = self.axis(label).expect("label belongs to the operand");
+            if shape[axis] == 1 {
+                shape[axis] = other.size(label);
+            }
+        }
+        if shape == self.tensor.shape() {
+            return self;
+        }
+        Operand {
+            tensor: self.tensor.expand(&shape),
+            labels: self.labels,
+        }
+    }
+}
+
+/// Contracts two operands, keeping the labels in `keep` and summing over the others.
+/// Labels shared by both operands and kept act as batch dimensions of a batched matrix product,
+/// and shared labels that are not kept are the inner dimension of that product.
+fn contract(a: Operand, b: Operand, keep: &[Label]) -> Operand {
+    let a = a.sum_except(&[keep, &b.labels].concat());
+    let b = b.sum_except(&[keep, &a.labels].concat());
+    let shared = |label: &&Label| b.labels.contains(label);
+    let batch = a
+        .labels
+        .iter()
+        .filter(shared)
+        .filter(|label| keep.contains(label))
+        .copied()
+        .collect::<Vec<Label>>();
+    let inner = a
+        .labels
+        .iter()
+        .filter(shared)
+        .filter(|label| !keep.contains(label))
+        .copied()
+        .collect::<Vec<Label>>();
+    let left = a
+        .labels
+        .iter()
+        .filter(|label| !b.labels.contains(label))
+        .copied()
+        .collect::<Vec<Label>>();
+    let right = b
+        .labels
+        .iter()
+        .filter(|label| !a.labels.contains(label))
+        .copied()
+        .collect::<Vec<Label>>();
+
+    // Inner dimensions are summed, so a dimension broadcast from one must be materialized
+    let a = a.expand_to(&b, &inner);
+    let b = b.expand_to(&a, &inner);
+
+    let sizes = |operand: &Operand, labels: &[Label]| {
+        labels
+            .iter()
+            .map(|&label| operand.size(label))
+            .collect::<Vec<usize>>()
+    };
+    let product = |operand: &Operand, labels: &[Label]| sizes(operand, labels).iter().product();
+
+    let a_shape = [
+        sizes(&a, &batch),
+        vec![product(&a, &left), product(&a, &inner)],
+    ]
+    .concat();
+    let b_shape = [
+        sizes(&b, &batch),
+        vec![product(&b, &inner), product(&b, &right)],
+    ]
+    .concat();
+    let a_matrix = a
+        .permuted(&[&batch[..], &left, &inner].concat())
+        .reshape(&a_shape);
+    let b_matrix = b
+        .permuted(&[&batch[..], &inner, &right].concat())
+        .reshape(&b_shape);
+    let result = a_matrix.matmul(&b_matrix);
+
+    let batch_shape = result.shape()[..batch.len()].to_vec();
+    let shape = [batch_shape, sizes(&a, &left), sizes(&b, &right)].concat();
+    let labels = [batch, left, right].concat();
+    let shape = if shape.is_empty() { vec![1] } else { shape };
+    Operand {
+        tensor: result.reshape(&shape),
+        labels,
+    }
+}
+
+/// Parses the subscripts of one term, replacing an ellipsis with the labels of the dimensions it
+/// covers, given the rank of the operand.
+fn parse_term(term: &str, rank: Option<usize>, ellipsis_rank: usize) -> Result<Vec<Label>> {
+    let letters = term.replace("...", "");
+    if let Some(c) = letters.chars().find(|c| !c.is_ascii_alphabetic()) {
+        return Err(TensorError::InvalidArgument(format!(
+            "einsum: invalid subscript '{c}' in '{term}'"
+        )));
+    }
+    let covered = match (term.matches("...").count(), rank) {
+        (0, _) => 0,
+        (1, Some(rank)) => rank.saturating_sub(letters.len()),
+        (1, None) => ellipsis_rank,
+        _ => {
+            return Err(TensorError::InvalidArgument(format!(
+                "einsum: more than one ellipsis in '{term}'"
+            )));
+        }
+    };
+    let mut labels = Vec::new();
+    for (i, part) in term.split("...").enumerate() {
+        if i == 1 {
+            labels.extend(ellipsis_rank - covered..ellipsis_rank);
+        }
+        labels.extend(part.bytes().map(|c| LETTER_BASE + c as Label));
+    }
+    Ok(labels)
+}
+
+impl Tensor {
+    /// Evaluates an Einstein summation over the operands.
+    ///
+    /// The equation lists the subscripts of every operand, separated by commas, optionally
+    /// followed by `->` and the subscripts of the output. Subscripts are ASCII letters, one per
+    /// dimension, and `...` stands for the remaining dimensions, which are broadcast together.
+    /// A subscript repeated within an operand takes its diagonal, and subscripts missing from the
+    /// output are summed over. Without `->`, the output holds the ellipsis dimensions followed by
+    /// the subscripts appearing exactly once, in alphabetical order.
+    /// # Arguments
+    /// * `equation` - The subscripts of the operands and of the output
+    /// * `operands` - The tensors to combine, one per term of the equation
+    /// # Returns
+    /// A new tensor, with the shape `[1]` if the output has no subscripts
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    /// let b = Tensor::new(vec![5.0, 6.0, 7.0, 8.0], &[2, 2]);
+    /// assert_eq!(Tensor::einsum("ij,jk->ik", &[&a, &b]).as_slice(), a.matmul(&b).as_slice());
+    /// assert_eq!(Tensor::einsum("ii", &[&a]).as_slice(), &[5.0]);
+    /// assert_eq!(Tensor::einsum("i,j->ij", &[&a.select(0, 0), &b.select(0, 0)]).shape(), &[2, 2]);
+    /// ```
+    pub fn einsum(equation: &str, operands: &[&Tensor]) -> Tensor {
+        Tensor::try_einsum(equation, operands).or_panic()
+    }
+
+    /// Evaluates an Einstein summation over the operands.
+    /// Returns an error if the equation is malformed or does not match the shapes of the operands.
+    pub fn try_einsum(equation: &str, operands: &[&Tensor]) -> Result<Tensor> {
+        let equation = equation.replace(char::is_whitespace, "");
+        let (inputs, output) = match equation.split_once("->") {
+            Some((inputs, output)) => (inputs, Some(output)),
+            None => (equation.as_str(), None),
+        };
+        let terms = inputs.split(',').collect::<Vec<&str>>();
+        if terms.len() != operands.len() {
+            return Err(TensorError::InvalidArgument(format!(
+                "einsum: {} operands given for {} terms in '{equation}'",
+                operands.len(),
+                terms.len()
+            )));
+        }
+
+        // Scalars of shape [1] may be written without subscripts
+        let rank = |term: &str, tensor: &Tensor| match (term.is_empty(), tensor.shape()) {
+            (true, [1]) => 0,
+            (_, shape) => shape.len(),
+        };
+        let ellipsis_rank = terms
+            .iter()
+            .zip(operands)
+            .filter(|(term, _)| term.contains("..."))
+            .map(|(term, tensor)| rank(term, tensor).saturating_sub(term.len() - 3))
+            .max()
+            .unwrap_or(0);
+
+        let mut parsed = Vec::with_capacity(operands.len());
+        for (term, tensor) in terms.iter().zip(operands) {
+            let labels = parse_term(term, Some(rank(term, tensor)), ellipsis_rank)?;
+            if labels.len() != rank(term, tensor) {
+                return Err(TensorError::Rank {
+                    op: "einsum",
+                    expected: labels.len(),
+                    actual: tensor.shape().len(),
+                });
+            }
+            parsed.push(Operand {
+                tensor: (*tensor).clone(),
+                labels,
+            });
+        }
+
+        let all_labels = parsed
+            .iter()
+            .flat_map(|operand| operand.labels.iter().copied())
+            .collect::<Vec<Label>>();
+        let output = match output {
+            Some(output) => parse_term(output, None, ellipsis_rank)?,
+            None => {
+                let mut labels = all_labels
+                    .iter()
+                    .copied()
+                    .filter(|&label| {
+                        label < LETTER_BASE
+                            || all_labels.iter().filter(|&&l| l == label).count() == 1
+                    })
+                    .collect::<Vec<Label>>();
+                labels.sort_unstable();
+                labels.dedup();
+                labels
+            }
+        };
+        for (i, label) in output.iter().enumerate() {
+            if output[..i].contains(label) || !all_labels.contains(label) {
+                return Err(TensorError::InvalidArgument(format!(
+                    "einsum: output subscripts of '{equation}' must be unique and appear in an input"
+                )));
+            }
+        }
+
+        let mut operands = parsed
+            .into_iter()
+            .map(Operand::diagonal)
+            .collect::<Result<Vec<Operand>>>()?;
+        // Sizes of the same label must agree, up to broadcasting of dimensions of size one
+        for label in &all_labels {
+            let mut sizes = operands
+                .iter()
+                .filter(|operand| operand.labels.contains(label))
+                .map(|operand| operand.size(*label))
+                .filter(|&size| size != 1);
+            if let Some(size) = sizes.next()
+                && sizes.any(|other| other != size)
+            {
+                return Err(TensorError::ShapeMismatch {
+                    op: "einsum: subscript sizes must match",
+                    lhs: operands[0].tensor.shape().to_vec(),
+                    rhs: operands[operands.len() - 1].tensor.shape().to_vec(),
+                });
+            }
+        }
+
+        let mut remaining = operands.split_off(1);
+        remaining.reverse();
+        let mut result = operands.pop().expect("einsum has at least one operand");
+        while let Some(next) = remaining.pop() {
+            let keep = remaining
+                .iter()
+                .flat_map(|operand| operand.labels.iter().copied())
+                .chain(output.iter().copied())
+                .collect::<Vec<Label>>();
+            result = contract(result, next, &keep);
+        }
+        let result = result.sum_except(&output);
+        Ok(result.permuted(&output))
+    }
+}
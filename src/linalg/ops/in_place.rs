@@ -0,0 +1,147 @@
+use crate::dispatch_all;
+use crate::linalg::error::{OrPanic, Result, TensorError};
+use crate::linalg::tensor::{Scalar, Tensor};
+
+impl Tensor {
+    /// Returns the number of in-place writes to the data of the tensor. Tensors sharing their data,
+    /// such as clones and views, share the count, and see each other's writes.
+    pub fn version(&self) -> usize {
+        self.storage.version()
+    }
+
+    /// Overwrites the elements of the tensor with `values`, which has the shape of the tensor and
+    /// is converted to its dtype. The write goes to the shared storage, so that the tensors sharing
+    /// it see the new values, and bumps its version once.
+    fn assign(&mut self, op: &'static str, values: &Tensor) -> Result<()> {
+        if self.grad_fn.is_some() {
+            return Err(TensorError::InvalidArgument(format!(
+                "{op}: in-place operations are not differentiable, detach the tensor first"
+            )));
+        }
+        if self
+            .shape
+            .iter()
+            .zip(&self.strides)
+            .any(|(&size, &stride)| size > 1 && stride == 0)
+        {
+            return Err(TensorError::InvalidArgument(format!(
+                "{op}: cannot write to a broadcast view, whose elements overlap"
+            )));
+        }
+        let positions = self.storage_indices();
+        dispatch_all!(self.dtype(), T => {
+            // The values are read before locking the storage, which they may share
+            let values = values.to_dtype(self.dtype()).values::<T>().collect::<Vec<T>>();
+            self.storage.write::<T>(|data| {
+                for (position, value) in positions.zip(values) {
+                    data[position] = value;
+                }
+            });
+        });
+        Ok(())
+    }
+
+    /// Checks that `other` can be broadcast to the shape of the tensor, which in-place
+    /// operations cannot change.
+    fn check_in_place_shape(&self, op: &'static str, other: &Tensor) -> Result<()> {
+        if Tensor::broadcast_shape(&self.shape, &other.shape)? != self.shape {
+            return Err(TensorError::ShapeMismatch {
+                op,
+                lhs: self.shape.clone(),
+                rhs: other.shape.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Adds `other` to the tensor in place, broadcasting it to the shape of the tensor.
+    ///
+    /// In-place operations are not recorded by autograd: they update tensors outside of the
+    /// graph, such as parameters in an optimizer step. Calling `backward` on a graph that saved
+    /// the tensor before it was modified panics.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let mut a = Tensor::new(vec![1.0, 2.0], &[2]);
+    /// a.add_(&Tensor::from_scalar(10.0));
+    /// assert_eq!(a.as_slice(), &[11.0, 12.0]);
+    /// assert_eq!(a.version(), 1);
+    /// ```
+    pub fn add_(&mut self, other: &Tensor) {
+        self.try_add_(other).or_panic()
+    }
+
+    /// Adds `other` to the tensor in place.
+    /// Returns an error if `other` cannot be broadcast to the shape of the tensor, or if the
+    /// tensor is the output of a differentiable operation.
+    pub fn try_add_(&mut self, other: &Tensor) -> Result<()> {
+        self.check_in_place_shape("add_", other)?;
+        let values = &self.detached() + &other.detached();
+        self.assign("add_", &values)
+    }
+
+    /// Subtracts `other` from the tensor in place, broadcasting it to the shape of the tensor.
+    /// See [`Tensor::add_`] for the interaction with autograd.
+    pub fn sub_(&mut self, other: &Tensor) {
+        self.try_sub_(other).or_panic()
+    }
+
+    /// Subtracts `other` from the tensor in place.
+    /// Returns an error if `other` cannot be broadcast to the shape of the tensor, or if the
+    /// tensor is the output of a differentiable operation.
+    pub fn try_sub_(&mut self, other: &Tensor) -> Result<()> {
+        self.check_in_place_shape("sub_", other)?;
+        let values = &self.detached() - &other.detached();
+        self.assign("sub_", &values)
+    }
+
+    /// Multiplies the tensor by `other` in place, broadcasting it to the shape of the tensor.
+    /// See [`Tensor::add_`] for the interaction with autograd.
+    pub fn mul_(&mut self, other: &Tensor) {
+        self.try_mul_(other).or_panic()
+    }
+
+    /// Multiplies the tensor by `other` in place.
+    /// Returns an error if `other` cannot be broadcast to the shape of the tensor, or if the
+    /// tensor is the output of a differentiable operation.
+    pub fn try_mul_(&mut self, other: &Tensor) -> Result<()> {
+        self.check_in_place_shape("mul_", other)?;
+        let values = &self.detached() * &other.detached();
+        self.assign("mul_", &values)
+    }
+
+    /// Copies the elements of `src` into the tensor, broadcasting `src` to the shape of the
+    /// tensor and converting it to its dtype.
+    /// See [`Tensor::add_`] for the interaction with autograd.
+    pub fn copy_(&mut self, src: &Tensor) {
+        self.try_copy_(src).or_panic()
+    }
+
+    /// Copies the elements of `src` into the tensor.
+    /// Returns an error if `src` cannot be broadcast to the shape of the tensor, or if the
+    /// tensor is the output of a differentiable operation.
+    pub fn try_copy_(&mut self, src: &Tensor) -> Result<()> {
+        self.check_in_place_shape("copy_", src)?;
+        let values = src.detached().try_expand(&self.shape)?;
+        self.assign("copy_", &values)
+    }
+
+    /// Sets every element of the tensor to `value`, converted to its dtype.
+    /// See [`Tensor::add_`] for the interaction with autograd.
+    pub fn fill_(&mut self, value: Scalar) {
+        self.try_fill_(value).or_panic()
+    }
+
+    /// Sets every element of the tensor to `value`.
+    /// Returns an error if the tensor is the output of a differentiable operation.
+    pub fn try_fill_(&mut self, value: Scalar) -> Result<()> {
+        let values = self.full_like(value);
+        self.assign("fill_", &values)
+    }
+
+    /// Sets every element of the tensor to zero.
+    /// See [`Tensor::add_`] for the interaction with autograd.
+    pub fn zero_(&mut self) {
+        self.fill_(0.0)
+    }
+}
@@ -0,0 +1,408 @@
+use crate::linalg::autograd::grad_fn::index::{GatherGradFn, MaskedSelectGradFn, ScatterGradFn};
+use crate::linalg::dtype::{DType, Element, Numeric};
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype, check_index};
+use crate::linalg::strided::StridedIter;
+use crate::linalg::tensor::{InternalTensor, Storage, Tensor};
+use crate::{dispatch_all, dispatch_numeric};
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Converts an index tensor to `I64`, checking that it holds integers.
+fn index_tensor(op: &'static str, index: &Tensor) -> Result<Tensor> {
+    check_dtype(op, index.dtype(), |dtype| {
+        matches!(dtype, DType::U8 | DType::I64)
+    })?;
+    Ok(index.to_dtype(DType::I64))
+}
+
+/// Checks that `index` has the rank of `shape` and is not larger along any axis but `axis`.
+fn check_index_shape(op: &'static str, axis: usize, index: &Tensor, shape: &[usize]) -> Result<()> {
+    let fits = index.shape().len() == shape.len()
+        && (0..shape.len()).all(|d| d == axis || index.shape()[d] <= shape[d]);
+    if fits {
+        Ok(())
+    } else {
+        Err(TensorError::ShapeMismatch {
+            op,
+            lhs: shape.to_vec(),
+            rhs: index.shape().to_vec(),
+        })
+    }
+}
+
+/// Reads the values of an `I64` index tensor, checking that they are valid indices for an axis
+/// of size `size`.
+fn index_values(op: &'static str, index: &Tensor, size: usize) -> Result<Vec<usize>> {
+    index
+        .values::<i64>()
+        .map(|i| {
+            let i = usize::try_from(i)
+                .map_err(|_| TensorError::InvalidArgument(format!("{op}: negative index {i}")))?;
+            check_index(op, i, size).map(|()| i)
+        })
+        .collect()
+}
+
+/// Returns the storage positions addressed by every element of an index of shape `index_shape`,
+/// in a tensor laid out with `strides` and `offset`: the index picks the position along `axis`,
+/// and the position of the element in the index gives the other coordinates.
+fn indexed_positions<'a>(
+    strides: &[usize],
+    offset: usize,
+    index_shape: &[usize],
+    axis: usize,
+    indices: &'a [usize],
+) -> impl Iterator<Item = usize> + 'a {
+    let mut strides = strides.to_vec();
+    let axis_stride = std::mem::replace(&mut strides[axis], 0);
+    StridedIter::new(index_shape, &strides, offset)
+        .zip(indices)
+        .map(move |(position, &i)| position + i * axis_stride)
+}
+
+/// Copies `data` and combines the value at every position in `positions` with the matching value
+/// of `values` using `combine`.
+fn scatter_into<T: Element>(
+    data: &[T],
+    positions: impl Iterator<Item = usize>,
+    values: impl Iterator<Item = T>,
+    combine: impl Fn(T, T) -> T,
+) -> Vec<T> {
+    let mut data = data.to_vec();
+    for (position, value) in positions.zip(values) {
+        data[position] = combine(data[position], value);
+    }
+    data
+}
+
+impl Tensor {
+    /// Gathers elements along `axis`, reading the position along `axis` from `index`.
+    /// For a 2D tensor and `axis = 1`, `out[i][j] = self[i][index[i][j]]`.
+    /// # Arguments
+    /// * `axis` - The axis to index along
+    /// * `index` - An integer tensor of the same rank as the tensor, no larger than it along the
+    ///   other axes
+    /// # Returns
+    /// A new tensor of the shape of `index`
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let scores = Tensor::new(vec![0.1, 0.7, 0.2, 0.5, 0.3, 0.2], &[2, 3]);
+    /// let labels = Tensor::from_vec(vec![1i64, 0], &[2, 1]);
+    /// assert_eq!(scores.gather(1, &labels).as_slice(), &[0.7, 0.5]);
+    /// ```
+    pub fn gather(&self, axis: usize, index: &Tensor) -> Tensor {
+        self.try_gather(axis, index).or_panic()
+    }
+
+    /// Gathers elements along `axis`, reading the position along `axis` from `index`.
+    /// Returns an error if the axis or an index is out of bounds, or if `index` does not fit
+    /// the shape of the tensor.
+    pub fn try_gather(&self, axis: usize, index: &Tensor) -> Result<Tensor> {
+        check_index("gather", axis, self.shape.len())?;
+        let index = index_tensor("gather", index)?;
+        check_index_shape("gather", axis, &index, &self.shape)?;
+        let indices = index_values("gather", &index, self.shape[axis])?;
+        let storage = dispatch_all!(self.dtype(), T => {
+            let data = self.data::<T>();
+            let positions =
+                indexed_positions(&self.strides, self.offset, index.shape(), axis, &indices);
+            Storage::new(positions.map(|position| data[position]).collect::<Vec<T>>())
+        });
+
+        let requires_grad = self.records_grad();
+
+        Ok(InternalTensor {
+            storage: Arc::new(storage),
+            shape: index.shape().to_vec(),
+            strides: Tensor::compute_strides(index.shape()),
+            offset: 0,
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(GatherGradFn::new(
+                    axis,
+                    index.clone(),
+                    self.shape.clone(),
+                )))
+            } else {
+                None
+            },
+            parents: if requires_grad {
+                vec![self.clone()]
+            } else {
+                Vec::new()
+            },
+            requires_grad,
+        }
+        .into())
+    }
+
+    /// Writes the elements of `src` into a copy of the tensor, at the positions along `axis` given
+    /// by `index`. For a 2D tensor and `axis = 0`, `out[index[i][j]][j] = src[i][j]`.
+    /// When an element is written several times, the last write wins.
+    /// # Arguments
+    /// * `axis` - The axis to index along
+    /// * `index` - An integer tensor of the same rank as the tensor, no larger than it along the
+    ///   other axes
+    /// * `src` - The values to write, at least as large as `index` along every axis
+    /// # Returns
+    /// A new tensor of the same shape
+    pub fn scatter(&self, axis: usize, index: &Tensor, src: &Tensor) -> Tensor {
+        self.try_scatter(axis, index, src).or_panic()
+    }
+
+    /// Writes the elements of `src` into a copy of the tensor at the positions given by `index`.
+    /// Returns an error if the axis or an index is out of bounds, or if the shapes do not fit.
+    pub fn try_scatter(&self, axis: usize, index: &Tensor, src: &Tensor) -> Result<Tensor> {
+        self.scatter_with("scatter", axis, index, src, false)
+    }
+
+    /// Adds the elements of `src` to a copy of the tensor, at the positions along `axis` given by
+    /// `index`. For a 2D tensor and `axis = 0`, `out[index[i][j]][j] += src[i][j]`.
+    /// Elements indexed several times accumulate every value.
+    /// # Arguments
+    /// * `axis` - The axis to index along
+    /// * `index` - An integer tensor of the same rank as the tensor, no larger than it along the
+    ///   other axes
+    /// * `src` - The values to add, at least as large as `index` along every axis
+    /// # Returns
+    /// A new tensor of the same shape
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// // Histogram of the bins 0, 2, 2, 1
+    /// let bins = Tensor::from_vec(vec![0i64, 2, 2, 1], &[4]);
+    /// let counts = Tensor::zeros(&[3]).scatter_add(0, &bins, &Tensor::ones(&[4]));
+    /// assert_eq!(counts.as_slice(), &[1.0, 1.0, 2.0]);
+    /// ```
+    pub fn scatter_add(&self, axis: usize, index: &Tensor, src: &Tensor) -> Tensor {
+        self.try_scatter_add(axis, index, src).or_panic()
+    }
+
+    /// Adds the elements of `src` to a copy of the tensor at the positions given by `index`.
+    /// Returns an error if the axis or an index is out of bounds, or if the shapes do not fit.
+    pub fn try_scatter_add(&self, axis: usize, index: &Tensor, src: &Tensor) -> Result<Tensor> {
+        self.scatter_with("scatter_add", axis, index, src, true)
+    }
+
+    /// Shared implementation of [`Tensor::scatter`] and [`Tensor::scatter_add`].
+    fn scatter_with(
+        &self,
+        op: &'static str,
+        axis: usize,
+        index: &Tensor,
+        src: &Tensor,
+        accumulate: bool,
+    ) -> Result<Tensor> {
+        check_index(op, axis, self.shape.len())?;
+        let index = index_tensor(op, index)?;
+        check_index_shape(op, axis, &index, &self.shape)?;
+        if src.shape.len() != index.shape().len()
+            || src.shape.iter().zip(index.shape()).any(|(s, i)| s < i)
+        {
+            return Err(TensorError::ShapeMismatch {
+                op,
+                lhs: src.shape.clone(),
+                rhs: index.shape().to_vec(),
+            });
+        }
+        let indices = index_values(op, &index, self.shape[axis])?;
+
+        let dtype = self.dtype().promote(src.dtype());
+        if accumulate {
+            check_dtype(op, dtype, |dtype| dtype != DType::Bool)?;
+        }
+        let input = self.to_dtype(dtype);
+        // Only the part of `src` covered by `index` is read, which keeps its gradient in place
+        let mut src = src.to_dtype(dtype);
+        for (d, &size) in index.shape().iter().enumerate() {
+            if src.shape[d] != size {
+                src = src.narrow(d, 0, size);
+            }
+        }
+
+        let strides = Tensor::compute_strides(&self.shape);
+        let positions = indexed_positions(&strides, 0, index.shape(), axis, &indices);
+        let storage = if accumulate {
+            dispatch_numeric!(dtype, T => {
+                // The source is read before the input, as they may share their storage
+                let values = src.values::<T>().collect::<Vec<T>>();
+                let data = input.contiguous_data::<T>();
+                Storage::new(scatter_into(&data, positions, values.into_iter(), T::add))
+            })
+        } else {
+            dispatch_all!(dtype, T => {
+                // The source is read before the input, as they may share their storage
+                let values = src.values::<T>().collect::<Vec<T>>();
+                let data = input.contiguous_data::<T>();
+                Storage::new(scatter_into(&data, positions, values.into_iter(), |_, value: T| value))
+            })
+        };
+
+        let requires_grad = input.records_grad() || src.records_grad();
+
+        Ok(InternalTensor {
+            storage: Arc::new(storage),
+            shape: self.shape.clone(),
+            strides,
+            offset: 0,
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(ScatterGradFn::new(
+                    axis,
+                    index,
+                    accumulate,
+                    input.requires_grad,
+                    src.requires_grad,
+                )))
+            } else {
+                None
+            },
+            parents: if requires_grad {
+                vec![input, src]
+            } else {
+                Vec::new()
+            },
+            requires_grad,
+        }
+        .into())
+    }
+
+    /// Expands a 1D index to `shape`, laying its values along `axis`.
+    fn expand_index(
+        op: &'static str,
+        index: &Tensor,
+        axis: usize,
+        shape: &[usize],
+    ) -> Result<Tensor> {
+        if index.shape().len() != 1 {
+            return Err(TensorError::Rank {
+                op,
+                expected: 1,
+                actual: index.shape().len(),
+            });
+        }
+        let mut index_shape = vec![1; shape.len()];
+        index_shape[axis] = index.numel();
+        index.clone().try_reshape(&index_shape)?.try_expand(shape)
+    }
+
+    /// Selects the slices at the given indices along `axis`.
+    /// Indices may repeat, and the gradient of a slice selected several times accumulates.
+    /// # Arguments
+    /// * `axis` - The axis to select along
+    /// * `index` - A 1D integer tensor holding the indices to select, in order
+    /// # Returns
+    /// A new tensor of size `index.numel()` along `axis`
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// // Embedding lookup of the tokens 2, 0, 2
+    /// let table = Tensor::new(vec![0.0, 0.1, 1.0, 1.1, 2.0, 2.1], &[3, 2]);
+    /// let tokens = Tensor::from_vec(vec![2i64, 0, 2], &[3]);
+    /// let embedded = table.index_select(0, &tokens);
+    /// assert_eq!(embedded.as_slice(), &[2.0, 2.1, 0.0, 0.1, 2.0, 2.1]);
+    /// ```
+    pub fn index_select(&self, axis: usize, index: &Tensor) -> Tensor {
+        self.try_index_select(axis, index).or_panic()
+    }
+
+    /// Selects the slices at the given indices along `axis`.
+    /// Returns an error if the axis or an index is out of bounds, or if `index` is not 1D.
+    pub fn try_index_select(&self, axis: usize, index: &Tensor) -> Result<Tensor> {
+        check_index("index_select", axis, self.shape.len())?;
+        let mut shape = self.shape.clone();
+        shape[axis] = index.numel();
+        let index = Tensor::expand_index("index_select", index, axis, &shape)?;
+        self.try_gather(axis, &index)
+    }
+
+    /// Adds the slices of `source` to a copy of the tensor, at the given indices along `axis`.
+    /// Slices added to the same index accumulate.
+    /// # Arguments
+    /// * `axis` - The axis to index along
+    /// * `index` - A 1D integer tensor holding, for every slice of `source`, where to add it
+    /// * `source` - A tensor of the shape of the tensor, except of size `index.numel()` along `axis`
+    /// # Returns
+    /// A new tensor of the same shape
+    pub fn index_add(&self, axis: usize, index: &Tensor, source: &Tensor) -> Tensor {
+        self.try_index_add(axis, index, source).or_panic()
+    }
+
+    /// Adds the slices of `source` to a copy of the tensor, at the given indices along `axis`.
+    /// Returns an error if the axis or an index is out of bounds, or if the shapes do not match.
+    pub fn try_index_add(&self, axis: usize, index: &Tensor, source: &Tensor) -> Result<Tensor> {
+        check_index("index_add", axis, self.shape.len())?;
+        let mut shape = self.shape.clone();
+        shape[axis] = index.numel();
+        if source.shape != shape {
+            return Err(TensorError::ShapeMismatch {
+                op: "index_add",
+                lhs: shape,
+                rhs: source.shape.clone(),
+            });
+        }
+        let index = Tensor::expand_index("index_add", index, axis, &shape)?;
+        self.try_scatter_add(axis, &index, source)
+    }
+
+    /// Selects the elements where `mask` is true, broadcasting the tensor and the mask together.
+    /// Non-zero elements of `mask` are true.
+    /// # Arguments
+    /// * `mask` - The mask choosing the elements to keep
+    /// # Returns
+    /// A new 1D tensor holding the selected elements in row-major order
+    pub fn masked_select(&self, mask: &Tensor) -> Tensor {
+        self.try_masked_select(mask).or_panic()
+    }
+
+    /// Selects the elements where `mask` is true, broadcasting the tensor and the mask together.
+    /// Returns an error if the shapes cannot be broadcast together.
+    pub fn try_masked_select(&self, mask: &Tensor) -> Result<Tensor> {
+        let shape = Tensor::broadcast_shape(&self.shape, mask.shape())?;
+        let mask = mask.to_dtype(DType::Bool);
+        let positions = {
+            // The mask is released before the tensor is read, as they may share their storage
+            let mask_data = mask.data::<bool>();
+            StridedIter::new(&shape, &mask.broadcast_strides(&shape), mask.offset)
+                .enumerate()
+                .filter(|&(_, index)| mask_data[index])
+                .map(|(position, _)| position)
+                .collect::<Vec<usize>>()
+        };
+        let storage = dispatch_all!(self.dtype(), T => {
+            let data = self.data::<T>();
+            let indices =
+                StridedIter::new(&shape, &self.broadcast_strides(&shape), self.offset)
+                    .collect::<Vec<usize>>();
+            Storage::new(positions.iter().map(|&p| data[indices[p]]).collect::<Vec<T>>())
+        });
+
+        let requires_grad = self.records_grad();
+        let len = positions.len();
+
+        Ok(InternalTensor {
+            storage: Arc::new(storage),
+            shape: vec![len],
+            strides: vec![1],
+            offset: 0,
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(MaskedSelectGradFn::new(
+                    shape,
+                    positions,
+                    self.shape.clone(),
+                )))
+            } else {
+                None
+            },
+            parents: if requires_grad {
+                vec![self.clone()]
+            } else {
+                Vec::new()
+            },
+            requires_grad,
+        }
+        .into())
+    }
+}
@@ -1,85 +1,168 @@
+use crate::dispatch_numeric;
 use crate::linalg::autograd::grad_fn::matmul::MatMulGradFn;
+use crate::linalg::dtype::{DType, Numeric};
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype};
+use crate::linalg::gemm::{MatRef, gemm};
+use crate::linalg::parallel;
+use crate::linalg::strided::StridedIter;
 use crate::linalg::tensor::{InternalTensor, Storage, Tensor};
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
 
 impl Tensor {
+    /// Matrix product of two tensors, following the usual batched semantics:
+    /// - 2D operands are multiplied as matrices.
+    /// - A 1D left operand is treated as a row vector and a 1D right operand as a column
+    ///   vector, and the added dimension is removed from the result.
+    /// - Operands with more than two dimensions are treated as batches of matrices stored in the
+    ///   last two dimensions, and their leading (batch) dimensions are broadcast together.
+    ///
+    /// Panics if the inner dimensions do not match or if the batch dimensions cannot be broadcast.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let a = Tensor::new(vec![1.0; 2 * 3 * 4], &[2, 3, 4]);
+    /// let b = Tensor::new(vec![1.0; 4 * 5], &[4, 5]);
+    /// assert_eq!(a.matmul(&b).shape(), &[2, 3, 5]);
+    /// ```
     pub fn matmul(&self, other: &Tensor) -> Tensor {
-        let a = match self.shape().len() {
-            1 => self.unsqueeze(0), // [k] -> [1, k]
-            2 => self.clone(),
-            _ => panic!("matmul expects 1D or 2D tensor"),
-        };
+        self.try_matmul(other).or_panic()
+    }
+
+    /// Matrix product of two tensors, following the usual batched semantics.
+    /// Returns an error if the inner dimensions do not match or if the batch dimensions cannot be broadcast.
+    pub fn try_matmul(&self, other: &Tensor) -> Result<Tensor> {
+        let dtype = self.dtype().promote(other.dtype());
+        check_dtype("matmul", dtype, |dtype| dtype != DType::Bool)?;
+        let (lhs, rhs) = (self.to_dtype(dtype), other.to_dtype(dtype));
 
-        let b = match other.shape().len() {
-            1 => other.unsqueeze(1), // [k] -> [k, 1]
-            2 => other.clone(),
-            _ => panic!("matmul expects 1D or 2D tensor"),
+        let a = match lhs.shape().len() {
+            1 => lhs.unsqueeze(0), // [k] -> [1, k]
+            _ => lhs.clone(),
+        };
+        let b = match rhs.shape().len() {
+            1 => rhs.unsqueeze(1), // [k] -> [k, 1]
+            _ => rhs.clone(),
         };
 
-        assert_eq!(
-            a.shape().len(),
-            2,
-            "Left tensor must be 2D, got shape {:?}",
-            a.shape()
-        );
-        assert_eq!(
-            b.shape().len(),
-            2,
-            "Right tensor must be 2D, got shape {:?}",
-            b.shape()
-        );
-        assert_eq!(
-            a.shape()[1],
-            b.shape()[0],
-            "Inner dimensions must match: got {} vs {}",
-            a.shape()[1],
-            b.shape()[0]
-        );
+        let (a_rank, b_rank) = (a.shape.len(), b.shape.len());
+        let (m, k) = (a.shape[a_rank - 2], a.shape[a_rank - 1]);
+        let n = b.shape[b_rank - 1];
+        if k != b.shape[b_rank - 2] {
+            return Err(TensorError::ShapeMismatch {
+                op: "matmul: inner dimensions must match",
+                lhs: lhs.shape().to_vec(),
+                rhs: rhs.shape().to_vec(),
+            });
+        }
+        let batch = Tensor::broadcast_shape(&a.shape[..a_rank - 2], &b.shape[..b_rank - 2])
+            .map_err(|_| TensorError::ShapeMismatch {
+                op: "matmul: batch dimensions must broadcast",
+                lhs: lhs.shape().to_vec(),
+                rhs: rhs.shape().to_vec(),
+            })?;
 
-        let [m, k] = a.shape()[..] else {
-            panic!("Expected 2D shape")
-        };
-        let n = b.shape()[1];
+        // Broadcast batch dimensions get a zero stride, so a single matrix is reused across them
+        let nb = batch.len();
+        let a_strides = a.broadcast_strides(&[&batch[..], &[m, k]].concat());
+        let b_strides = b.broadcast_strides(&[&batch[..], &[k, n]].concat());
+        let a_offsets = StridedIter::new(&batch, &a_strides[..nb], a.offset).collect::<Vec<_>>();
+        let b_offsets = StridedIter::new(&batch, &b_strides[..nb], b.offset).collect::<Vec<_>>();
 
-        let mut result_data = vec![0.0; m * n];
+        let storage = dispatch_numeric!(dtype, T => {
+            let mut result_data = vec![T::zero(); a_offsets.len() * m * n];
 
-        for i in 0..m {
-            for j in 0..n {
-                let mut sum = 0.0;
-                for kk in 0..k {
-                    let a_idx = a.offset + i * a.strides[0] + kk * a.strides[1];
-                    let b_idx = b.offset + kk * b.strides[0] + j * b.strides[1];
-                    sum += a.storage.data[a_idx] * b.storage.data[b_idx];
+            // The rows of every matrix of the batch are split across threads. Each output element
+            // is accumulated in the same order whatever the split, so results stay deterministic.
+            let grain = (parallel::GRAIN / k.max(1)).max(1);
+            Tensor::with_data::<T, _>(&[&a, &b], |data| {
+            let (a_data, b_data) = (data[0], data[1]);
+            parallel::for_each_chunk(&mut result_data, n.max(1), grain, |start, out| {
+                let first_row = start / n.max(1);
+                let mut row = first_row;
+                while (row - first_row) * n < out.len() {
+                    let (matrix, i) = (row / m, row % m);
+                    let rows = (m - i).min(out.len() / n - (row - first_row));
+                    let a = MatRef {
+                        data: a_data,
+                        offset: a_offsets[matrix] + i * a_strides[nb],
+                        row_stride: a_strides[nb],
+                        col_stride: a_strides[nb + 1],
+                    };
+                    let b = MatRef {
+                        data: b_data,
+                        offset: b_offsets[matrix],
+                        row_stride: b_strides[nb],
+                        col_stride: b_strides[nb + 1],
+                    };
+                    let out = &mut out[(row - first_row) * n..(row - first_row + rows) * n];
+                    gemm(rows, n, k, a, b, out);
+                    row += rows;
                 }
-                result_data[i * n + j] = sum;
-            }
-        }
+            });
+            });
+            Storage::new(result_data)
+        });
 
-        let requires_grad = a.requires_grad || b.requires_grad;
-        InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: vec![m, n],
-            strides: vec![n, 1],
+        let shape = [&batch[..], &[m, n]].concat();
+        let requires_grad = a.records_grad() || b.records_grad();
+        let mut result: Tensor = InternalTensor {
+            storage: Arc::new(storage),
+            strides: Tensor::compute_strides(&shape),
+            shape,
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: if requires_grad {
-                Some(Rc::new(MatMulGradFn {
-                    lhs: a.clone(),
-                    rhs: b.clone(),
-                    lhs_shape: self.shape().to_vec(),
-                    rhs_shape: other.shape().to_vec(),
-                }))
+                Some(Arc::new(MatMulGradFn::new(a.clone(), b.clone())))
             } else {
                 None
             },
             parents: if requires_grad {
-                vec![self.clone(), other.clone()]
+                vec![a.clone(), b.clone()]
             } else {
                 Vec::new()
             },
             requires_grad,
         }
-        .into()
+        .into();
+
+        // Remove the dimensions added to 1D operands
+        if rhs.shape().len() == 1 {
+            result = result.squeeze_axis(result.shape.len() - 1);
+        }
+        if lhs.shape().len() == 1 && result.shape.len() > 1 {
+            result = result.squeeze_axis(result.shape.len() - 2);
+        }
+        Ok(result)
+    }
+
+    /// Batched matrix product of two 3D tensors of shapes `[batch, m, k]` and `[batch, k, n]`.
+    /// Unlike [`Tensor::matmul`], the batch dimensions are not broadcast.
+    /// # Returns
+    /// A tensor of shape `[batch, m, n]`
+    pub fn bmm(&self, other: &Tensor) -> Tensor {
+        self.try_bmm(other).or_panic()
+    }
+
+    /// Batched matrix product of two 3D tensors of shapes `[batch, m, k]` and `[batch, k, n]`.
+    /// Returns an error if an operand is not 3D or if the batch or inner dimensions do not match.
+    pub fn try_bmm(&self, other: &Tensor) -> Result<Tensor> {
+        for operand in [self, other] {
+            if operand.shape.len() != 3 {
+                return Err(TensorError::Rank {
+                    op: "bmm",
+                    expected: 3,
+                    actual: operand.shape.len(),
+                });
+            }
+        }
+        if self.shape[0] != other.shape[0] {
+            return Err(TensorError::ShapeMismatch {
+                op: "bmm: batch dimensions must match",
+                lhs: self.shape.clone(),
+                rhs: other.shape.clone(),
+            });
+        }
+        self.try_matmul(other)
     }
 }
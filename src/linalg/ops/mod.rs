@@ -1,6 +1,12 @@
 mod activation;
 mod binary;
+mod concat;
+mod creation;
+mod einsum;
+mod in_place;
+mod index;
 mod matmul;
-mod reduce;
+pub(crate) mod reduce;
 mod shape;
 mod unary;
+pub(crate) mod view;
@@ -1,323 +1,694 @@
-use crate::linalg::autograd::grad_fn::reduce::{MeanGradFn, SumAxisGradFn, SumGradFn};
+use crate::linalg::autograd::grad_fn::GradFn;
+use crate::linalg::autograd::grad_fn::reduce::{
+    ExtremumGradFn, LogSumExpGradFn, ProdGradFn, SumAxesGradFn,
+};
+use crate::linalg::dtype::{DType, Element, Float, Numeric};
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype, check_index};
+use crate::linalg::parallel;
+use crate::linalg::strided::StridedIter;
 use crate::linalg::tensor::{InternalTensor, Scalar, Storage, Tensor};
-use crate::not_implemented_grad_fn;
-use std::cell::RefCell;
-use std::rc::Rc;
+use crate::{dispatch_all, dispatch_float, dispatch_numeric};
+use std::sync::Arc;
+use std::sync::Mutex;
 
 impl Tensor {
+    /// Computes the mean over the given axes, removing them from the shape
     pub fn mean(&self, axes: &[usize]) -> Tensor {
-        let mut result_data = Vec::new();
-        let mut count = 1.0;
-        let mut reduced_shape = self.shape.clone();
-
-        for &axis in axes {
-            count *= self.shape[axis] as Scalar;
-            reduced_shape[axis] = 1;
-        }
-        reduced_shape.retain(|&dim| dim != 1);
-        if reduced_shape.is_empty() {
-            reduced_shape.push(1);
-        }
-
-        let total_elements: usize = reduced_shape.iter().product();
-        for i in 0..total_elements {
-            let mut sum = 0.0;
-            for j in 0..(self.shape.iter().product::<usize>() / total_elements) {
-                let index = i + j * total_elements;
-                sum += self.storage.data[index];
-            }
-            result_data.push(sum / count);
-        }
-        let strides = Tensor::compute_strides(&reduced_shape);
+        self.try_mean(axes).or_panic()
+    }
 
-        InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: reduced_shape,
-            strides,
-            offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: if self.requires_grad {
-                Some(Rc::new(MeanGradFn::new(axes.to_vec(), self.shape.clone())))
-            } else {
-                None
-            },
-            parents: if self.requires_grad {
-                vec![self.clone()]
-            } else {
-                vec![]
-            },
-            requires_grad: self.requires_grad,
-        }
-        .into()
+    /// Computes the mean over the given axes.
+    /// Returns an error if one of the axes is out of bounds.
+    pub fn try_mean(&self, axes: &[usize]) -> Result<Tensor> {
+        self.try_mean_axes(axes, false)
     }
 
     pub fn mean_scalar(&self) -> Tensor {
-        let total_elements: usize = self.shape.iter().product();
-        let sum: Scalar = self.storage.data.iter().cloned().sum();
-        let mean = sum / (total_elements as Scalar);
-
-        InternalTensor {
-            storage: Rc::new(Storage::new(vec![mean])),
-            shape: vec![1],
-            strides: vec![1],
-            offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: if self.requires_grad {
-                Some(Rc::new(MeanGradFn::new(
-                    (0..self.shape.len()).collect(),
-                    self.shape.clone(),
-                )))
-            } else {
-                None
-            },
-            parents: if self.requires_grad {
-                vec![self.clone()]
-            } else {
-                vec![]
-            },
-            requires_grad: self.requires_grad,
-        }
-        .into()
+        self.mean_axes(&self.all_axes(), false)
     }
 
     pub fn norm(&self) -> Tensor {
         self.square().sum().sqrt()
     }
 
-    /// Slices the tensor along the specified axis
-    /// and returns a new tensor containing the slice.
+    /// Computes the sum over `axes`
     /// # Arguments
-    /// * `axis` - The axis along which to slice the tensor.
-    /// * `start` - The starting index of the slice (inclusive).
-    /// * `len` - The length of the slice.
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
     /// # Returns
-    /// A new tensor containing the slice.
-    pub fn slice(&self, axis: usize, start: usize, len: usize) -> Tensor {
-        assert!(axis < self.shape.len(), "Axis out of bounds");
-        assert!(start + len <= self.shape[axis], "Invalid slice indices");
-
-        let mut new_shape = self.shape.clone();
-        new_shape[axis] = len;
-
-        let mut result_data = Vec::with_capacity(new_shape.iter().product());
-        let mut coords = vec![0; self.shape.len()];
-        for _ in 0..new_shape.iter().product() {
-            coords[axis] += start;
-            let idx = self.compute_flat_index(&coords);
-            result_data.push(self.storage.data[idx]);
-            coords[axis] -= start;
-            Tensor::increment_indices(&mut coords, &new_shape);
+    /// A tensor with the reduced axes removed, or kept with size one if `keepdim` is set. Boolean
+    /// tensors are summed as `I64`, counting their true elements.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    /// assert_eq!(tensor.sum_axes(&[1], false).as_slice(), &[6.0, 15.0]);
+    /// assert_eq!(tensor.sum_axes(&[0], true).shape(), &[1, 3]);
+    /// ```
+    pub fn sum_axes(&self, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_sum_axes(axes, keepdim).or_panic()
+    }
+
+    /// Computes the sum over `axes`.
+    /// Returns an error if one of the axes is out of bounds or repeated.
+    pub fn try_sum_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        if self.dtype() == DType::Bool {
+            return self.to_dtype(DType::I64).try_sum_axes(axes, keepdim);
         }
+        let (kept_shape, shape) = self.reduction_shapes("sum_axes", axes, keepdim)?;
+        let storage = dispatch_numeric!(self.dtype(), T => {
+            Storage::new(self.fold_lanes::<T>(axes, T::zero(), T::add))
+        });
+        Ok(self.reduced(
+            storage,
+            shape,
+            SumAxesGradFn::new(kept_shape, self.shape.clone()),
+        ))
+    }
 
-        let strides = Tensor::compute_strides(&new_shape);
+    /// Computes the mean over `axes`
+    /// # Arguments
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// A float tensor with the reduced axes removed, or kept with size one if `keepdim` is set
+    pub fn mean_axes(&self, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_mean_axes(axes, keepdim).or_panic()
+    }
 
-        InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: new_shape,
-            strides,
-            offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: not_implemented_grad_fn!("Slice"),
-            parents: vec![],
-            requires_grad: self.requires_grad,
-        }
-        .into()
+    /// Computes the mean over `axes`.
+    /// Returns an error if one of the axes is out of bounds or repeated.
+    pub fn try_mean_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        let input = self.to_float();
+        let sum = input.try_sum_axes(axes, keepdim)?;
+        Ok(sum / self.reduced_count(axes) as Scalar)
     }
 
-    /// Gathers elements from the tensor along specified axis using provided indices
+    /// Computes the product over `axes`
     /// # Arguments
-    /// * `axis` - The axis along which to gather elements
-    /// * `indices` - A vector containing the indices to gather
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
     /// # Returns
-    /// A new tensor containing the gathered elements
-    pub fn gather(&self, axis: usize, indices: &[usize]) -> Tensor {
-        let ndim = self.shape.len();
-        assert!(axis < ndim);
-
-        let axis_dim = self.shape[axis];
-        for &idx in indices {
-            assert!(idx < axis_dim, "gather index out of bounds");
+    /// A tensor with the reduced axes removed, or kept with size one if `keepdim` is set. Boolean
+    /// tensors are multiplied as `I64`.
+    pub fn prod_axes(&self, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_prod_axes(axes, keepdim).or_panic()
+    }
+
+    /// Computes the product over `axes`.
+    /// Returns an error if one of the axes is out of bounds or repeated.
+    pub fn try_prod_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        if self.dtype() == DType::Bool {
+            return self.to_dtype(DType::I64).try_prod_axes(axes, keepdim);
         }
+        let (kept_shape, shape) = self.reduction_shapes("prod_axes", axes, keepdim)?;
+        let storage = dispatch_numeric!(self.dtype(), T => {
+            Storage::new(self.fold_lanes::<T>(axes, T::one(), T::mul))
+        });
+        Ok(self.reduced(storage, shape, ProdGradFn::new(self.clone(), kept_shape)))
+    }
 
-        // Output shape
-        let mut out_shape = self.shape.clone();
-        out_shape[axis] = indices.len();
+    /// Computes the maximum over `axes`
+    /// # Arguments
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// A tensor with the reduced axes removed, or kept with size one if `keepdim` is set.
+    /// The gradient flows to the first maximum of every reduced slice.
+    pub fn max_axes(&self, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_max_axes(axes, keepdim).or_panic()
+    }
 
-        let out_strides = Tensor::compute_strides(&out_shape);
-        let total: usize = out_shape.iter().product();
+    /// Computes the maximum over `axes`.
+    /// Returns an error if one of the axes is out of bounds, repeated or empty.
+    pub fn try_max_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        self.extremum_axes("max_axes", axes, keepdim, false)
+    }
 
-        let in_strides = &self.strides;
+    /// Computes the minimum over `axes`
+    /// # Arguments
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// A tensor with the reduced axes removed, or kept with size one if `keepdim` is set.
+    /// The gradient flows to the first minimum of every reduced slice.
+    pub fn min_axes(&self, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_min_axes(axes, keepdim).or_panic()
+    }
 
-        let mut out_data = Vec::with_capacity(total);
+    /// Computes the minimum over `axes`.
+    /// Returns an error if one of the axes is out of bounds, repeated or empty.
+    pub fn try_min_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        self.extremum_axes("min_axes", axes, keepdim, true)
+    }
 
-        let mut coord = vec![0usize; ndim];
-        let mut in_offset = self.offset;
+    /// Computes the position of the maximum over `axes`
+    /// # Arguments
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// An `I64` tensor containing, for every reduced slice, the row-major index of its first
+    /// maximum within the reduced axes
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let tensor = Tensor::new(vec![4.0, 3.0, 6.0, 1.0, 5.0, 2.0], &[2, 3]);
+    /// assert_eq!(tensor.argmax_axes(&[1], true).shape(), &[2, 1]);
+    /// assert_eq!(tensor.argmax_axes(&[0, 1], false).as_typed_slice::<i64>(), &[2]);
+    /// ```
+    pub fn argmax_axes(&self, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_argmax_axes(axes, keepdim).or_panic()
+    }
 
-        for _ in 0..total {
-            let gather_idx = indices[coord[axis]];
-            let src_offset = in_offset + gather_idx * in_strides[axis];
+    /// Computes the position of the maximum over `axes`.
+    /// Returns an error if one of the axes is out of bounds, repeated or empty.
+    pub fn try_argmax_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        self.arg_extremum_axes("argmax_axes", axes, keepdim, false)
+    }
 
-            out_data.push(self.storage.data[src_offset]);
+    /// Computes the position of the minimum over `axes`
+    /// # Arguments
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// An `I64` tensor containing, for every reduced slice, the row-major index of its first
+    /// minimum within the reduced axes
+    pub fn argmin_axes(&self, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_argmin_axes(axes, keepdim).or_panic()
+    }
 
-            // increment output index
-            for d in (0..ndim).rev() {
-                coord[d] += 1;
-                if d != axis {
-                    in_offset += in_strides[d];
-                }
+    /// Computes the position of the minimum over `axes`.
+    /// Returns an error if one of the axes is out of bounds, repeated or empty.
+    pub fn try_argmin_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        self.arg_extremum_axes("argmin_axes", axes, keepdim, true)
+    }
 
-                if coord[d] < out_shape[d] {
-                    break;
-                }
+    /// Computes the variance over `axes`
+    /// # Arguments
+    /// * `axes` - The axes to reduce
+    /// * `unbiased` - Whether to divide by `n - 1` (Bessel's correction) instead of `n`
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// A float tensor with the reduced axes removed, or kept with size one if `keepdim` is set
+    pub fn var_axes(&self, axes: &[usize], unbiased: bool, keepdim: bool) -> Tensor {
+        self.try_var_axes(axes, unbiased, keepdim).or_panic()
+    }
 
-                coord[d] = 0;
-                if d != axis {
-                    in_offset -= in_strides[d] * out_shape[d];
-                }
-            }
-        }
+    /// Computes the variance over `axes`.
+    /// Returns an error if one of the axes is out of bounds or repeated.
+    pub fn try_var_axes(&self, axes: &[usize], unbiased: bool, keepdim: bool) -> Result<Tensor> {
+        let input = self.to_float();
+        let mean = input.try_mean_axes(axes, true)?;
+        let squared_deviation = (&input - &mean).square();
+        // Reducing fewer elements than the correction gives NaN, like any division of zero by zero
+        let count = self
+            .reduced_count(axes)
+            .saturating_sub(usize::from(unbiased));
+        Ok(squared_deviation.try_sum_axes(axes, keepdim)? / count as Scalar)
+    }
 
-        InternalTensor {
-            storage: Rc::new(Storage::new(out_data)),
-            shape: out_shape,
-            strides: out_strides,
-            offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: not_implemented_grad_fn!("Gather"),
-            parents: vec![],
-            requires_grad: self.requires_grad,
+    /// Computes the standard deviation over `axes`
+    /// # Arguments
+    /// * `axes` - The axes to reduce
+    /// * `unbiased` - Whether to divide by `n - 1` (Bessel's correction) instead of `n`
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// A float tensor with the reduced axes removed, or kept with size one if `keepdim` is set
+    pub fn std_axes(&self, axes: &[usize], unbiased: bool, keepdim: bool) -> Tensor {
+        self.try_std_axes(axes, unbiased, keepdim).or_panic()
+    }
+
+    /// Computes the standard deviation over `axes`.
+    /// Returns an error if one of the axes is out of bounds or repeated.
+    pub fn try_std_axes(&self, axes: &[usize], unbiased: bool, keepdim: bool) -> Result<Tensor> {
+        Ok(self.try_var_axes(axes, unbiased, keepdim)?.sqrt())
+    }
+
+    /// Computes the `p`-norm over `axes`
+    /// # Arguments
+    /// * `p` - The order of the norm, which may be `Scalar::INFINITY` for the maximum norm
+    /// * `axes` - The axes to reduce
+    /// * `keepdim` - Whether to keep the reduced axes with size one
+    /// # Returns
+    /// A float tensor with the reduced axes removed, or kept with size one if `keepdim` is set
+    pub fn norm_axes(&self, p: Scalar, axes: &[usize], keepdim: bool) -> Tensor {
+        self.try_norm_axes(p, axes, keepdim).or_panic()
+    }
+
+    /// Computes the `p`-norm over `axes`.
+    /// Returns an error if `p` is not positive, or if one of the axes is out of bounds or repeated.
+    pub fn try_norm_axes(&self, p: Scalar, axes: &[usize], keepdim: bool) -> Result<Tensor> {
+        if p.is_nan() || p <= 0.0 {
+            return Err(TensorError::InvalidArgument(format!(
+                "norm_axes: the order of the norm must be positive, got {p}"
+            )));
         }
-        .into()
+        let abs = self.to_float().abs();
+        if p == Scalar::INFINITY {
+            abs.try_max_axes(axes, keepdim)
+        } else if p == 1.0 {
+            abs.try_sum_axes(axes, keepdim)
+        } else if p == 2.0 {
+            Ok(abs.square().try_sum_axes(axes, keepdim)?.sqrt())
+        } else {
+            Ok(abs.pow(p).try_sum_axes(axes, keepdim)?.pow(1.0 / p))
+        }
+    }
+
+    /// Slices the tensor along the specified axis
+    /// and returns a view of the slice, sharing the storage of the tensor.
+    /// Equivalent to [`Tensor::narrow`].
+    /// # Arguments
+    /// * `axis` - The axis along which to slice the tensor.
+    /// * `start` - The starting index of the slice (inclusive).
+    /// * `len` - The length of the slice.
+    /// # Returns
+    /// A view containing the slice.
+    pub fn slice(&self, axis: usize, start: usize, len: usize) -> Tensor {
+        self.try_slice(axis, start, len).or_panic()
+    }
+
+    /// Slices the tensor along the specified axis.
+    /// Returns an error if the axis or the slice bounds are out of range.
+    pub fn try_slice(&self, axis: usize, start: usize, len: usize) -> Result<Tensor> {
+        self.try_narrow(axis, start, len)
     }
 
     /// Computes the indices of the maximum value in the tensor
     /// # Arguments
     /// * `axis` - The axis along which to compute the argmax
     /// # Returns
-    /// A vector containing the indices of the maximum values along the specified axis
+    /// An `I64` tensor containing the indices of the maximum values along the specified axis,
+    /// with the reduced axis removed from the shape
     /// # Example
     /// ```rust
     /// use nn_rs::linalg::tensor::Tensor;
     /// let tensor = Tensor::new(vec![4.0, 3.0, 6.0, 1.0, 5.0, 2.0], &vec![2, 3]);
     /// // 4 3 6
     /// // 1 5 2
-    /// assert_eq!(tensor.argmax_axis(0), vec![0, 1, 0]); // Max values in each column are 4, 5, 6
-    /// assert_eq!(tensor.argmax_axis(1), vec![2, 1]);    // Max values in each row are 6, 5
+    /// assert_eq!(tensor.argmax_axis(0).as_typed_slice::<i64>(), &[0, 1, 0]); // Max values in each column are 4, 5, 6
+    /// assert_eq!(tensor.argmax_axis(1).as_typed_slice::<i64>(), &[2, 1]);    // Max values in each row are 6, 5
     /// ```
-    pub fn argmax_axis(&self, axis: usize) -> Vec<usize> {
-        assert!(axis < self.shape.len(), "Axis out of bounds");
-        // find indices of max values along given axis
-        let mut result_indices = Vec::new();
-        let outer_dim = self.shape.iter().take(axis).product::<usize>();
-        let inner_dim = self.shape.iter().skip(axis + 1).product::<usize>();
-        let axis_dim = self.shape[axis];
-        for outer in 0..outer_dim {
-            for inner in 0..inner_dim {
-                let mut max_index = 0;
-                let mut max_value = Scalar::MIN;
-                for axis_index in 0..axis_dim {
-                    let index = outer * axis_dim * inner_dim + axis_index * inner_dim + inner;
-                    let value = self.storage.data[index];
-                    if value > max_value {
-                        max_value = value;
-                        max_index = axis_index;
-                    }
-                }
-                result_indices.push(max_index);
+    pub fn argmax_axis(&self, axis: usize) -> Tensor {
+        self.try_argmax_axis(axis).or_panic()
+    }
+
+    /// Computes the indices of the maximum value along the given axis.
+    /// Returns an error if the axis is out of bounds or empty.
+    pub fn try_argmax_axis(&self, axis: usize) -> Result<Tensor> {
+        self.try_argmax_axes(&[axis], false)
+    }
+
+    /// Encodes integer class indices as one-hot vectors along a new trailing dimension.
+    /// # Arguments
+    /// * `num_classes` - The number of classes, i.e. the size of the new dimension
+    /// # Returns
+    /// A floating point tensor of shape `[..self.shape, num_classes]`
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let labels = Tensor::from_vec(vec![2i64, 0], &[2]);
+    /// assert_eq!(labels.one_hot(3).as_slice(), &[0.0, 0.0, 1.0, 1.0, 0.0, 0.0]);
+    /// ```
+    pub fn one_hot(&self, num_classes: usize) -> Tensor {
+        self.try_one_hot(num_classes).or_panic()
+    }
+
+    /// Encodes integer class indices as one-hot vectors along a new trailing dimension.
+    /// Returns an error if the tensor is not integral or if a class index is out of bounds.
+    pub fn try_one_hot(&self, num_classes: usize) -> Result<Tensor> {
+        check_dtype("one_hot", self.dtype(), |dtype| !dtype.is_floating_point())?;
+        let classes = self.to_dtype(DType::I64);
+        let mut data = vec![0.0 as Scalar; self.numel() * num_classes];
+        for (i, class) in classes.values::<i64>().enumerate() {
+            if !(0..num_classes as i64).contains(&class) {
+                return Err(TensorError::InvalidArgument(format!(
+                    "one_hot: class index {class} out of bounds for {num_classes} classes"
+                )));
             }
+            data[i * num_classes + class as usize] = 1.0;
         }
-        result_indices
+        let mut shape = self.shape.clone();
+        shape.push(num_classes);
+        Tensor::try_new(data, &shape)
     }
 
     /// Computes the maximum value in the tensor
     /// # Returns
     /// The maximum value
     pub fn max(&self) -> Tensor {
-        let max_value = self
-            .storage
-            .data
-            .iter()
-            .cloned()
-            .fold(Scalar::MIN, Scalar::max);
-        InternalTensor {
-            storage: Rc::new(Storage::new(vec![max_value])),
-            shape: vec![1],
-            strides: vec![1],
-            offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: not_implemented_grad_fn!("max"),
-            parents: vec![],
-            requires_grad: self.requires_grad,
-        }
-        .into()
+        self.max_axes(&self.all_axes(), false)
+    }
+
+    /// Computes the minimum value in the tensor
+    /// # Returns
+    /// The minimum value
+    pub fn min(&self) -> Tensor {
+        self.min_axes(&self.all_axes(), false)
     }
 
     /// Computes the sum of all elements in the tensor
     /// # Returns
     /// A tensor containing the sum of all elements
     pub fn sum(&self) -> Tensor {
-        let sum_value: Scalar = self.storage.data.iter().cloned().sum();
+        self.sum_axes(&self.all_axes(), false)
+    }
 
-        InternalTensor {
-            storage: Rc::new(Storage::new(vec![sum_value])),
-            shape: vec![1],
-            strides: vec![1],
+    /// Computes `log(sum(exp(x)))` along `axis` without overflowing for large inputs
+    /// # Arguments
+    /// * `axis` - The axis to reduce
+    /// * `keepdim` - Whether to keep the reduced axis with size one
+    /// # Returns
+    /// A float tensor with the reduced axis removed, or kept with size one if `keepdim` is set
+    pub fn logsumexp(&self, axis: usize, keepdim: bool) -> Tensor {
+        self.try_logsumexp(axis, keepdim).or_panic()
+    }
+
+    /// Computes `log(sum(exp(x)))` along `axis`.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_logsumexp(&self, axis: usize, keepdim: bool) -> Result<Tensor> {
+        check_index("logsumexp", axis, self.shape.len())?;
+        let input = self.to_float();
+        let mut kept_shape = input.shape.clone();
+        kept_shape[axis] = 1;
+        let mut reduced_shape = kept_shape.clone();
+        if !keepdim {
+            reduced_shape.remove(axis);
+            if reduced_shape.is_empty() {
+                reduced_shape.push(1);
+            }
+        }
+
+        let outer = input.shape[..axis].iter().product();
+        let len = input.shape[axis];
+        let inner = input.shape[axis + 1..].iter().product();
+        let storage = dispatch_float!(input.dtype(), T => {
+            Storage::new(logsumexp::<T>(&input.contiguous_data(), outer, len, inner))
+        });
+
+        let requires_grad = input.records_grad();
+
+        let mut out: Tensor = InternalTensor {
+            storage: Arc::new(storage),
+            strides: Tensor::compute_strides(&reduced_shape),
+            shape: reduced_shape,
             offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: if self.requires_grad {
-                Some(Rc::new(SumGradFn::new(self.shape.clone())))
-            } else {
-                None
-            },
-            parents: if self.requires_grad {
-                vec![self.clone()]
-            } else {
-                vec![]
-            },
-            requires_grad: self.requires_grad,
+            grad: Mutex::new(None),
+            grad_fn: None,
+            parents: Vec::new(),
+            requires_grad,
         }
-        .into()
+        .into();
+        if requires_grad {
+            let output = out.detached().reshape(&kept_shape);
+            out.set_grad_metadata(
+                Arc::new(LogSumExpGradFn::new(input.clone(), output, axis)),
+                vec![input.clone()],
+            );
+        }
+        Ok(out)
     }
 
+    /// Computes the sum along the given axis, removing it from the shape
     pub fn sum_axis(&self, axis: usize) -> Tensor {
-        assert!(axis < self.shape.len(), "Axis out of bounds");
-        let mut result_data = Vec::new();
-        let mut reduced_shape = self.shape.clone();
-        reduced_shape[axis] = 1;
-        reduced_shape.retain(|&dim| dim != 1);
-        if reduced_shape.is_empty() {
-            reduced_shape.push(1);
+        self.try_sum_axis(axis).or_panic()
+    }
+
+    /// Computes the sum along the given axis.
+    /// Returns an error if the axis is out of bounds.
+    pub fn try_sum_axis(&self, axis: usize) -> Result<Tensor> {
+        self.try_sum_axes(&[axis], false)
+    }
+
+    fn all_axes(&self) -> Vec<usize> {
+        (0..self.shape.len()).collect()
+    }
+
+    /// Returns the number of elements reduced into every output element by a reduction over `axes`.
+    fn reduced_count(&self, axes: &[usize]) -> usize {
+        axes.iter().map(|&axis| self.shape[axis]).product()
+    }
+
+    /// Validates the axes of a reduction and returns the shape of the tensor with the reduced axes
+    /// set to one, along with the shape of the output.
+    fn reduction_shapes(
+        &self,
+        op: &'static str,
+        axes: &[usize],
+        keepdim: bool,
+    ) -> Result<(Vec<usize>, Vec<usize>)> {
+        let mut kept_shape = self.shape.clone();
+        for (i, &axis) in axes.iter().enumerate() {
+            check_index(op, axis, self.shape.len())?;
+            if axes[..i].contains(&axis) {
+                return Err(TensorError::InvalidArgument(format!(
+                    "{op}: axis {axis} appears more than once in {axes:?}"
+                )));
+            }
+            kept_shape[axis] = 1;
+        }
+        if keepdim {
+            return Ok((kept_shape.clone(), kept_shape));
         }
+        let mut shape = (0..self.shape.len())
+            .filter(|axis| !axes.contains(axis))
+            .map(|axis| self.shape[axis])
+            .collect::<Vec<usize>>();
+        if shape.is_empty() {
+            shape.push(1);
+        }
+        Ok((kept_shape, shape))
+    }
+
+    /// Orders the axes of the tensor with the reduced axes last, so that the elements reduced into
+    /// every output element form a contiguous range, or lane, of the reordered logical order.
+    fn lane_order(&self, axes: &[usize]) -> Vec<usize> {
+        let mut reduced = axes.to_vec();
+        reduced.sort_unstable();
+        (0..self.shape.len())
+            .filter(|axis| !axes.contains(axis))
+            .chain(reduced)
+            .collect()
+    }
+
+    /// Iterates over the storage positions of the elements in the order given by [`Self::lane_order`].
+    fn lane_iter(&self, order: &[usize]) -> StridedIter {
+        let shape = order
+            .iter()
+            .map(|&axis| self.shape[axis])
+            .collect::<Vec<usize>>();
+        let strides = order
+            .iter()
+            .map(|&axis| self.strides[axis])
+            .collect::<Vec<usize>>();
+        StridedIter::new(&shape, &strides, self.offset)
+    }
+
+    /// Reduces the elements of every lane with the associative operation `op`, whose identity is
+    /// `identity`. Lanes are split into blocks whose results are combined in a fixed order, so the
+    /// result does not depend on the number of threads.
+    fn fold_lanes<T: Element>(
+        &self,
+        axes: &[usize],
+        identity: T,
+        op: impl Fn(T, T) -> T + Sync,
+    ) -> Vec<T> {
+        let order = self.lane_order(axes);
+        let lanes = order[..order.len() - axes.len()]
+            .iter()
+            .map(|&axis| self.shape[axis])
+            .product();
+        let len = self.reduced_count(axes);
+        let data = self.data::<T>();
+        let positions = self.lane_iter(&order);
+        parallel::reduce_lanes(
+            lanes,
+            len,
+            |lane, range| {
+                positions
+                    .clone()
+                    .starting_at(lane * len + range.start)
+                    .take(range.len())
+                    .fold(identity, |acc, index| op(acc, data[index]))
+            },
+            &op,
+        )
+    }
 
-        let total_elements: usize = reduced_shape.iter().product();
-        for i in 0..total_elements {
-            let mut sum = 0.0;
-            for j in 0..(self.shape.iter().product::<usize>() / total_elements) {
-                let index = i + j * total_elements;
-                sum += self.storage.data[index];
+    /// Finds the first maximum (or minimum if `min` is set) of every lane, returning its logical
+    /// position in the tensor, its row-major index within the reduced axes and its value.
+    fn arg_extremum<T: Element>(&self, axes: &[usize], min: bool) -> Vec<(usize, usize, T)> {
+        let better = |value: T, current: T| {
+            if min {
+                value < current
+            } else {
+                value > current
             }
-            result_data.push(sum);
+        };
+        let order = self.lane_order(axes);
+        let lanes = order[..order.len() - axes.len()]
+            .iter()
+            .map(|&axis| self.shape[axis])
+            .product();
+        let len = self.reduced_count(axes);
+        let data = self.data::<T>();
+        let positions = self.lane_iter(&order);
+        let extrema = parallel::reduce_lanes(
+            lanes,
+            len,
+            |lane, range| {
+                let values = positions
+                    .clone()
+                    .starting_at(lane * len + range.start)
+                    .map(|index| data[index]);
+                (range.start..range.end)
+                    .zip(values)
+                    .fold(None, |best, (index, value)| match best {
+                        Some((_, current)) if !better(value, current) => best,
+                        _ => Some((index, value)),
+                    })
+            },
+            // Ties keep the earlier block, like a sequential scan would
+            |left, right| match (left, right) {
+                (Some((_, current)), Some((index, value))) if better(value, current) => {
+                    Some((index, value))
+                }
+                (None, right) => right,
+                (left, _) => left,
+            },
+        );
+
+        // Map the position in the lane order back to the logical order of the tensor
+        let logical_strides = Tensor::compute_strides(&self.shape);
+        let shape = order
+            .iter()
+            .map(|&axis| self.shape[axis])
+            .collect::<Vec<usize>>();
+        let strides = order
+            .iter()
+            .map(|&axis| logical_strides[axis])
+            .collect::<Vec<usize>>();
+        extrema
+            .into_iter()
+            .enumerate()
+            .map(|(lane, extremum)| {
+                let (index, value) = extremum.expect("reduced axes are not empty");
+                let position = StridedIter::new(&shape, &strides, 0)
+                    .starting_at(lane * len + index)
+                    .next()
+                    .expect("position is in bounds");
+                (position, index, value)
+            })
+            .collect()
+    }
+
+    fn check_reduced_not_empty(&self, op: &'static str, axes: &[usize]) -> Result<()> {
+        if self.reduced_count(axes) == 0 {
+            return Err(TensorError::InvalidArgument(format!(
+                "{op}: cannot reduce over an empty axis"
+            )));
         }
-        let strides = Tensor::compute_strides(&reduced_shape);
+        Ok(())
+    }
+
+    fn extremum_axes(
+        &self,
+        op: &'static str,
+        axes: &[usize],
+        keepdim: bool,
+        min: bool,
+    ) -> Result<Tensor> {
+        let (_, shape) = self.reduction_shapes(op, axes, keepdim)?;
+        self.check_reduced_not_empty(op, axes)?;
+        let (storage, positions) = dispatch_all!(self.dtype(), T => {
+            let extrema = self.arg_extremum::<T>(axes, min);
+            let values = extrema.iter().map(|&(_, _, value)| value).collect::<Vec<T>>();
+            let positions = extrema.iter().map(|&(position, _, _)| position).collect();
+            (Storage::new(values), positions)
+        });
+        Ok(self.reduced(
+            storage,
+            shape,
+            ExtremumGradFn::new(self.shape.clone(), positions),
+        ))
+    }
+
+    fn arg_extremum_axes(
+        &self,
+        op: &'static str,
+        axes: &[usize],
+        keepdim: bool,
+        min: bool,
+    ) -> Result<Tensor> {
+        let (_, shape) = self.reduction_shapes(op, axes, keepdim)?;
+        self.check_reduced_not_empty(op, axes)?;
+        let indices = dispatch_all!(self.dtype(), T => {
+            self.arg_extremum::<T>(axes, min)
+                .into_iter()
+                .map(|(_, index, _)| index as i64)
+                .collect::<Vec<i64>>()
+        });
+        Tensor::try_from_vec(indices, &shape)
+    }
+
+    /// Wraps the result of a reduction of the tensor, attaching `grad_fn` if gradients are required.
+    fn reduced(
+        &self,
+        storage: Storage,
+        shape: Vec<usize>,
+        grad_fn: impl GradFn + 'static,
+    ) -> Tensor {
+        let requires_grad = self.records_grad();
 
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: reduced_shape,
-            strides,
+            storage: Arc::new(storage),
+            strides: Tensor::compute_strides(&shape),
+            shape,
             offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: if self.requires_grad {
-                Some(Rc::new(SumAxisGradFn::new(self.shape.clone())))
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(grad_fn))
             } else {
                 None
             },
-            parents: if self.requires_grad {
+            parents: if requires_grad {
                 vec![self.clone()]
             } else {
-                vec![]
+                Vec::new()
             },
-            requires_grad: self.requires_grad,
+            requires_grad,
         }
         .into()
     }
 }
+
+/// Returns, for every element of a tensor of shape `shape` in logical order, the position of the
+/// output element it reduces into. `kept_shape` is `shape` with the reduced axes set to one.
+pub(crate) fn reduction_slots(shape: &[usize], kept_shape: &[usize]) -> StridedIter {
+    let strides = Tensor::compute_strides(kept_shape)
+        .into_iter()
+        .zip(kept_shape)
+        .map(|(stride, &dim)| if dim == 1 { 0 } else { stride })
+        .collect::<Vec<usize>>();
+    StridedIter::new(shape, &strides, 0)
+}
+
+/// Reduces every lane of `len` elements spaced `inner` apart with the log-sum-exp trick.
+/// `data` holds `outer` blocks of `len * inner` elements, so empty lanes still give a result.
+fn logsumexp<T: Float>(data: &[T], outer: usize, len: usize, inner: usize) -> Vec<T> {
+    let mut result = Vec::with_capacity(outer * inner);
+    for o in 0..outer {
+        for i in 0..inner {
+            let lane = (0..len).map(|k| data[(o * len + k) * inner + i]);
+            let max_val = lane.clone().fold(T::neg_infinity(), T::max);
+            if max_val == T::neg_infinity() {
+                // Avoids `-inf - -inf` for lanes that are empty or entirely `-inf`
+                result.push(max_val);
+                continue;
+            }
+            let exp_sum = lane.fold(T::zero(), |sum, x| sum.add(x.sub(max_val).exp()));
+            result.push(exp_sum.ln().add(max_val));
+        }
+    }
+    result
+}
@@ -1,43 +1,53 @@
-use crate::linalg::autograd::grad_fn::shape::TransposeGradFn;
+use crate::dispatch_all;
+use crate::linalg::autograd::grad_fn::shape::{ContiguousGradFn, TransposeGradFn};
+use crate::linalg::dtype::Element;
+use crate::linalg::error::{OrPanic, Result, TensorError};
 use crate::linalg::tensor::Tensor;
-use crate::linalg::tensor::{InternalTensor, Scalar};
-use crate::not_implemented_grad_fn;
-use std::cell::RefCell;
-use std::rc::Rc;
+use crate::linalg::tensor::{InternalTensor, Scalar, Storage, TensorSlice};
+use std::sync::Arc;
+use std::sync::Mutex;
 
 impl Tensor {
     /// Transposes a 2D tensor_old by swapping its rows and columns.
     /// Returns a new tensor_old that is the transposed version of the original tensor_old, with updated shape and strides, without modifying the original tensor_old's data.
     pub fn transpose(&self) -> Tensor {
-        assert_eq!(
-            self.shape.len(),
-            2,
-            "Transpose only supported for 2D tensors"
-        );
+        self.try_transpose().or_panic()
+    }
+
+    /// Transposes a 2D tensor_old by swapping its rows and columns.
+    /// Returns an error if the tensor_old is not 2D.
+    pub fn try_transpose(&self) -> Result<Tensor> {
+        if self.shape.len() != 2 {
+            return Err(TensorError::Rank {
+                op: "transpose",
+                expected: 2,
+                actual: self.shape.len(),
+            });
+        }
         let new_shape = vec![self.shape[1], self.shape[0]];
         let mut new_strides = vec![0; 2];
         new_strides[0] = self.strides[1];
         new_strides[1] = self.strides[0];
 
-        InternalTensor {
-            storage: Rc::clone(&self.storage),
+        Ok(InternalTensor {
+            storage: Arc::clone(&self.storage),
             shape: new_shape,
             strides: new_strides,
             offset: self.offset,
-            grad: RefCell::new(None),
-            grad_fn: if self.requires_grad {
-                Some(Rc::new(TransposeGradFn))
+            grad: Mutex::new(None),
+            grad_fn: if self.records_grad() {
+                Some(Arc::new(TransposeGradFn))
             } else {
                 None
             },
-            parents: if self.requires_grad {
+            parents: if self.records_grad() {
                 vec![self.clone()]
             } else {
                 Vec::new()
             },
-            requires_grad: self.requires_grad,
+            requires_grad: self.records_grad(),
         }
-        .into()
+        .into())
     }
 
     /// Reshapes the tensor_old to the specified shape without changing the underlying data.
@@ -46,71 +56,94 @@ impl Tensor {
     ///
     /// Returns a new tensor_old with the specified shape.
     pub fn reshape(self, shape: &[usize]) -> Self {
-        assert_eq!(
-            self.shape.iter().product::<usize>(),
-            shape.iter().product::<usize>(),
-            "Total number of elements must remain the same when reshaping"
-        );
+        self.try_reshape(shape).or_panic()
+    }
+
+    /// Reshapes the tensor_old to the specified shape without changing the underlying data.
+    /// Returns an error if the total number of elements differs.
+    pub fn try_reshape(self, shape: &[usize]) -> Result<Self> {
+        if self.shape.iter().product::<usize>() != shape.iter().product::<usize>() {
+            return Err(TensorError::ShapeMismatch {
+                op: "reshape",
+                lhs: self.shape.clone(),
+                rhs: shape.to_vec(),
+            });
+        }
+        // Only a contiguous layout can be reinterpreted with new strides
+        Ok(self.contiguous().view(|layout| {
+            layout.shape = shape.to_vec();
+            layout.strides = Self::compute_strides(shape);
+        }))
+    }
+
+    /// Returns a tensor_old with the same values laid out contiguously in row-major order.
+    /// Returns the tensor_old itself if it is already contiguous, and a copy otherwise (e.g. for
+    /// transposed or sliced views).
+    pub fn contiguous(&self) -> Tensor {
+        if self.is_contiguous() {
+            return self.clone();
+        }
+        let storage = dispatch_all!(self.dtype(), T => Storage::new(self.map_data::<T, T>(|x| x)));
+
         InternalTensor {
-            storage: Rc::clone(&self.storage),
-            shape: shape.to_vec(),
-            strides: Self::compute_strides(shape),
-            offset: self.offset,
-            grad: RefCell::new(None),
-            grad_fn: not_implemented_grad_fn!("reshape"),
-            parents: vec![],
-            requires_grad: self.requires_grad,
+            storage: Arc::new(storage),
+            shape: self.shape.clone(),
+            strides: Self::compute_strides(&self.shape),
+            offset: 0,
+            grad: Mutex::new(None),
+            grad_fn: if self.records_grad() {
+                Some(Arc::new(ContiguousGradFn))
+            } else {
+                None
+            },
+            parents: if self.records_grad() {
+                vec![self.clone()]
+            } else {
+                Vec::new()
+            },
+            requires_grad: self.records_grad(),
         }
         .into()
     }
 
-    /// Returns the underlying data of the tensor_old as a slice. If the tensor_old is not contiguous or has a non-zero offset, this will panic.
+    /// Returns the data of the tensor_old as a slice. If the tensor_old is not contiguous, this will panic.
+    ///
+    /// Returns a slice of the tensor_old's data, which holds a read lock on it until it is dropped.
+    pub fn as_slice(&self) -> TensorSlice<'_, Scalar> {
+        self.as_typed_slice()
+    }
+
+    /// Returns the data of the tensor_old as a slice of `T`. If the tensor_old is not contiguous
+    /// or if `T` does not match its dtype, this will panic.
+    /// Use [`Tensor::contiguous`] first to read a transposed or sliced view.
     ///
-    /// Returns a slice of the tensor_old's data.
-    pub fn as_slice(&self) -> &[Scalar] {
+    /// Returns a slice of the tensor_old's data. In-place operations on tensors sharing the data
+    /// wait until it is dropped.
+    pub fn as_typed_slice<T: Element>(&self) -> TensorSlice<'_, T> {
         assert!(
             self.is_contiguous(),
             "Tensor must be contiguous to get as slice"
         );
-        assert_eq!(self.offset, 0, "Tensor offset must be zero to get as slice");
-        &self.storage.data
+        self.data()
+            .narrowed(self.offset..self.offset + self.numel())
     }
 
-    /// Returns the underlying data of the tensor_old as a mutable slice. If the tensor_old is not contiguous or has a non-zero offset, this will panic.
+    /// Returns the data of the tensor_old as a mutable slice. If the tensor_old is not contiguous, this will panic.
     /// If the storage is shared, it will create a unique copy before returning the mutable slice.
     ///
     /// Returns a mutable slice of the tensor_old's data.
-    /// Clones the tensor's storage if it's shared with other tensors (copy-on-write).
-    pub fn as_mut_slice(&mut self) -> &mut [f32] {
-        // Rc::make_mut clones if refcount > 1
-        let inner = Rc::make_mut(&mut self.0);
-        let storage = Rc::make_mut(&mut inner.storage);
-        &mut storage.data
-    }
-
-    pub(crate) fn unsqueeze(&self, dim: usize) -> Tensor {
-        let mut shape = self.shape.clone();
-        let mut strides = self.strides.clone();
-
-        let stride = if dim < self.strides.len() {
-            self.strides[dim] * self.shape[dim]
-        } else {
-            1
-        };
-
-        shape.insert(dim, 1);
-        strides.insert(dim, stride);
-
-        InternalTensor {
-            storage: self.storage.clone(),
-            shape,
-            strides,
-            offset: self.offset,
-            grad: RefCell::new(None),
-            grad_fn: not_implemented_grad_fn!("unsqueeze"),
-            parents: vec![],
-            requires_grad: self.requires_grad,
-        }
-        .into()
+    /// Clones the tensor's storage if it's shared with other tensors (copy-on-write), and counts
+    /// as a write in [`Tensor::version`].
+    pub fn as_mut_slice(&mut self) -> &mut [Scalar] {
+        assert!(
+            self.is_contiguous(),
+            "Tensor must be contiguous to get as mutable slice"
+        );
+        let (offset, numel) = (self.offset, self.numel());
+        // Arc::make_mut clones if refcount > 1
+        let inner = Arc::make_mut(&mut self.0);
+        let storage = Arc::make_mut(&mut inner.storage);
+        storage.bump_version();
+        &mut storage.as_mut_slice()[offset..offset + numel]
     }
 }
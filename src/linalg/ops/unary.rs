@@ -1,33 +1,29 @@
 use crate::linalg::autograd::grad_fn::unary::{
-    AbsGradFn, ClampGradFn, ExpGradFn, LogGradFn, NegGradFn, PowGradFn,
+    AbsGradFn, CastGradFn, ClampGradFn, ExpGradFn, LogGradFn, NegGradFn, PowGradFn, SignGradFn,
 };
+use crate::linalg::dtype::{DType, Element, Numeric};
 use crate::linalg::tensor::{InternalTensor, Scalar, Storage, Tensor};
-use std::cell::RefCell;
+use crate::{dispatch_all, dispatch_float, dispatch_numeric};
 use std::ops::Neg;
-use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
 
 impl Neg for &Tensor {
     type Output = Tensor;
 
     fn neg(self) -> Self::Output {
-        let result_data = self
-            .storage
-            .data
-            .iter()
-            .copied()
-            .map(|x| -x)
-            .collect::<Vec<Scalar>>();
+        let storage = dispatch_numeric!(self.dtype(), T => Storage::new(self.map_data::<T, T>(<T as Numeric>::neg)));
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = self.records_grad();
 
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
+            storage: Arc::new(storage),
             shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            strides: Tensor::compute_strides(&self.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: if requires_grad {
-                Some(Rc::new(NegGradFn))
+                Some(Arc::new(NegGradFn))
             } else {
                 None
             },
@@ -50,35 +46,88 @@ impl Neg for Tensor {
     }
 }
 
+/// Builds the `{-1, 0, 1}` sign of each element.
+fn signum<T: Numeric>(x: T) -> T {
+    if x > T::zero() {
+        T::one()
+    } else if x < T::zero() {
+        Numeric::neg(T::one())
+    } else {
+        T::zero()
+    }
+}
+
 impl Tensor {
+    /// Converts the tensor to the given dtype.
+    /// Returns the tensor itself if it already has the requested dtype. Conversions between floating
+    /// point dtypes are differentiable, conversions to other dtypes detach the result from the graph.
+    /// # Arguments
+    /// * `dtype` - The target dtype
+    /// # Returns
+    /// A tensor containing the converted values
+    pub fn to_dtype(&self, dtype: DType) -> Tensor {
+        if self.dtype() == dtype {
+            return self.clone();
+        }
+        let storage = dispatch_all!(self.dtype(), S => dispatch_all!(dtype, D => {
+            Storage::new(self.map_data::<S, D>(|x| D::from_f64(x.to_f64())))
+        }));
+
+        let requires_grad = self.records_grad() && dtype.is_floating_point();
+
+        InternalTensor {
+            storage: Arc::new(storage),
+            shape: self.shape.clone(),
+            strides: Tensor::compute_strides(&self.shape),
+            offset: 0,
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(CastGradFn::new(self.dtype())))
+            } else {
+                None
+            },
+            parents: if requires_grad {
+                vec![self.clone()]
+            } else {
+                Vec::new()
+            },
+            requires_grad,
+        }
+        .into()
+    }
+
+    /// Converts the tensor to a floating point dtype if it is not one already.
+    pub(crate) fn to_float(&self) -> Tensor {
+        self.to_dtype(self.dtype().promote_scalar())
+    }
+
     /// Raises each element of the tensor to the given exponent
     /// # Arguments
     /// * `exponent` - The exponent to raise each element to
     /// # Returns
     /// A tensor containing the results
     pub fn pow(&self, exponent: Scalar) -> Tensor {
-        let result_data = self
-            .storage
-            .data
-            .iter()
-            .map(|&x| x.powf(exponent))
-            .collect::<Vec<Scalar>>();
+        let input = self.to_float();
+        let storage = dispatch_float!(input.dtype(), T => {
+            let exponent = T::from_f64(exponent as f64);
+            Storage::new(input.map_data::<T, T>(|x| x.powf(exponent)))
+        });
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = input.records_grad();
 
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            storage: Arc::new(storage),
+            shape: input.shape.clone(),
+            strides: Tensor::compute_strides(&input.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: if requires_grad {
-                Some(Rc::new(PowGradFn::new(self.clone(), exponent)))
+                Some(Arc::new(PowGradFn::new(input.clone(), exponent)))
             } else {
                 None
             },
             parents: if requires_grad {
-                vec![self.clone()]
+                vec![input.clone()]
             } else {
                 Vec::new()
             },
@@ -105,37 +154,20 @@ impl Tensor {
     /// # Returns
     /// A tensor containing the absolute values
     pub fn abs(&self) -> Tensor {
-        let result_data = self
-            .storage
-            .data
-            .iter()
-            .map(|&x| x.abs())
-            .collect::<Vec<Scalar>>();
+        let storage = dispatch_numeric!(self.dtype(), T => {
+            Storage::new(self.map_data::<T, T>(|x| if x < T::zero() { Numeric::neg(x) } else { x }))
+        });
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = self.records_grad();
 
-        let mask = self
-            .storage
-            .data
-            .iter()
-            .map(|&x| {
-                if x > 0.0 {
-                    1.0
-                } else if x < 0.0 {
-                    -1.0
-                } else {
-                    0.0
-                }
-            })
-            .collect::<Vec<Scalar>>();
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
+            storage: Arc::new(storage),
             shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            strides: Tensor::compute_strides(&self.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: if requires_grad {
-                Some(Rc::new(AbsGradFn::new(Tensor::new(mask, &self.shape))))
+                Some(Arc::new(AbsGradFn::new(self.sign().detached())))
             } else {
                 None
             },
@@ -156,31 +188,35 @@ impl Tensor {
     /// # Returns
     /// A tensor with values clamped between min and max
     pub fn clamp(&self, min: Scalar, max: Scalar) -> Tensor {
-        let mut result_data = Vec::with_capacity(self.shape.iter().product());
-        let mut mask = Vec::with_capacity(self.shape.iter().product());
-        for i in 0..self.storage.data.len() {
-            if self.storage.data[i] < min {
-                result_data.push(min);
-                mask.push(0.);
-            } else if self.storage.data[i] > max {
-                result_data.push(max);
-                mask.push(0.);
-            } else {
-                result_data.push(self.storage.data[i]);
-                mask.push(1.);
+        let (storage, mask) = dispatch_numeric!(self.dtype(), T => {
+            let (min, max) = (T::from_f64(min as f64), T::from_f64(max as f64));
+            let mut result_data = Vec::with_capacity(self.numel());
+            let mut mask = Vec::with_capacity(self.numel());
+            for x in self.values::<T>() {
+                if x < min {
+                    result_data.push(min);
+                    mask.push(T::zero());
+                } else if x > max {
+                    result_data.push(max);
+                    mask.push(T::zero());
+                } else {
+                    result_data.push(x);
+                    mask.push(T::one());
+                }
             }
-        }
+            (Storage::new(result_data), Tensor::from_vec(mask, &self.shape))
+        });
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = self.records_grad();
 
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
+            storage: Arc::new(storage),
             shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            strides: Tensor::compute_strides(&self.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: if requires_grad {
-                Some(Rc::new(ClampGradFn::new(Tensor::new(mask, &self.shape))))
+                Some(Arc::new(ClampGradFn::new(mask)))
             } else {
                 None
             },
@@ -198,26 +234,25 @@ impl Tensor {
     /// # Returns
     /// A tensor containing the logarithm values
     pub fn log(&self) -> Tensor {
-        let mut result_data = Vec::with_capacity(self.shape.iter().product());
-        for i in 0..self.storage.data.len() {
-            result_data.push(self.storage.data[i].ln());
-        }
+        let input = self.to_float();
+        let storage =
+            dispatch_float!(input.dtype(), T => Storage::new(input.map_data::<T, T>(T::ln)));
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = input.records_grad();
 
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            storage: Arc::new(storage),
+            shape: input.shape.clone(),
+            strides: Tensor::compute_strides(&input.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: if requires_grad {
-                Some(Rc::new(LogGradFn::new(self.clone())))
+                Some(Arc::new(LogGradFn::new(input.clone())))
             } else {
                 None
             },
             parents: if requires_grad {
-                vec![self.clone()]
+                vec![input.clone()]
             } else {
                 Vec::new()
             },
@@ -227,22 +262,21 @@ impl Tensor {
     }
 
     pub fn exp(&self) -> Tensor {
-        let mut result_data = Vec::with_capacity(self.shape.iter().product());
-        for i in 0..self.storage.data.len() {
-            result_data.push(self.storage.data[i].exp());
-        }
+        let input = self.to_float();
+        let storage =
+            dispatch_float!(input.dtype(), T => Storage::new(input.map_data::<T, T>(T::exp)));
 
-        let requires_grad = self.requires_grad;
+        let requires_grad = input.records_grad();
 
         let mut out: Tensor = InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
-            shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            storage: Arc::new(storage),
+            shape: input.shape.clone(),
+            strides: Tensor::compute_strides(&input.shape),
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: None,
             parents: if requires_grad {
-                vec![self.clone()]
+                vec![input.clone()]
             } else {
                 Vec::new()
             },
@@ -251,27 +285,31 @@ impl Tensor {
         .into();
 
         if requires_grad {
-            out.set_grad_metadata(Rc::new(ExpGradFn::new(out.clone())), vec![self.clone()]);
+            let output = out.detached();
+            out.set_grad_metadata(
+                Arc::new(ExpGradFn::new(input.clone(), output)),
+                vec![input.clone()],
+            );
         }
         out
     }
 
     pub fn sign(&self) -> Tensor {
-        let result_data = self
-            .storage
-            .data
-            .iter()
-            .map(|x| x.signum())
-            .collect::<Vec<_>>();
-        let requires_grad = self.requires_grad;
+        let storage =
+            dispatch_numeric!(self.dtype(), T => Storage::new(self.map_data::<T, T>(signum)));
+        let requires_grad = self.records_grad();
 
         InternalTensor {
-            storage: Rc::new(Storage::new(result_data)),
+            storage: Arc::new(storage),
             shape: self.shape.clone(),
-            strides: self.strides.clone(),
+            strides: Tensor::compute_strides(&self.shape),
             offset: 0,
-            grad: RefCell::new(None),
-            grad_fn: None, // Gradient function for sign not implemented
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                Some(Arc::new(SignGradFn))
+            } else {
+                None
+            },
             parents: if requires_grad {
                 vec![self.clone()]
             } else {
@@ -0,0 +1,311 @@
+use crate::linalg::autograd::grad_fn::shape::ViewGradFn;
+use crate::linalg::error::{OrPanic, Result, TensorError, check_index};
+use crate::linalg::tensor::{InternalTensor, Tensor};
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Shape, strides and offset describing how a tensor reads its storage.
+pub(crate) struct Layout {
+    pub(crate) shape: Vec<usize>,
+    pub(crate) strides: Vec<usize>,
+    pub(crate) offset: usize,
+}
+
+impl Tensor {
+    /// Creates a view sharing the storage of the tensor, whose layout is obtained by applying
+    /// `transform` to the layout of the tensor. The same transform applied to a contiguous layout
+    /// tells the gradient where each element of the view comes from.
+    pub(crate) fn view(&self, transform: impl Fn(&mut Layout)) -> Tensor {
+        let mut layout = Layout {
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            offset: self.offset,
+        };
+        transform(&mut layout);
+
+        let requires_grad = self.records_grad();
+
+        InternalTensor {
+            storage: Arc::clone(&self.storage),
+            shape: layout.shape,
+            strides: layout.strides,
+            offset: layout.offset,
+            grad: Mutex::new(None),
+            grad_fn: if requires_grad {
+                let mut relative = Layout {
+                    shape: self.shape.clone(),
+                    strides: Tensor::compute_strides(&self.shape),
+                    offset: 0,
+                };
+                transform(&mut relative);
+                Some(Arc::new(ViewGradFn::new(self.shape.clone(), relative)))
+            } else {
+                None
+            },
+            parents: if requires_grad {
+                vec![self.clone()]
+            } else {
+                Vec::new()
+            },
+            requires_grad,
+        }
+        .into()
+    }
+
+    /// Returns a view of `len` elements along `axis`, starting at `start`.
+    /// # Arguments
+    /// * `axis` - The axis to narrow.
+    /// * `start` - The first index kept along `axis`.
+    /// * `len` - The number of indices kept along `axis`.
+    /// # Returns
+    /// A view sharing the storage of the tensor
+    pub fn narrow(&self, axis: usize, start: usize, len: usize) -> Tensor {
+        self.try_narrow(axis, start, len).or_panic()
+    }
+
+    /// Returns a view of `len` elements along `axis`, starting at `start`.
+    /// Returns an error if the axis or the range is out of bounds.
+    pub fn try_narrow(&self, axis: usize, start: usize, len: usize) -> Result<Tensor> {
+        check_index("narrow", axis, self.shape.len())?;
+        if start + len > self.shape[axis] {
+            return Err(TensorError::IndexOutOfBounds {
+                op: "narrow",
+                index: start + len,
+                bound: self.shape[axis] + 1,
+            });
+        }
+        Ok(self.view(|layout| {
+            layout.offset += start * layout.strides[axis];
+            layout.shape[axis] = len;
+        }))
+    }
+
+    /// Returns a view of the slice at `index` along `axis`, removing that axis.
+    /// # Arguments
+    /// * `axis` - The axis to select from.
+    /// * `index` - The index to select along `axis`.
+    /// # Returns
+    /// A view with one dimension less than the tensor
+    pub fn select(&self, axis: usize, index: usize) -> Tensor {
+        self.try_select(axis, index).or_panic()
+    }
+
+    /// Returns a view of the slice at `index` along `axis`, removing that axis.
+    /// Returns an error if the axis or the index is out of bounds.
+    pub fn try_select(&self, axis: usize, index: usize) -> Result<Tensor> {
+        check_index("select", axis, self.shape.len())?;
+        check_index("select", index, self.shape[axis])?;
+        Ok(self.view(|layout| {
+            layout.offset += index * layout.strides[axis];
+            layout.shape.remove(axis);
+            layout.strides.remove(axis);
+        }))
+    }
+
+    /// Returns a view with the dimensions reordered.
+    /// # Arguments
+    /// * `axes` - A permutation of `0..rank`; dimension `i` of the result is dimension `axes[i]` of the tensor.
+    /// # Returns
+    /// A view sharing the storage of the tensor
+    pub fn permute(&self, axes: &[usize]) -> Tensor {
+        self.try_permute(axes).or_panic()
+    }
+
+    /// Returns a view with the dimensions reordered.
+    /// Returns an error if `axes` is not a permutation of the dimensions of the tensor.
+    pub fn try_permute(&self, axes: &[usize]) -> Result<Tensor> {
+        let rank = self.shape.len();
+        let mut seen = vec![false; rank];
+        for &axis in axes {
+            check_index("permute", axis, rank)?;
+            if std::mem::replace(&mut seen[axis], true) {
+                return Err(TensorError::InvalidArgument(format!(
+                    "permute: axis {axis} appears more than once in {axes:?}"
+                )));
+            }
+        }
+        if axes.len() != rank {
+            return Err(TensorError::Rank {
+                op: "permute",
+                expected: rank,
+                actual: axes.len(),
+            });
+        }
+        Ok(self.view(|layout| {
+            layout.shape = axes.iter().map(|&axis| layout.shape[axis]).collect();
+            layout.strides = axes.iter().map(|&axis| layout.strides[axis]).collect();
+        }))
+    }
+
+    /// Returns a view with the dimensions `axis0` and `axis1` swapped.
+    /// # Returns
+    /// A view sharing the storage of the tensor
+    pub fn swap_axes(&self, axis0: usize, axis1: usize) -> Tensor {
+        self.try_swap_axes(axis0, axis1).or_panic()
+    }
+
+    /// Returns a view with the dimensions `axis0` and `axis1` swapped.
+    /// Returns an error if one of the axes is out of bounds.
+    pub fn try_swap_axes(&self, axis0: usize, axis1: usize) -> Result<Tensor> {
+        check_index("swap_axes", axis0, self.shape.len())?;
+        check_index("swap_axes", axis1, self.shape.len())?;
+        Ok(self.view(|layout| {
+            layout.shape.swap(axis0, axis1);
+            layout.strides.swap(axis0, axis1);
+        }))
+    }
+
+    /// Swaps the last two dimensions, transposing every matrix of a batch.
+    pub(crate) fn matrix_transpose(&self) -> Tensor {
+        let rank = self.shape.len();
+        self.swap_axes(rank - 2, rank - 1)
+    }
+
+    /// Returns a view repeating the tensor to the given shape without copying, using zero strides.
+    /// New dimensions are prepended, and existing dimensions of size one can take any size.
+    /// # Arguments
+    /// * `shape` - The target shape
+    /// # Returns
+    /// A view of shape `shape` sharing the storage of the tensor
+    pub fn expand(&self, shape: &[usize]) -> Tensor {
+        self.try_expand(shape).or_panic()
+    }
+
+    /// Returns a view repeating the tensor to the given shape without copying, using zero strides.
+    /// Returns an error if the tensor cannot be expanded to `shape`.
+    pub fn try_expand(&self, shape: &[usize]) -> Result<Tensor> {
+        if Tensor::broadcast_shape(&self.shape, shape)? != shape {
+            return Err(TensorError::ShapeMismatch {
+                op: "expand",
+                lhs: self.shape.clone(),
+                rhs: shape.to_vec(),
+            });
+        }
+        Ok(self.view(|layout| {
+            let padding = shape.len() - layout.shape.len();
+            let mut strides = vec![0; shape.len()];
+            for (d, (&dim, &stride)) in layout.shape.iter().zip(&layout.strides).enumerate() {
+                if dim == shape[padding + d] {
+                    strides[padding + d] = stride;
+                }
+            }
+            layout.shape = shape.to_vec();
+            layout.strides = strides;
+        }))
+    }
+
+    /// Expands the tensor by repeating its data along a new dimension.
+    /// # Arguments
+    /// * `axis` - The axis along which to expand the tensor.
+    /// * `size` - The size of the new dimension.
+    /// # Returns
+    /// A view with the expanded dimension, sharing the storage of the tensor.
+    pub fn expand_dim(&self, axis: usize, size: usize) -> Tensor {
+        let unsqueezed = self.unsqueeze(axis);
+        let mut shape = unsqueezed.shape.clone();
+        shape[axis] = size;
+        unsqueezed.expand(&shape)
+    }
+
+    /// Returns a view with a new dimension of size one inserted at `axis`.
+    /// # Arguments
+    /// * `axis` - The position of the new dimension, between `0` and `rank` (inclusive).
+    /// # Returns
+    /// A view sharing the storage of the tensor
+    pub fn unsqueeze(&self, axis: usize) -> Tensor {
+        self.try_unsqueeze(axis).or_panic()
+    }
+
+    /// Returns a view with a new dimension of size one inserted at `axis`.
+    /// Returns an error if `axis` is greater than the rank of the tensor.
+    pub fn try_unsqueeze(&self, axis: usize) -> Result<Tensor> {
+        check_index("unsqueeze", axis, self.shape.len() + 1)?;
+        Ok(self.view(|layout| {
+            let stride = if axis < layout.strides.len() {
+                layout.strides[axis] * layout.shape[axis]
+            } else {
+                1
+            };
+            layout.shape.insert(axis, 1);
+            layout.strides.insert(axis, stride);
+        }))
+    }
+
+    /// Returns a view with every dimension of size one removed.
+    /// A tensor containing a single element keeps one dimension.
+    pub fn squeeze(&self) -> Tensor {
+        self.view(|layout| {
+            let (shape, strides): (Vec<usize>, Vec<usize>) = layout
+                .shape
+                .iter()
+                .zip(&layout.strides)
+                .filter(|(dim, _)| **dim != 1)
+                .unzip();
+            if shape.is_empty() {
+                layout.shape = vec![1];
+                layout.strides = vec![1];
+            } else {
+                layout.shape = shape;
+                layout.strides = strides;
+            }
+        })
+    }
+
+    /// Returns a view with the dimension `axis`, which must have size one, removed.
+    pub fn squeeze_axis(&self, axis: usize) -> Tensor {
+        self.try_squeeze_axis(axis).or_panic()
+    }
+
+    /// Returns a view with the dimension `axis`, which must have size one, removed.
+    /// Returns an error if the axis is out of bounds or does not have size one.
+    pub fn try_squeeze_axis(&self, axis: usize) -> Result<Tensor> {
+        check_index("squeeze_axis", axis, self.shape.len())?;
+        if self.shape[axis] != 1 {
+            return Err(TensorError::InvalidArgument(format!(
+                "squeeze_axis: dimension {axis} has size {}, expected 1",
+                self.shape[axis]
+            )));
+        }
+        Ok(self.view(|layout| {
+            layout.shape.remove(axis);
+            layout.strides.remove(axis);
+        }))
+    }
+
+    /// Merges the dimensions `start_axis..=end_axis` into a single dimension.
+    /// Returns a view when the merged dimensions are laid out contiguously, and a copy otherwise.
+    /// # Arguments
+    /// * `start_axis` - The first dimension to merge.
+    /// * `end_axis` - The last dimension to merge (inclusive).
+    /// # Returns
+    /// A tensor with `end_axis - start_axis` dimensions less
+    pub fn flatten(&self, start_axis: usize, end_axis: usize) -> Tensor {
+        self.try_flatten(start_axis, end_axis).or_panic()
+    }
+
+    /// Merges the dimensions `start_axis..=end_axis` into a single dimension.
+    /// Returns an error if the axes are out of bounds or in the wrong order.
+    pub fn try_flatten(&self, start_axis: usize, end_axis: usize) -> Result<Tensor> {
+        check_index("flatten", end_axis, self.shape.len())?;
+        check_index("flatten", start_axis, end_axis + 1)?;
+
+        // Dimensions of size one can be merged regardless of their stride
+        let dims = (start_axis..=end_axis)
+            .filter(|&d| self.shape[d] != 1)
+            .collect::<Vec<usize>>();
+        let mergeable = dims
+            .windows(2)
+            .all(|pair| self.strides[pair[0]] == self.strides[pair[1]] * self.shape[pair[1]]);
+        let input = if mergeable {
+            self.clone()
+        } else {
+            self.contiguous()
+        };
+        Ok(input.view(|layout| {
+            let size = layout.shape[start_axis..=end_axis].iter().product();
+            let stride = dims.last().map_or(1, |&d| layout.strides[d]);
+            layout.shape.splice(start_axis..=end_axis, [size]);
+            layout.strides.splice(start_axis..=end_axis, [stride]);
+        }))
+    }
+}
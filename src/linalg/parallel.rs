@@ -0,0 +1,116 @@
+//! Multi-threaded execution of the CPU kernels.
+//!
+//! With the `parallel` cargo feature enabled, element-wise operations, reductions and matrix
+//! products split their output across scoped std threads. Without it, everything runs on the
+//! calling thread. Reductions always combine fixed-size blocks in the same order, so their results
+//! do not depend on the number of threads.
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of threads requested with [`set_num_threads`], or zero for the default.
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Minimum number of element-wise operations worth handing to a separate thread.
+pub(crate) const GRAIN: usize = 1 << 15;
+
+/// Number of elements folded sequentially before partial results are combined in reductions.
+/// It does not depend on the number of threads, which keeps reductions deterministic.
+const REDUCTION_BLOCK: usize = 1 << 12;
+
+/// Sets the number of threads the kernels may use. Zero restores the default, which is the
+/// available parallelism of the machine. Has no effect unless the `parallel` feature is enabled.
+/// # Example
+/// ```rust
+/// use nn_rs::linalg::parallel::{num_threads, set_num_threads};
+/// set_num_threads(1);
+/// assert_eq!(num_threads(), 1);
+/// set_num_threads(0);
+/// ```
+pub fn set_num_threads(threads: usize) {
+    NUM_THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// Returns the number of threads the kernels may use, which is always one unless the `parallel`
+/// feature is enabled.
+pub fn num_threads() -> usize {
+    if !cfg!(feature = "parallel") {
+        return 1;
+    }
+    match NUM_THREADS.load(Ordering::Relaxed) {
+        0 => std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+        threads => threads,
+    }
+}
+
+/// Splits `out` into contiguous chunks processed on separate threads, calling `f(start, chunk)`
+/// where `start` is the index of the first element of `chunk` in `out`.
+/// # Arguments
+/// * `align` - Every chunk but the last has a length multiple of `align`.
+/// * `grain` - The minimum number of elements per chunk.
+pub(crate) fn for_each_chunk<T: Send>(
+    out: &mut [T],
+    align: usize,
+    grain: usize,
+    f: impl Fn(usize, &mut [T]) + Sync,
+) {
+    let threads = num_threads().min(out.len() / grain.max(1)).max(1);
+    if threads == 1 {
+        f(0, out);
+        return;
+    }
+    let chunk = out.len().div_ceil(threads).next_multiple_of(align.max(1));
+    std::thread::scope(|scope| {
+        for (i, part) in out.chunks_mut(chunk).enumerate() {
+            let f = &f;
+            scope.spawn(move || f(i * chunk, part));
+        }
+    });
+}
+
+/// Fills `out` with `f(i)` for every index `i`, splitting the work across threads.
+/// `f` receives a range of indices and must return the values of exactly those indices.
+pub(crate) fn fill_with<T: Send, I: Iterator<Item = T>>(
+    out: &mut [T],
+    f: impl Fn(Range<usize>) -> I + Sync,
+) {
+    for_each_chunk(out, 1, GRAIN, |start, chunk| {
+        let values = f(start..start + chunk.len());
+        for (slot, value) in chunk.iter_mut().zip(values) {
+            *slot = value;
+        }
+    });
+}
+
+/// Reduces `lanes` independent lanes of `len` elements each.
+/// Every lane is split into blocks of a fixed size: `partial(lane, range)` reduces the elements
+/// `range` of a lane, and the partial results of a lane are combined in order with `combine`.
+/// An empty lane is reduced by `partial(lane, 0..0)`.
+pub(crate) fn reduce_lanes<A: Send>(
+    lanes: usize,
+    len: usize,
+    partial: impl Fn(usize, Range<usize>) -> A + Sync,
+    combine: impl Fn(A, A) -> A,
+) -> Vec<A> {
+    let blocks = len.div_ceil(REDUCTION_BLOCK).max(1);
+    let mut partials = Vec::with_capacity(lanes * blocks);
+    partials.resize_with(lanes * blocks, || None);
+    let grain = (GRAIN / REDUCTION_BLOCK.min(len).max(1)).max(1);
+    for_each_chunk(&mut partials, 1, grain, |start, chunk| {
+        for (i, slot) in chunk.iter_mut().enumerate() {
+            let (lane, block) = ((start + i) / blocks, (start + i) % blocks);
+            let begin = block * REDUCTION_BLOCK;
+            *slot = Some(partial(lane, begin..len.min(begin + REDUCTION_BLOCK)));
+        }
+    });
+
+    let mut partials = partials
+        .into_iter()
+        .map(|partial| partial.expect("every block is reduced"));
+    (0..lanes)
+        .map(|_| {
+            let first = partials.next().expect("every lane has a block");
+            partials.by_ref().take(blocks - 1).fold(first, &combine)
+        })
+        .collect()
+}
@@ -0,0 +1,364 @@
+//! Random tensor constructors driven by a seedable generator.
+//!
+//! Every constructor exists as a method of [`Generator`], and as a function of [`Tensor`] drawing
+//! from a library-wide generator. That generator is seeded from the operating system, and
+//! [`manual_seed`] reseeds it to make a run reproducible.
+
+use crate::dispatch_float;
+use crate::linalg::dtype::{DType, Element};
+use crate::linalg::error::{OrPanic, Result, TensorError, check_dtype};
+use crate::linalg::tensor::{Scalar, Tensor};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+static GLOBAL: LazyLock<Mutex<Generator>> = LazyLock::new(|| Mutex::new(Generator::from_entropy()));
+
+/// Reseeds the library-wide generator, so that the random tensors created afterwards are the
+/// same on every run.
+/// # Arguments
+/// * `seed` - The new seed
+pub fn manual_seed(seed: u64) {
+    with_global_generator(|generator| generator.manual_seed(seed));
+}
+
+/// Runs `f` with exclusive access to the library-wide generator, for example to shuffle data with
+/// it. `f` must not create random tensors through [`Tensor`], which would wait for the generator
+/// forever; it can use the generator it is given instead.
+pub fn with_global_generator<R>(f: impl FnOnce(&mut Generator) -> R) -> R {
+    let mut generator = GLOBAL.lock().unwrap_or_else(PoisonError::into_inner);
+    f(&mut generator)
+}
+
+/// A seedable pseudo-random number generator. Generators created with the same seed produce the
+/// same sequence of tensors.
+/// # Example
+/// ```rust
+/// use nn_rs::linalg::random::Generator;
+/// let a = Generator::new(42).randn(&[2, 3]);
+/// let b = Generator::new(42).randn(&[2, 3]);
+/// assert_eq!(a.as_slice(), b.as_slice());
+/// ```
+pub struct Generator {
+    rng: StdRng,
+}
+
+impl Generator {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Creates a generator seeded from the operating system.
+    pub fn from_entropy() -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+        }
+    }
+
+    /// Restarts the generator from a seed.
+    pub fn manual_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Samples a tensor uniformly from `[0, 1)`.
+    pub fn rand(&mut self, shape: &[usize]) -> Tensor {
+        self.uniform(shape, 0.0, 1.0)
+    }
+
+    /// Samples a tensor from the standard normal distribution.
+    pub fn randn(&mut self, shape: &[usize]) -> Tensor {
+        self.normal(shape, 0.0, 1.0)
+    }
+
+    /// Samples a tensor uniformly from `[low, high)`.
+    /// # Arguments
+    /// * `shape` - The shape of the tensor
+    /// * `low` - The lower bound, included
+    /// * `high` - The upper bound, excluded
+    pub fn uniform(&mut self, shape: &[usize], low: Scalar, high: Scalar) -> Tensor {
+        self.try_uniform(shape, low, high).or_panic()
+    }
+
+    /// Samples a tensor uniformly from `[low, high)`.
+    /// Returns an error if `low` is not lower than `high`.
+    pub fn try_uniform(&mut self, shape: &[usize], low: Scalar, high: Scalar) -> Result<Tensor> {
+        if low >= high || !(high - low).is_finite() {
+            return Err(TensorError::InvalidArgument(format!(
+                "uniform: invalid range [{low}, {high})"
+            )));
+        }
+        let data = (0..shape.iter().product())
+            .map(|_| low + (high - low) * self.rng.random::<Scalar>())
+            .collect();
+        Tensor::try_new(data, shape)
+    }
+
+    /// Samples a tensor from a normal distribution.
+    /// # Arguments
+    /// * `shape` - The shape of the tensor
+    /// * `mean` - The mean of the distribution
+    /// * `std` - The standard deviation of the distribution
+    pub fn normal(&mut self, shape: &[usize], mean: Scalar, std: Scalar) -> Tensor {
+        self.try_normal(shape, mean, std).or_panic()
+    }
+
+    /// Samples a tensor from a normal distribution.
+    /// Returns an error if the standard deviation is negative or not finite.
+    pub fn try_normal(&mut self, shape: &[usize], mean: Scalar, std: Scalar) -> Result<Tensor> {
+        if std < 0.0 || !std.is_finite() {
+            return Err(TensorError::InvalidArgument(format!(
+                "normal: invalid standard deviation {std}"
+            )));
+        }
+        // The Box-Muller transform turns two uniform samples into two independent normal ones
+        let len = shape.iter().product::<usize>();
+        let mut data = Vec::with_capacity(len + 1);
+        while data.len() < len {
+            let radius = (-2.0 * (1.0 - self.rng.random::<f64>()).ln()).sqrt();
+            let angle = std::f64::consts::TAU * self.rng.random::<f64>();
+            for z in [radius * angle.cos(), radius * angle.sin()] {
+                data.push(mean + std * z as Scalar);
+            }
+        }
+        data.truncate(len);
+        Tensor::try_new(data, shape)
+    }
+
+    /// Samples every element from a Bernoulli distribution: one with the probability given by the
+    /// matching element of `probabilities`, and zero otherwise.
+    /// # Arguments
+    /// * `probabilities` - A floating point tensor of probabilities between zero and one
+    /// # Returns
+    /// A tensor of zeros and ones of the same shape and dtype
+    pub fn bernoulli(&mut self, probabilities: &Tensor) -> Tensor {
+        self.try_bernoulli(probabilities).or_panic()
+    }
+
+    /// Samples every element from a Bernoulli distribution.
+    /// Returns an error if `probabilities` is not a floating point tensor of values in `[0, 1]`.
+    pub fn try_bernoulli(&mut self, probabilities: &Tensor) -> Result<Tensor> {
+        check_dtype("bernoulli", probabilities.dtype(), DType::is_floating_point)?;
+        dispatch_float!(probabilities.dtype(), T => {
+            let mut data = Vec::with_capacity(probabilities.numel());
+            for p in probabilities.values::<T>() {
+                let p = p.to_f64();
+                if !(0.0..=1.0).contains(&p) {
+                    return Err(TensorError::InvalidArgument(format!(
+                        "bernoulli: invalid probability {p}"
+                    )));
+                }
+                data.push(T::from_f64(f64::from(u8::from(self.rng.random_bool(p)))));
+            }
+            Tensor::try_from_vec(data, probabilities.shape())
+        })
+    }
+
+    /// Samples an `I64` tensor uniformly from the integers in `[low, high)`.
+    /// # Arguments
+    /// * `low` - The lowest integer, included
+    /// * `high` - The highest integer, excluded
+    /// * `shape` - The shape of the tensor
+    pub fn randint(&mut self, low: i64, high: i64, shape: &[usize]) -> Tensor {
+        self.try_randint(low, high, shape).or_panic()
+    }
+
+    /// Samples an `I64` tensor uniformly from the integers in `[low, high)`.
+    /// Returns an error if `low` is not lower than `high`.
+    pub fn try_randint(&mut self, low: i64, high: i64, shape: &[usize]) -> Result<Tensor> {
+        if low >= high {
+            return Err(TensorError::InvalidArgument(format!(
+                "randint: invalid range [{low}, {high})"
+            )));
+        }
+        let data = (0..shape.iter().product())
+            .map(|_| self.rng.random_range(low..high))
+            .collect::<Vec<i64>>();
+        Tensor::try_from_vec(data, shape)
+    }
+
+    /// Returns a random permutation of the integers in `[0, n)`.
+    /// # Returns
+    /// An `I64` tensor of shape `[n]`
+    pub fn randperm(&mut self, n: usize) -> Tensor {
+        let mut data = (0..n as i64).collect::<Vec<i64>>();
+        data.shuffle(&mut self.rng);
+        Tensor::from_vec(data, &[n])
+    }
+
+    /// Draws indices from the categorical distributions given by the rows of `weights`.
+    /// The weights of a row do not need to sum to one, only to be positive.
+    /// # Arguments
+    /// * `weights` - A 1D tensor of weights, or a 2D tensor holding one distribution per row
+    /// * `samples` - The number of indices drawn from every distribution
+    /// * `replacement` - Whether an index can be drawn several times from the same distribution
+    /// # Returns
+    /// An `I64` tensor of shape `[samples]`, or `[rows, samples]` for 2D weights
+    pub fn multinomial(&mut self, weights: &Tensor, samples: usize, replacement: bool) -> Tensor {
+        self.try_multinomial(weights, samples, replacement)
+            .or_panic()
+    }
+
+    /// Draws indices from the categorical distributions given by the rows of `weights`.
+    /// Returns an error if `weights` is not 1D or 2D, if a weight is negative or not finite, if a
+    /// row sums to zero, or if more samples are drawn without replacement than a row has
+    /// non-zero weights.
+    pub fn try_multinomial(
+        &mut self,
+        weights: &Tensor,
+        samples: usize,
+        replacement: bool,
+    ) -> Result<Tensor> {
+        let rank = weights.shape().len();
+        if rank != 1 && rank != 2 {
+            return Err(TensorError::Rank {
+                op: "multinomial",
+                expected: 2,
+                actual: rank,
+            });
+        }
+        check_dtype("multinomial", weights.dtype(), |dtype| dtype != DType::Bool)?;
+        let categories = weights.shape()[rank - 1];
+        let values = weights
+            .to_dtype(DType::F64)
+            .values::<f64>()
+            .collect::<Vec<f64>>();
+
+        let mut data = Vec::with_capacity(values.len() / categories.max(1) * samples);
+        for row in values.chunks(categories.max(1)) {
+            if row.iter().any(|&w| w < 0.0 || !w.is_finite()) {
+                return Err(TensorError::InvalidArgument(
+                    "multinomial: weights must be finite and non-negative".to_string(),
+                ));
+            }
+            let available = row.iter().filter(|&&w| w > 0.0).count();
+            if available == 0 || (!replacement && samples > available) {
+                return Err(TensorError::InvalidArgument(format!(
+                    "multinomial: cannot draw {samples} samples from {available} categories"
+                )));
+            }
+            let mut row = row.to_vec();
+            for _ in 0..samples {
+                let total = row.iter().sum::<f64>();
+                let target = self.rng.random::<f64>() * total;
+                // The last category with a positive weight absorbs rounding errors
+                let mut index = row.iter().rposition(|&w| w > 0.0).unwrap_or(0);
+                let mut cumulative = 0.0;
+                for (i, &w) in row.iter().enumerate() {
+                    cumulative += w;
+                    if w > 0.0 && target < cumulative {
+                        index = i;
+                        break;
+                    }
+                }
+                data.push(index as i64);
+                if !replacement {
+                    row[index] = 0.0;
+                }
+            }
+        }
+        let shape = if rank == 1 {
+            vec![samples]
+        } else {
+            vec![weights.shape()[0], samples]
+        };
+        Tensor::try_from_vec(data, &shape)
+    }
+}
+
+impl RngCore for Generator {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst);
+    }
+}
+
+impl Tensor {
+    /// Samples a tensor uniformly from `[0, 1)` with the library-wide generator.
+    /// See [`Generator::rand`].
+    pub fn rand(shape: &[usize]) -> Tensor {
+        with_global_generator(|generator| generator.rand(shape))
+    }
+
+    /// Samples a tensor from the standard normal distribution with the library-wide generator.
+    /// See [`Generator::randn`].
+    pub fn randn(shape: &[usize]) -> Tensor {
+        with_global_generator(|generator| generator.randn(shape))
+    }
+
+    /// Samples a tensor uniformly from `[low, high)` with the library-wide generator.
+    /// See [`Generator::uniform`].
+    pub fn uniform(shape: &[usize], low: Scalar, high: Scalar) -> Tensor {
+        Tensor::try_uniform(shape, low, high).or_panic()
+    }
+
+    /// Samples a tensor uniformly from `[low, high)` with the library-wide generator.
+    /// Returns an error if `low` is not lower than `high`.
+    pub fn try_uniform(shape: &[usize], low: Scalar, high: Scalar) -> Result<Tensor> {
+        with_global_generator(|generator| generator.try_uniform(shape, low, high))
+    }
+
+    /// Samples a tensor from a normal distribution with the library-wide generator.
+    /// See [`Generator::normal`].
+    pub fn normal(shape: &[usize], mean: Scalar, std: Scalar) -> Tensor {
+        Tensor::try_normal(shape, mean, std).or_panic()
+    }
+
+    /// Samples a tensor from a normal distribution with the library-wide generator.
+    /// Returns an error if the standard deviation is negative or not finite.
+    pub fn try_normal(shape: &[usize], mean: Scalar, std: Scalar) -> Result<Tensor> {
+        with_global_generator(|generator| generator.try_normal(shape, mean, std))
+    }
+
+    /// Samples every element from a Bernoulli distribution with the library-wide generator.
+    /// See [`Generator::bernoulli`].
+    pub fn bernoulli(probabilities: &Tensor) -> Tensor {
+        Tensor::try_bernoulli(probabilities).or_panic()
+    }
+
+    /// Samples every element from a Bernoulli distribution with the library-wide generator.
+    /// Returns an error if `probabilities` is not a floating point tensor of values in `[0, 1]`.
+    pub fn try_bernoulli(probabilities: &Tensor) -> Result<Tensor> {
+        with_global_generator(|generator| generator.try_bernoulli(probabilities))
+    }
+
+    /// Samples an `I64` tensor uniformly from `[low, high)` with the library-wide generator.
+    /// See [`Generator::randint`].
+    pub fn randint(low: i64, high: i64, shape: &[usize]) -> Tensor {
+        Tensor::try_randint(low, high, shape).or_panic()
+    }
+
+    /// Samples an `I64` tensor uniformly from `[low, high)` with the library-wide generator.
+    /// Returns an error if `low` is not lower than `high`.
+    pub fn try_randint(low: i64, high: i64, shape: &[usize]) -> Result<Tensor> {
+        with_global_generator(|generator| generator.try_randint(low, high, shape))
+    }
+
+    /// Returns a random permutation of `[0, n)` drawn with the library-wide generator.
+    /// See [`Generator::randperm`].
+    pub fn randperm(n: usize) -> Tensor {
+        with_global_generator(|generator| generator.randperm(n))
+    }
+
+    /// Draws indices from categorical distributions with the library-wide generator.
+    /// See [`Generator::multinomial`].
+    pub fn multinomial(weights: &Tensor, samples: usize, replacement: bool) -> Tensor {
+        Tensor::try_multinomial(weights, samples, replacement).or_panic()
+    }
+
+    /// Draws indices from categorical distributions with the library-wide generator.
+    /// Returns an error if the weights are invalid or too few to draw `samples` indices.
+    pub fn try_multinomial(weights: &Tensor, samples: usize, replacement: bool) -> Result<Tensor> {
+        with_global_generator(|generator| generator.try_multinomial(weights, samples, replacement))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::linalg::dtype::Element;
+use crate::linalg::tensor::{InternalTensor, TensorSlice};
+use std::ops::Deref;
+
+/// Iterates over the storage positions of a strided layout in logical (row-major) order.
+/// Every kernel reads its operands through this iterator, so views with permuted strides,
+/// zero (broadcast) strides or a non-zero offset are handled uniformly.
+#[derive(Clone)]
+pub(crate) struct StridedIter {
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    indices: Vec<usize>,
+    position: usize,
+    remaining: usize,
+}
+
+impl StridedIter {
+    /// Creates an iterator over a layout.
+    /// # Arguments
+    /// * `shape` - The logical shape to iterate over.
+    /// * `strides` - The storage stride of each dimension.
+    /// * `offset` - The storage position of the first element.
+    pub(crate) fn new(shape: &[usize], strides: &[usize], offset: usize) -> Self {
+        StridedIter {
+            shape: shape.to_vec(),
+            strides: strides.to_vec(),
+            indices: vec![0; shape.len()],
+            position: offset,
+            remaining: shape.iter().product(),
+        }
+    }
+
+    /// Skips the first `start` elements of a new iterator, in constant time per dimension.
+    pub(crate) fn starting_at(mut self, start: usize) -> Self {
+        let start = start.min(self.remaining);
+        if start == 0 {
+            return self;
+        }
+        let mut rest = start;
+        for d in (0..self.shape.len()).rev() {
+            self.indices[d] = rest % self.shape[d];
+            self.position += self.indices[d] * self.strides[d];
+            rest /= self.shape[d];
+        }
+        self.remaining -= start;
+        self
+    }
+}
+
+impl Iterator for StridedIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let current = self.position;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            for d in (0..self.shape.len()).rev() {
+                self.indices[d] += 1;
+                self.position += self.strides[d];
+                if self.indices[d] < self.shape[d] {
+                    break;
+                }
+                self.position -= self.strides[d] * self.shape[d];
+                self.indices[d] = 0;
+            }
+        }
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for StridedIter {}
+
+impl InternalTensor {
+    /// Returns the storage positions of the elements in logical (row-major) order.
+    pub(crate) fn storage_indices(&self) -> StridedIter {
+        StridedIter::new(&self.shape, &self.strides, self.offset)
+    }
+
+    /// Returns the elements in logical (row-major) order, honoring strides and offset.
+    /// Panics if `T` does not match the dtype of the tensor_old.
+    pub(crate) fn values<T: Element>(&self) -> impl ExactSizeIterator<Item = T> + '_ {
+        let data = self.data::<T>();
+        self.storage_indices().map(move |index| data[index])
+    }
+
+    /// Returns the elements in logical (row-major) order as a contiguous slice, borrowing the
+    /// storage when the layout is already contiguous and copying it otherwise.
+    /// Panics if `T` does not match the dtype of the tensor_old.
+    pub(crate) fn contiguous_data<T: Element>(&self) -> ContiguousData<'_, T> {
+        if self.is_contiguous() {
+            let range = self.offset..self.offset + self.numel();
+            ContiguousData::Borrowed(self.data::<T>().narrowed(range))
+        } else {
+            ContiguousData::Owned(self.values().collect())
+        }
+    }
+}
+
+/// Elements of a tensor in logical order, either borrowed from its storage or copied.
+pub(crate) enum ContiguousData<'a, T> {
+    Borrowed(TensorSlice<'a, T>),
+    Owned(Vec<T>),
+}
+
+impl<T: Element> Deref for ContiguousData<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            ContiguousData::Borrowed(slice) => slice,
+            ContiguousData::Owned(data) => data,
+        }
+    }
+}
@@ -1,32 +1,159 @@
+use crate::dispatch_all;
 use crate::linalg::autograd::grad_fn::GradFn;
-use std::cell::RefCell;
+use crate::linalg::dtype::{Buffer, DType, Element};
+use crate::linalg::error::{OrPanic, Result, TensorError, check_index};
+use crate::linalg::parallel;
 use std::fmt::Debug;
-use std::ops::Deref;
-use std::rc::Rc;
+use std::marker::PhantomData;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard};
 
 pub(crate) type Scalar = f32;
 
-/// Internal storage for tensor_old data. Allows multiple tensors to share the same data.
-#[derive(Clone)]
+/// Internal storage for tensor_old data, shared by a tensor with its clones and views.
+///
+/// In-place operations write through the lock, so that every tensor sharing the storage sees the
+/// new values, and bump its version counter. Autograd records the version of the tensors it saves
+/// for the backward pass, which tells the ones that were modified in place after being saved.
 pub(crate) struct Storage {
-    pub(crate) data: Vec<Scalar>,
+    data: RwLock<Buffer>,
+    dtype: DType,
+    version: AtomicUsize,
 }
 impl Storage {
     /// Creates a new Storage with the given data.
     /// * `data` - A vector containing the storage data.
-    pub(crate) fn new(data: Vec<Scalar>) -> Self {
-        Storage { data }
+    pub(crate) fn new<T: Element>(data: Vec<T>) -> Self {
+        let data = T::into_buffer(data);
+        Storage {
+            dtype: data.dtype(),
+            data: RwLock::new(data),
+            version: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of writes to the storage.
+    pub(crate) fn version(&self) -> usize {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// Records a write to the storage.
+    pub(crate) fn bump_version(&self) {
+        self.version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Returns the element type of the storage.
+    pub(crate) fn dtype(&self) -> DType {
+        self.dtype
+    }
+
+    /// Returns the storage data as a typed slice, holding a read lock until it is dropped.
+    /// Panics if `T` does not match the storage dtype.
+    pub(crate) fn as_slice<T: Element>(&self) -> TensorSlice<'_, T> {
+        self.check_dtype::<T>();
+        let guard = self.data.read().unwrap_or_else(PoisonError::into_inner);
+        let len = T::slice(&guard).map_or(0, <[T]>::len);
+        TensorSlice {
+            guard,
+            range: 0..len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Writes to the storage data through a mutable typed slice, holding a write lock while `f`
+    /// runs, and records the write. Panics if `T` does not match the storage dtype.
+    pub(crate) fn write<T: Element>(&self, f: impl FnOnce(&mut [T])) {
+        self.check_dtype::<T>();
+        let mut guard = self.data.write().unwrap_or_else(PoisonError::into_inner);
+        self.bump_version();
+        f(T::slice_mut(&mut guard).expect("dtype was checked"));
+    }
+
+    /// Returns the storage data as a mutable typed slice, without locking since the storage is
+    /// borrowed mutably. Panics if `T` does not match the storage dtype.
+    pub(crate) fn as_mut_slice<T: Element>(&mut self) -> &mut [T] {
+        self.check_dtype::<T>();
+        let data = self.data.get_mut().unwrap_or_else(PoisonError::into_inner);
+        T::slice_mut(data).expect("dtype was checked")
+    }
+
+    fn check_dtype<T: Element>(&self) {
+        assert!(
+            T::DTYPE == self.dtype,
+            "Storage dtype mismatch: expected {:?}, got {:?}",
+            T::DTYPE,
+            self.dtype
+        );
+    }
+}
+
+impl Clone for Storage {
+    /// Copies the data into a new storage, which continues the version count of the original.
+    fn clone(&self) -> Self {
+        let data = self.data.read().unwrap_or_else(PoisonError::into_inner);
+        Storage {
+            data: RwLock::new(data.clone()),
+            dtype: self.dtype,
+            version: AtomicUsize::new(self.version()),
+        }
+    }
+}
+
+/// Elements of a tensor borrowed from its storage, which derefs to a slice.
+///
+/// It holds a read lock on the storage, so in-place operations on the tensor and on the tensors
+/// sharing its data wait until it is dropped.
+pub struct TensorSlice<'a, T> {
+    guard: RwLockReadGuard<'a, Buffer>,
+    range: Range<usize>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: Element> TensorSlice<'a, T> {
+    /// Restricts the slice to `range`, relative to its current start.
+    pub(crate) fn narrowed(mut self, range: Range<usize>) -> TensorSlice<'a, T> {
+        assert!(range.end <= self.range.len(), "Slice range out of bounds");
+        self.range = self.range.start + range.start..self.range.start + range.end;
+        self
+    }
+}
+
+impl<T: Element> Deref for TensorSlice<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &T::slice(&self.guard).expect("dtype was checked")[self.range.clone()]
+    }
+}
+
+impl<T: Element> AsRef<[T]> for TensorSlice<'_, T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: Element> Debug for TensorSlice<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: Element, U: AsRef<[T]> + ?Sized> PartialEq<U> for TensorSlice<'_, T> {
+    fn eq(&self, other: &U) -> bool {
+        **self == *other.as_ref()
     }
 }
 
 /// A multidimensional array (tensor) that supports automatic differentiation.
-/// This wrapper struct holds a reference-counted pointer to the internal tensor representation,
-/// allowing for efficient sharing and cloning of tensor data.
+/// This wrapper struct holds an atomically reference-counted pointer to the internal tensor representation,
+/// allowing for efficient sharing and cloning of tensor data, including across threads.
 #[derive(Clone)]
-pub struct Tensor(pub(crate) Rc<InternalTensor>);
+pub struct Tensor(pub(crate) Arc<InternalTensor>);
 
 impl Deref for Tensor {
-    type Target = Rc<InternalTensor>;
+    type Target = Arc<InternalTensor>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
@@ -34,7 +161,81 @@ impl Deref for Tensor {
 
 impl From<InternalTensor> for Tensor {
     fn from(internal: InternalTensor) -> Self {
-        Tensor(Rc::new(internal))
+        Tensor(Arc::new(internal))
+    }
+}
+
+/// Elements, or nested vectors and arrays of elements, from which a tensor infers its shape.
+/// Every level of nesting adds a dimension, whose size is the length of the vectors at that level.
+pub trait NestedData {
+    /// The type of the innermost elements.
+    type Item: Element;
+    /// The number of dimensions of the data.
+    const RANK: usize;
+
+    /// Returns the shape of the data, inferred from the first element of every level.
+    fn nested_shape(&self) -> Vec<usize>;
+
+    /// Appends the elements in row-major order to `data`.
+    /// Returns false if the data does not have the given shape.
+    fn flatten_into(self, shape: &[usize], data: &mut Vec<Self::Item>) -> bool;
+}
+
+impl<T: Element> NestedData for T {
+    type Item = T;
+    const RANK: usize = 0;
+
+    fn nested_shape(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn flatten_into(self, _shape: &[usize], data: &mut Vec<T>) -> bool {
+        data.push(self);
+        true
+    }
+}
+
+impl<N: NestedData> NestedData for Vec<N> {
+    type Item = N::Item;
+    const RANK: usize = N::RANK + 1;
+
+    fn nested_shape(&self) -> Vec<usize> {
+        let mut shape = vec![self.len()];
+        match self.first() {
+            Some(first) => shape.extend(first.nested_shape()),
+            None => shape.resize(Self::RANK, 0),
+        }
+        shape
+    }
+
+    fn flatten_into(self, shape: &[usize], data: &mut Vec<N::Item>) -> bool {
+        self.len() == shape[0] && self.into_iter().all(|n| n.flatten_into(&shape[1..], data))
+    }
+}
+
+impl<N: NestedData, const K: usize> NestedData for [N; K] {
+    type Item = N::Item;
+    const RANK: usize = N::RANK + 1;
+
+    fn nested_shape(&self) -> Vec<usize> {
+        let mut shape = vec![K];
+        match self.first() {
+            Some(first) => shape.extend(first.nested_shape()),
+            None => shape.resize(Self::RANK, 0),
+        }
+        shape
+    }
+
+    fn flatten_into(self, shape: &[usize], data: &mut Vec<N::Item>) -> bool {
+        self.into_iter().all(|n| n.flatten_into(&shape[1..], data))
+    }
+}
+
+/// Collects elements, or rows of nested data, into a tensor with an inferred shape.
+/// Panics if the rows do not all have the same shape.
+impl<N: NestedData> FromIterator<N> for Tensor {
+    fn from_iter<I: IntoIterator<Item = N>>(iter: I) -> Self {
+        Tensor::from_nested(iter.into_iter().collect::<Vec<N>>())
     }
 }
 
@@ -44,6 +245,61 @@ impl Tensor {
         InternalTensor::new(data, shape).into()
     }
 
+    /// Creates a new Tensor with the given data and shape.
+    /// Returns an error if the data length does not match the shape.
+    pub fn try_new(data: Vec<Scalar>, shape: &[usize]) -> Result<Self> {
+        Self::try_from_vec(data, shape)
+    }
+
+    /// Creates a new Tensor of any element type with the given data and shape.
+    /// The dtype of the tensor is inferred from the element type.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::dtype::DType;
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let labels = Tensor::from_vec(vec![3i64, 1, 4], &[3]);
+    /// assert_eq!(labels.dtype(), DType::I64);
+    /// ```
+    pub fn from_vec<T: Element>(data: Vec<T>, shape: &[usize]) -> Self {
+        InternalTensor::new(data, shape).into()
+    }
+
+    /// Creates a new Tensor of any element type with the given data and shape.
+    /// Returns an error if the data length does not match the shape.
+    pub fn try_from_vec<T: Element>(data: Vec<T>, shape: &[usize]) -> Result<Self> {
+        InternalTensor::try_new(data, shape).map(Tensor::from)
+    }
+
+    /// Creates a new Tensor from nested vectors or arrays, inferring its shape from the nesting.
+    /// A single element becomes a tensor of shape `[1]`.
+    /// # Example
+    /// ```rust
+    /// use nn_rs::linalg::tensor::Tensor;
+    /// let matrix = Tensor::from_nested(vec![vec![1.0f32, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+    /// assert_eq!(matrix.shape(), &[2, 3]);
+    /// let labels: Tensor = [3i64, 1, 4].into_iter().collect();
+    /// assert_eq!(labels.shape(), &[3]);
+    /// ```
+    pub fn from_nested<N: NestedData>(data: N) -> Self {
+        Self::try_from_nested(data).or_panic()
+    }
+
+    /// Creates a new Tensor from nested vectors or arrays, inferring its shape from the nesting.
+    /// Returns an error if the nested vectors of a level have different lengths.
+    pub fn try_from_nested<N: NestedData>(data: N) -> Result<Self> {
+        let mut shape = data.nested_shape();
+        let mut values = Vec::with_capacity(shape.iter().product());
+        if !data.flatten_into(&shape, &mut values) {
+            return Err(TensorError::InvalidArgument(
+                "from_nested: nested vectors of the same level have different lengths".to_string(),
+            ));
+        }
+        if shape.is_empty() {
+            shape.push(1);
+        }
+        Self::try_from_vec(values, &shape)
+    }
+
     /// Creates a tensor_old filled with ones with the specified shape.
     /// * `shape` - A slice representing the shape of the tensor_old.
     ///
@@ -66,11 +322,11 @@ impl Tensor {
 
     pub fn from_scalar(value: Scalar) -> Self {
         InternalTensor {
-            storage: Rc::new(Storage::new(vec![value])),
+            storage: Arc::new(Storage::new(vec![value])),
             shape: vec![1],
             strides: vec![1],
             offset: 0,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: None,
             parents: Vec::new(),
             requires_grad: false,
@@ -102,7 +358,7 @@ impl Tensor {
     /// Returns a vector containing the computed strides.
     pub(crate) fn compute_strides(shape: &[usize]) -> Vec<usize> {
         let mut strides = vec![1; shape.len()];
-        for i in (0..shape.len() - 1).rev() {
+        for i in (0..shape.len().saturating_sub(1)).rev() {
             strides[i] = strides[i + 1] * shape[i + 1];
         }
         strides
@@ -125,35 +381,72 @@ impl Tensor {
     /// Clones the storage if it's shared with other tensors.
     pub fn make_unique(&mut self) {
         // Clone inner if shared
-        let inner = Rc::make_mut(&mut self.0);
+        let inner = Arc::make_mut(&mut self.0);
 
         // Clone storage if shared
-        if Rc::strong_count(&inner.storage) > 1 {
-            inner.storage = Rc::new(Storage {
-                data: inner.storage.data.clone(),
-            });
+        if Arc::strong_count(&inner.storage) > 1 {
+            inner.storage = Arc::new(Storage::clone(&inner.storage));
         }
     }
 
     /// Sets the value at the specified multidimensional indices.
+    /// The value is converted to the dtype of the tensor_old.
     /// * `indices` - A slice of indices for each dimension of the tensor_old.
     /// * `value` - The value to set at the specified indices.
     pub fn set(&mut self, indices: &[usize], value: Scalar) {
-        let inner = Rc::make_mut(&mut self.0);
-        let index = inner.compute_flat_index(indices);
-        let storage = Rc::make_mut(&mut inner.storage);
-        storage.data[index] = value;
+        self.try_set(indices, value).or_panic()
+    }
+
+    /// Sets the value at the specified multidimensional indices.
+    /// Returns an error if the indices do not match the shape of the tensor_old.
+    /// * `indices` - A slice of indices for each dimension of the tensor_old.
+    /// * `value` - The value to set at the specified indices.
+    pub fn try_set(&mut self, indices: &[usize], value: Scalar) -> Result<()> {
+        let index = self.try_compute_flat_index(indices)?;
+        let inner = Arc::make_mut(&mut self.0);
+        let storage = Arc::make_mut(&mut inner.storage);
+        storage.bump_version();
+        dispatch_all!(storage.dtype(), T => {
+            storage.as_mut_slice::<T>()[index] = T::from_f64(value as f64)
+        });
+        Ok(())
+    }
+
+    /// Calls `f` with the underlying storage of every tensor of `tensors` as a typed slice,
+    /// ignoring shape, strides and offset. Tensors sharing their storage are read through a
+    /// single lock, since locking it again while an in-place write waits for it would deadlock.
+    /// Panics if `T` does not match the dtype of one of the tensors.
+    pub(crate) fn with_data<T: Element, R>(tensors: &[&Tensor], f: impl FnOnce(&[&[T]]) -> R) -> R {
+        let mut guards = Vec::new();
+        let mut owners = Vec::with_capacity(tensors.len());
+        for (i, tensor) in tensors.iter().enumerate() {
+            let shared = tensors[..i]
+                .iter()
+                .position(|other| Arc::ptr_eq(&other.storage, &tensor.storage));
+            match shared {
+                Some(j) => owners.push(owners[j]),
+                None => {
+                    owners.push(guards.len());
+                    guards.push(tensor.data::<T>());
+                }
+            }
+        }
+        let data = owners
+            .iter()
+            .map(|&owner| &*guards[owner])
+            .collect::<Vec<&[T]>>();
+        f(&data)
     }
 }
 
 pub struct InternalTensor {
-    pub(crate) storage: Rc<Storage>,
+    pub(crate) storage: Arc<Storage>,
     pub(crate) shape: Vec<usize>,
     pub(crate) strides: Vec<usize>,
     pub(crate) offset: usize,
 
-    pub(crate) grad: RefCell<Option<Tensor>>,
-    pub(crate) grad_fn: Option<Rc<dyn GradFn>>,
+    pub(crate) grad: Mutex<Option<Tensor>>,
+    pub(crate) grad_fn: Option<Arc<dyn GradFn>>,
     pub(crate) parents: Vec<Tensor>,
     pub(crate) requires_grad: bool,
 }
@@ -165,35 +458,47 @@ impl InternalTensor {
     ///
     /// * `data` - A vector containing the tensor_old data.
     /// * `shape` - A slice representing the shape of the tensor_old.
-    pub fn new(data: Vec<Scalar>, shape: &[usize]) -> Self {
-        assert_eq!(
-            data.len(),
-            shape.iter().product(),
-            "Data length does not match shape dimensions"
-        );
+    pub fn new<T: Element>(data: Vec<T>, shape: &[usize]) -> Self {
+        Self::try_new(data, shape).or_panic()
+    }
+
+    /// Creates a new Tensor with the given data and shape.
+    /// Returns an error if the data length does not match the product of the shape dimensions.
+    ///
+    /// * `data` - A vector containing the tensor_old data.
+    /// * `shape` - A slice representing the shape of the tensor_old.
+    pub fn try_new<T: Element>(data: Vec<T>, shape: &[usize]) -> Result<Self> {
+        if data.len() != shape.iter().product::<usize>() {
+            return Err(TensorError::ShapeMismatch {
+                op: "new: data length does not match shape dimensions",
+                lhs: vec![data.len()],
+                rhs: shape.to_vec(),
+            });
+        }
         let strides = Tensor::compute_strides(shape);
         let offset = 0;
-        let storage = Rc::new(Storage::new(data));
-        InternalTensor {
+        let storage = Arc::new(Storage::new(data));
+        Ok(InternalTensor {
             storage,
             shape: shape.to_vec(),
             strides,
             offset,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: None,
             parents: Vec::new(),
             requires_grad: false,
-        }
+        })
     }
 
-    /// Checks if the tensor_old is stored in contiguous memory.
+    /// Checks if the elements of the tensor_old are laid out densely in row-major order,
+    /// starting at `offset`. Strides of dimensions of size one are irrelevant.
     /// Returns true if the tensor_old is contiguous, false otherwise.
-    pub(crate) fn is_contiguous(&self) -> bool {
-        if self.offset != 0 {
-            return false;
-        }
+    pub fn is_contiguous(&self) -> bool {
         let expected_strides = Tensor::compute_strides(&self.shape);
-        self.strides == expected_strides
+        self.shape
+            .iter()
+            .zip(self.strides.iter().zip(&expected_strides))
+            .all(|(&dim, (stride, expected))| dim == 1 || stride == expected)
     }
 
     /// Returns the shape of the tensor_old as a slice.
@@ -201,11 +506,60 @@ impl InternalTensor {
         &self.shape
     }
 
-    /// Gets the value at the specified multidimensional indices.
+    /// Locks the gradient slot of the tensor_old. A poisoned lock is recovered since the gradient is
+    /// only ever replaced as a whole.
+    pub(crate) fn grad_lock(&self) -> MutexGuard<'_, Option<Tensor>> {
+        self.grad.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the element type of the tensor_old.
+    pub fn dtype(&self) -> DType {
+        self.storage.dtype()
+    }
+
+    /// Returns the underlying storage as a typed slice, ignoring shape, strides and offset.
+    /// Panics if `T` does not match the dtype of the tensor_old.
+    pub(crate) fn data<T: Element>(&self) -> TensorSlice<'_, T> {
+        self.storage.as_slice()
+    }
+
+    /// Applies `f` to every element in logical (row-major) order, honoring strides and offset.
+    /// The result is laid out contiguously. Panics if `T` does not match the dtype of the tensor_old.
+    pub(crate) fn map_data<T: Element, U: Element>(&self, f: impl Fn(T) -> U + Sync) -> Vec<U> {
+        let data = self.data::<T>();
+        let mut result = vec![U::default(); self.numel()];
+        parallel::fill_with(&mut result, |range| {
+            self.storage_indices()
+                .starting_at(range.start)
+                .take(range.len())
+                .map(|index| f(data[index]))
+        });
+        result
+    }
+
+    /// Gets the value at the specified multidimensional indices, converted to `Scalar`.
     /// * `indices` - A slice of indices for each dimension of the tensor_old.
+    ///
     /// Returns the value at the specified indices.
     pub fn get(&self, indices: &[usize]) -> Scalar {
-        self.storage.data[self.compute_flat_index(indices)]
+        self.try_get(indices).or_panic()
+    }
+
+    /// Gets the value at the specified multidimensional indices, converted to `Scalar`.
+    /// Returns an error if the indices do not match the shape of the tensor_old.
+    /// * `indices` - A slice of indices for each dimension of the tensor_old.
+    pub fn try_get(&self, indices: &[usize]) -> Result<Scalar> {
+        let index = self.try_compute_flat_index(indices)?;
+        Ok(dispatch_all!(self.dtype(), T => self.data::<T>()[index].to_f64() as Scalar))
+    }
+
+    /// Gets the value at the specified multidimensional indices without any conversion.
+    /// Panics if `T` does not match the dtype of the tensor_old.
+    /// * `indices` - A slice of indices for each dimension of the tensor_old.
+    ///
+    /// Returns the value at the specified indices.
+    pub fn get_as<T: Element>(&self, indices: &[usize]) -> T {
+        self.data::<T>()[self.compute_flat_index(indices)]
     }
 
     /// Checks if the tensor_old is a scalar (i.e., has shape [1]).
@@ -221,10 +575,16 @@ impl InternalTensor {
     }
 
     pub fn as_scalar(&self) -> Scalar {
+        dispatch_all!(self.dtype(), T => self.item::<T>().to_f64() as Scalar)
+    }
+
+    /// Returns the value of a single element tensor_old without any conversion.
+    /// Panics if the tensor_old is not a scalar or if `T` does not match its dtype.
+    pub fn item<T: Element>(&self) -> T {
         if !self.is_scalar() {
             panic!("Tensor is not a scalar")
         }
-        self.storage.data[self.offset]
+        self.data::<T>()[self.offset]
     }
 
     /// Computes the flat index in the storage for the given multidimensional indices.
@@ -233,28 +593,36 @@ impl InternalTensor {
     /// # Returns
     /// The computed flat index.
     pub(crate) fn compute_flat_index(&self, indices: &[usize]) -> usize {
-        assert_eq!(
-            indices.len(),
-            self.shape.len(),
-            "Number of indices must match tensor dimensions"
-        );
+        self.try_compute_flat_index(indices).or_panic()
+    }
+
+    /// Computes the flat index in the storage for the given multidimensional indices.
+    /// Returns an error if the number of indices does not match the rank or if an index is out of bounds.
+    pub(crate) fn try_compute_flat_index(&self, indices: &[usize]) -> Result<usize> {
+        if indices.len() != self.shape.len() {
+            return Err(TensorError::Rank {
+                op: "index",
+                expected: self.shape.len(),
+                actual: indices.len(),
+            });
+        }
         let mut flat_index = self.offset;
         for (i, &idx) in indices.iter().enumerate() {
-            assert!(idx < self.shape[i], "Index out of bounds for dimension {i}",);
+            check_index("index", idx, self.shape[i])?;
             flat_index += idx * self.strides[i];
         }
-        flat_index
+        Ok(flat_index)
     }
 }
 
 impl Clone for InternalTensor {
     fn clone(&self) -> Self {
         InternalTensor {
-            storage: Rc::clone(&self.storage),
+            storage: Arc::clone(&self.storage),
             shape: self.shape.clone(),
             strides: self.strides.clone(),
             offset: self.offset,
-            grad: RefCell::new(None),
+            grad: Mutex::new(None),
             grad_fn: self.grad_fn.clone(),
             parents: self.parents.clone(),
             requires_grad: self.requires_grad,
@@ -263,22 +631,18 @@ impl Clone for InternalTensor {
 }
 
 impl Tensor {
-    fn debug_min(&self) -> Scalar {
-        *self
-            .storage
-            .data
-            .iter()
-            .min_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap()
+    fn debug_min(&self) -> f64 {
+        dispatch_all!(self.dtype(), T => self
+            .values::<T>()
+            .map(|x| x.to_f64())
+            .fold(f64::INFINITY, f64::min))
     }
 
-    fn debug_max(&self) -> Scalar {
-        *self
-            .storage
-            .data
-            .iter()
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap()
+    fn debug_max(&self) -> f64 {
+        dispatch_all!(self.dtype(), T => self
+            .values::<T>()
+            .map(|x| x.to_f64())
+            .fold(f64::NEG_INFINITY, f64::max))
     }
 }
 
@@ -288,13 +652,14 @@ impl Debug for Tensor {
             .field("shape", &self.shape)
             .field("strides", &self.strides)
             .field("offset", &self.offset)
+            .field("dtype", &self.dtype())
             .field("requires_grad", &self.requires_grad)
             .field(
                 "data",
                 &format_args!(
                     "mean: {:.4}, sum: {:.4}, min: {:.4}, max: {:.4}",
-                    self.mean_scalar().as_scalar(),
-                    self.sum().as_scalar(),
+                    self.to_dtype(DType::F64).mean_scalar().as_scalar(),
+                    self.to_dtype(DType::F64).sum().as_scalar(),
                     self.debug_min(),
                     self.debug_max()
                 ),
@@ -302,8 +667,7 @@ impl Debug for Tensor {
             .field(
                 "grad",
                 &self
-                    .grad
-                    .borrow()
+                    .grad_lock()
                     .as_ref()
                     .map(|grad| format!("Norm {:.4}", grad.norm().as_scalar()))
                     .unwrap_or("None".into()),
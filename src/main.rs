@@ -11,7 +11,7 @@ fn main() {
     );
     let test_batches = mnist.to_batches(&mnist.test_images, &mnist.test_labels, 100, true);
 
-    let mut net = if std::env::args().any(|arg| arg == "--load") {
+    let net = if std::env::args().any(|arg| arg == "--load") {
         load()
     } else if std::env::args().any(|arg| arg == "--train") {
         train(&mnist, None)
@@ -22,7 +22,7 @@ fn main() {
         panic!("Please specify an action: --train, --load, or --test");
     };
 
-    let test_accuracy = mnist.test_model(&test_batches, &mut net);
+    let test_accuracy = mnist.test_model(&test_batches, &net);
     println!("Test accuracy: {:.2}%", test_accuracy * 100.0);
 }
 
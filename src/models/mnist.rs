@@ -1,5 +1,6 @@
-use crate::helpers::metrics::mse;
+use crate::helpers::metrics::{accuracy, mse};
 use crate::helpers::optimizer::Optimizer;
+use crate::linalg::random;
 use crate::linalg::tensor::{Scalar, Tensor};
 use crate::nn::activation::{ReLU, Sigmoid};
 use crate::nn::linear::Linear;
@@ -16,6 +17,7 @@ pub struct MNIST {
 
 pub struct MNISTBatch {
     pub images: Tensor,
+    /// `I64` tensor of shape `[batch_size]` containing the digit of each image
     pub labels: Tensor,
 }
 
@@ -93,12 +95,6 @@ impl MNIST {
         }
     }
 
-    fn label_to_one_hot(label: u8) -> Vec<Scalar> {
-        let mut one_hot = vec![0.0; 10];
-        one_hot[label as usize] = 1.0;
-        one_hot
-    }
-
     pub fn to_batches(
         &self,
         images: &[Vec<u8>],
@@ -110,7 +106,7 @@ impl MNIST {
         let num_batches = images.len() / batch_size;
 
         let mut shuffled_indices: Vec<usize> = (0..images.len()).collect();
-        shuffled_indices.shuffle(&mut rand::rng());
+        random::with_global_generator(|rng| shuffled_indices.shuffle(rng));
 
         for i in 0..num_batches {
             let start = i * batch_size;
@@ -122,10 +118,7 @@ impl MNIST {
                 .flat_map(|&idx| images[idx].iter().map(|&x| x as Scalar / 255.0))
                 .collect();
 
-            let labels: Vec<Scalar> = indices
-                .iter()
-                .flat_map(|&idx| Self::label_to_one_hot(labels[idx]))
-                .collect();
+            let labels: Vec<i64> = indices.iter().map(|&idx| labels[idx] as i64).collect();
 
             let images_tensor = if flat {
                 Tensor::new(images, &[batch_size, 28 * 28])
@@ -133,7 +126,7 @@ impl MNIST {
                 Tensor::new(images, &[batch_size, 28, 28])
             };
 
-            let labels_tensor = Tensor::new(labels, &[batch_size, 10]);
+            let labels_tensor = Tensor::from_vec(labels, &[batch_size]);
 
             batches.push(MNISTBatch {
                 images: images_tensor,
@@ -164,6 +157,7 @@ impl MNIST {
         net
     }
 
+    #[allow(clippy::ptr_arg)] // Part of the public API, which takes the batches as a vector
     pub fn train(
         &self,
         batches: &mut Vec<MNISTBatch>,
@@ -172,40 +166,34 @@ impl MNIST {
         net: &mut NeuralNetwork,
     ) {
         for epoch in 0..epochs {
-            batches.shuffle(&mut rand::rng());
+            random::with_global_generator(|rng| batches.shuffle(rng));
             for (i, batch) in batches.iter().enumerate() {
                 let mut output = net.forward(batch.images.clone());
-                let mut loss = mse(&batch.labels, &output);
+                let mut loss = mse(&batch.labels.one_hot(10), &output);
                 let loss_scalar = loss.as_scalar();
                 println!("Epoch {epoch}: Batch {i} Loss = {loss_scalar}");
                 loss.backward();
                 if i > 0 && i % 10 == 0 {
                     optimizer.step(net.parameters_mut(), true);
-                    net.parameters_mut().iter_mut().for_each(|x| x.detach());
                 }
                 loss.detach();
                 output.detach();
             }
             optimizer.step(net.parameters_mut(), true);
             optimizer.reset();
-            net.parameters_mut().iter_mut().for_each(|x| x.detach());
         }
     }
 
-    pub fn test_model(&self, batches: &Vec<MNISTBatch>, net: &mut NeuralNetwork) -> Scalar {
-        let mut correct = 0;
+    pub fn test_model(&self, batches: &Vec<MNISTBatch>, net: &NeuralNetwork) -> Scalar {
+        let mut correct = 0.0;
         let mut total = 0;
 
         for batch in batches {
-            let output = net.forward(batch.images.clone());
-            for (i, &label) in batch.labels.storage.data.iter().enumerate() {
-                let predicted = output.storage.data[i].round() as u8;
-                if predicted == label as u8 {
-                    correct += 1;
-                }
-                total += 1;
-            }
+            let output = net.predict(&batch.images);
+            let batch_size = batch.labels.numel();
+            correct += accuracy(&batch.labels, &output) * batch_size as Scalar;
+            total += batch_size;
         }
-        correct as Scalar / total as Scalar
+        correct / total as Scalar
     }
 }
@@ -3,8 +3,6 @@ use crate::helpers::optimizer::{Optimizer, SGD};
 use crate::helpers::stopper::*;
 use crate::linalg::tensor::{Scalar, Tensor};
 use crate::nn::{linear::Linear, models::NeuralNetwork};
-use rand::Rng;
-use rand::seq::SliceRandom;
 use std::io::Write;
 
 #[allow(unused)]
@@ -19,9 +17,6 @@ pub fn some(
     let output_size = targets.shape()[1];
     let mut net = NeuralNetwork::init(vec![Box::new(Linear::init(input_size, output_size))]);
 
-    let mut rng = rand::rng();
-    let uniform = rand::distr::Uniform::new(-0.1, 0.1).unwrap();
-
     let mut file = std::fs::File::create("output.csv").expect("Unable to create file");
 
     let mut plateau = PlateauDetector::new(
@@ -32,15 +27,11 @@ pub fn some(
     for epoch in 0..epochs {
         let mut optimizer = SGD::new(learning_rate);
         let mut data: Vec<Scalar> = Vec::new();
-        let mut shuffled_indices = (0..batch_size).collect::<Vec<usize>>();
-        shuffled_indices.shuffle(&mut rng);
+        let shuffled_indices = Tensor::randperm(batch_size);
 
-        let noise: Vec<Scalar> = (0..batch_size)
-            .map(|_| rng.clone().sample(uniform) as Scalar)
-            .collect();
-        let noise_tensor = Tensor::new(noise, &[batch_size, 1]);
-        let input = inputs.gather(0, &shuffled_indices); // + noise_tensor;
-        let target = targets.gather(0, &shuffled_indices);
+        let noise_tensor = Tensor::uniform(&[batch_size, 1], -0.1, 0.1);
+        let input = inputs.index_select(0, &shuffled_indices); // + noise_tensor;
+        let target = targets.index_select(0, &shuffled_indices);
         let output = net.forward(input.clone());
         let mut loss = mse(&target, &output);
         let loss_scalar = loss.as_scalar();
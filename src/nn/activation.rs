@@ -1,3 +1,4 @@
+use crate::linalg::error::Result;
 use crate::linalg::tensor::Tensor;
 use crate::nn::{Dumpable, Layer};
 use std::fs::File;
@@ -7,11 +8,11 @@ use std::io::BufReader;
 pub struct ReLU {}
 
 impl Dumpable for ReLU {
-    fn restore(_reader: &mut BufReader<File>) -> Box<dyn Dumpable>
+    fn restore(_reader: &mut BufReader<File>) -> Result<Box<dyn Layer>>
     where
         Self: Sized,
     {
-        Box::new(ReLU {})
+        Ok(Box::new(ReLU {}))
     }
     fn type_id() -> &'static str {
         "relu"
@@ -28,11 +29,11 @@ impl Layer for ReLU {
 pub struct LogSoftmax;
 
 impl Dumpable for LogSoftmax {
-    fn restore(_reader: &mut BufReader<File>) -> Box<dyn Dumpable>
+    fn restore(_reader: &mut BufReader<File>) -> Result<Box<dyn Layer>>
     where
         Self: Sized,
     {
-        Box::new(LogSoftmax {})
+        Ok(Box::new(LogSoftmax {}))
     }
     fn type_id() -> &'static str {
         "log_softmax"
@@ -41,7 +42,8 @@ impl Dumpable for LogSoftmax {
 
 impl Layer for LogSoftmax {
     fn forward(&self, input: &Tensor) -> Tensor {
-        input.log_softmax()
+        // The class axis is the last one, for both single samples and batches
+        input.log_softmax(input.shape().len() - 1)
     }
 }
 
@@ -49,11 +51,11 @@ impl Layer for LogSoftmax {
 pub struct Softmax;
 
 impl Dumpable for Softmax {
-    fn restore(_reader: &mut BufReader<File>) -> Box<dyn Dumpable>
+    fn restore(_reader: &mut BufReader<File>) -> Result<Box<dyn Layer>>
     where
         Self: Sized,
     {
-        Box::new(Softmax {})
+        Ok(Box::new(Softmax {}))
     }
     fn type_id() -> &'static str {
         "softmax"
@@ -62,18 +64,18 @@ impl Dumpable for Softmax {
 
 impl Layer for Softmax {
     fn forward(&self, input: &Tensor) -> Tensor {
-        input.softmax()
+        input.softmax(input.shape().len() - 1)
     }
 }
 
 #[derive(Default)]
 pub struct Sigmoid;
 impl Dumpable for Sigmoid {
-    fn restore(_reader: &mut BufReader<File>) -> Box<dyn Dumpable>
+    fn restore(_reader: &mut BufReader<File>) -> Result<Box<dyn Layer>>
     where
         Self: Sized,
     {
-        Box::new(Sigmoid {})
+        Ok(Box::new(Sigmoid {}))
     }
     fn type_id() -> &'static str {
         "sigmoid"
@@ -1,6 +1,7 @@
+use crate::linalg::dtype::DType;
+use crate::linalg::error::{Result, TensorError};
 use crate::linalg::tensor::{Scalar, Tensor};
 use crate::nn::{Dumpable, Layer};
-use rand::Rng;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 
@@ -13,19 +14,12 @@ impl Linear {
     pub fn init(n_inputs: usize, n_outputs: usize) -> Self {
         // let fan_avg = (n_inputs + n_outputs) as Scalar / 2.0;
         // let range = rand::distr::Uniform::new(-6.0 / fan_avg.sqrt(), 6.0 / fan_avg.sqrt()).unwrap();
-        let range = rand::distr::Uniform::new(
-            -6.0 / (n_inputs as Scalar).sqrt(),
-            6.0 / (n_inputs as Scalar).sqrt(),
-        )
-        .unwrap();
-        let weights: Vec<Scalar> = rand::rng()
-            .sample_iter(range)
-            .take(n_inputs * n_outputs)
-            .collect();
-        let bias: Vec<Scalar> = rand::rng().sample_iter(range).take(n_outputs).collect();
-
-        let weights = Tensor::with_grad(weights, &[n_inputs, n_outputs]);
-        let bias = Tensor::with_grad(bias, &[1, n_outputs]);
+        let bound = 6.0 / (n_inputs as Scalar).sqrt();
+        let weights = Tensor::uniform(&[n_inputs, n_outputs], -bound, bound);
+        let bias = Tensor::uniform(&[1, n_outputs], -bound, bound);
+
+        let weights = Tensor::with_grad(weights.as_slice().to_vec(), &[n_inputs, n_outputs]);
+        let bias = Tensor::with_grad(bias.as_slice().to_vec(), &[1, n_outputs]);
 
         Linear { weights, bias }
     }
@@ -43,9 +37,10 @@ impl Linear {
 }
 
 impl Dumpable for Linear {
-    fn dump(&self, file: &mut BufWriter<File>) {
-        let weights = &self.weights;
-        let bias = &self.bias;
+    fn dump(&self, file: &mut BufWriter<File>) -> Result<()> {
+        // Parameters are always serialized as f32
+        let weights = &self.weights.to_dtype(DType::F32);
+        let bias = &self.bias.to_dtype(DType::F32);
 
         let mut sizes = weights
             .shape
@@ -60,40 +55,33 @@ impl Dumpable for Linear {
                 .collect::<Vec<u8>>(),
         );
 
-        file.write_all(&sizes)
-            .expect("Unable to write sizes to file");
+        file.write_all(&sizes)?;
         file.write_all(
             &weights
-                .storage
-                .data
-                .iter()
-                .flat_map(|&x| x.to_le_bytes())
+                .values::<f32>()
+                .flat_map(|x| x.to_le_bytes())
                 .collect::<Vec<u8>>(),
-        )
-        .expect("Unable to write weights to file");
+        )?;
         file.write_all(
             &bias
-                .storage
-                .data
-                .iter()
-                .flat_map(|&x| x.to_le_bytes())
+                .values::<f32>()
+                .flat_map(|x| x.to_le_bytes())
                 .collect::<Vec<u8>>(),
-        )
-        .expect("Unable to write bias to file");
+        )?;
+        Ok(())
     }
-    fn restore(file: &mut BufReader<File>) -> Box<dyn Dumpable> {
+    fn restore(file: &mut BufReader<File>) -> Result<Box<dyn Layer>> {
         let mut sizes = [0u8; 32]; // 4 * 8 bytes for 4 usize values
-        file.read_exact(&mut sizes)
-            .expect("Unable to read sizes from file");
+        file.read_exact(&mut sizes)?;
 
-        let weights_shape = (
-            usize::from_le_bytes(sizes[0..8].try_into().unwrap()),
-            usize::from_le_bytes(sizes[8..16].try_into().unwrap()),
-        );
-        let bias_shape = (
-            usize::from_le_bytes(sizes[16..24].try_into().unwrap()),
-            usize::from_le_bytes(sizes[24..32].try_into().unwrap()),
-        );
+        let size = |i: usize| usize::from_le_bytes(sizes[i * 8..(i + 1) * 8].try_into().unwrap());
+        let weights_shape = (size(0), size(1));
+        let bias_shape = (size(2), size(3));
+        if bias_shape.0 != 1 || bias_shape.1 != weights_shape.1 {
+            return Err(TensorError::Format(format!(
+                "linear layer with weights {weights_shape:?} cannot have bias {bias_shape:?}"
+            )));
+        }
 
         let weights_size = weights_shape.0 * weights_shape.1;
         let bias_size = bias_shape.1;
@@ -101,15 +89,13 @@ impl Dumpable for Linear {
         let mut weights_data = vec![0.0; weights_size];
         let mut bias_data = vec![0.0; bias_size];
 
-        file.read_exact(bytemuck::cast_slice_mut(&mut weights_data))
-            .expect("Unable to read weights from file");
-        file.read_exact(bytemuck::cast_slice_mut(&mut bias_data))
-            .expect("Unable to read bias from file");
+        file.read_exact(bytemuck::cast_slice_mut(&mut weights_data))?;
+        file.read_exact(bytemuck::cast_slice_mut(&mut bias_data))?;
 
         let weights = Tensor::with_grad(weights_data, &[weights_shape.0, weights_shape.1]);
         let bias = Tensor::with_grad(bias_data, &[bias_shape.0, bias_shape.1]);
 
-        Box::new(Linear { weights, bias })
+        Ok(Box::new(Linear { weights, bias }))
     }
     fn type_id() -> &'static str {
         "linear"
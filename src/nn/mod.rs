@@ -2,15 +2,16 @@ pub mod activation;
 pub mod linear;
 pub mod models;
 
+use crate::linalg::error::Result;
 use crate::linalg::tensor::Tensor;
-use crate::nn::activation::{LogSoftmax, ReLU, Softmax};
+use crate::nn::activation::{LogSoftmax, ReLU, Sigmoid, Softmax};
 use crate::nn::linear::Linear;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::sync::OnceLock;
 
-type RestoreFn = fn(&mut BufReader<File>) -> Box<dyn Dumpable>;
+type RestoreFn = fn(&mut BufReader<File>) -> Result<Box<dyn Layer>>;
 
 static REGISTRY: OnceLock<HashMap<&'static str, RestoreFn>> = OnceLock::new();
 fn registry() -> &'static HashMap<&'static str, RestoreFn> {
@@ -21,12 +22,14 @@ fn registry() -> &'static HashMap<&'static str, RestoreFn> {
         m.insert(ReLU::type_id(), ReLU::restore as RestoreFn);
         m.insert(LogSoftmax::type_id(), LogSoftmax::restore as RestoreFn);
         m.insert(Softmax::type_id(), Softmax::restore as RestoreFn);
+        m.insert(Sigmoid::type_id(), Sigmoid::restore as RestoreFn);
 
         m
     })
 }
 
-pub trait Layer: Dumpable {
+/// Layers are `Send + Sync` so a trained network can be shared between threads for inference.
+pub trait Layer: Dumpable + LayerTypeId + Send + Sync {
     /// Forward function takes an input tensor and returns the output tensor after applying the layer's operation.
     /// # Arguments
     /// * `input` - A reference to the input Tensor.
@@ -46,17 +49,26 @@ pub trait Layer: Dumpable {
 }
 
 pub trait Dumpable {
-    fn dump(&self, _file: &mut BufWriter<File>) {}
-    fn restore(_reader: &mut BufReader<File>) -> Box<dyn Dumpable>
+    /// Writes the layer's state to the file. Layers without state write nothing.
+    fn dump(&self, _file: &mut BufWriter<File>) -> Result<()> {
+        Ok(())
+    }
+    /// Reads back a layer written by [`Dumpable::dump`].
+    fn restore(_reader: &mut BufReader<File>) -> Result<Box<dyn Layer>>
     where
         Self: Sized;
     fn type_id() -> &'static str
     where
         Self: Sized;
-    fn type_id_instance(&self) -> &'static str
-    where
-        Self: Sized,
-    {
-        Self::type_id()
+}
+
+/// Object-safe access to [`Dumpable::type_id`], used to tag each layer when dumping a network.
+pub trait LayerTypeId {
+    fn type_id_instance(&self) -> &'static str;
+}
+
+impl<T: Dumpable> LayerTypeId for T {
+    fn type_id_instance(&self) -> &'static str {
+        T::type_id()
     }
 }
@@ -1,8 +1,8 @@
+use crate::linalg::autograd::grad_mode::inference_mode;
+use crate::linalg::error::{OrPanic, Result, TensorError};
 use crate::linalg::tensor::Tensor;
 use crate::nn::{Layer, registry};
-use std::any::Any;
 use std::io::{BufRead, Write};
-use std::ops::Deref;
 
 pub struct NeuralNetwork {
     pub layers: Vec<Box<dyn Layer>>,
@@ -14,19 +14,32 @@ impl NeuralNetwork {
     }
 
     pub fn restore(path: &str) -> Self {
+        Self::try_restore(path).or_panic()
+    }
+
+    /// Restores a network written by [`NeuralNetwork::dump_memory`].
+    /// Returns an error if the file cannot be read or is not a valid dump.
+    pub fn try_restore(path: &str) -> Result<Self> {
         let mut nn = NeuralNetwork { layers: vec![] };
-        nn.restore_memory(path);
-        nn
+        nn.restore_memory(path)?;
+        Ok(nn)
     }
 
-    pub fn forward(&mut self, input: Tensor) -> Tensor {
+    pub fn forward(&self, input: Tensor) -> Tensor {
         let mut output = input;
-        for layer in &mut self.layers {
+        for layer in &self.layers {
             output = layer.forward(&output);
         }
         output
     }
 
+    /// Runs the network on `input` without recording a computation graph, for evaluation.
+    /// The output does not require gradients.
+    pub fn predict(&self, input: &Tensor) -> Tensor {
+        let _guard = inference_mode();
+        self.forward(input.clone())
+    }
+
     pub fn parameters_mut(&mut self) -> Vec<&mut Tensor> {
         let mut params = Vec::new();
         for layer in &mut self.layers {
@@ -36,29 +49,36 @@ impl NeuralNetwork {
     }
 
     pub fn dump_memory(&self, path: &str) {
-        let file = std::fs::File::create(path).unwrap();
+        self.try_dump_memory(path).or_panic()
+    }
+
+    /// Writes every layer of the network to `path`, each one preceded by its type id.
+    /// Returns an error if the file cannot be written.
+    pub fn try_dump_memory(&self, path: &str) -> Result<()> {
+        let file = std::fs::File::create(path)?;
         let mut writer = std::io::BufWriter::new(file);
         for layer in &self.layers {
-            writer
-                .write_all(format!("{:?}\n", layer.deref().type_id()).as_bytes())
-                .unwrap();
-            layer.dump(&mut writer);
+            writer.write_all(format!("{}\n", layer.type_id_instance()).as_bytes())?;
+            layer.dump(&mut writer)?;
         }
+        writer.flush()?;
+        Ok(())
     }
 
-    fn restore_memory(&mut self, path: &str) {
-        let file = std::fs::File::open(path).unwrap();
+    fn restore_memory(&mut self, path: &str) -> Result<()> {
+        let file = std::fs::File::open(path)?;
         let mut reader = std::io::BufReader::new(file);
         let mut token = String::new();
-        while reader.read_line(&mut token).unwrap() > 0 {
+        while reader.read_line(&mut token)? > 0 {
             token.pop();
             let type_id = token.as_str();
             let map = registry();
             let restore_fn = map
                 .get(type_id)
-                .unwrap_or_else(|| panic!("Unknown type_id: {type_id}"));
-            restore_fn(&mut reader);
+                .ok_or_else(|| TensorError::Format(format!("unknown layer type id: {type_id}")))?;
+            self.layers.push(restore_fn(&mut reader)?);
             token.clear();
         }
+        Ok(())
     }
 }
@@ -0,0 +1,21 @@
+// The library-wide generator is shared by every test of a binary, and the tests of a binary run
+// concurrently, so reseeding it is only reproducible in a binary of its own.
+use nn_rs::linalg::random::manual_seed;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_manual_seed() {
+    manual_seed(42);
+    let a = Tensor::rand(&[3, 4]);
+    let b = Tensor::randn(&[5]);
+    let c = Tensor::randperm(6);
+
+    manual_seed(42);
+    assert_eq!(Tensor::rand(&[3, 4]).as_slice(), a.as_slice());
+    assert_eq!(Tensor::randn(&[5]).as_slice(), b.as_slice());
+    assert_eq!(
+        Tensor::randperm(6).as_typed_slice::<i64>(),
+        c.as_typed_slice::<i64>()
+    );
+}
@@ -0,0 +1,71 @@
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_log_softmax_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let weights = Tensor::new(vec![1.0, 0.0, 0.0], &[3]);
+    let loss = (&a.log_softmax(0) * &weights).sum();
+    loss.backward();
+
+    // d/dx_j sum_i w_i log_softmax(x)_i = w_j - softmax(x)_j * sum_i w_i
+    let softmax = Tensor::new(vec![1.0, 2.0, 3.0], &[3]).softmax(0);
+    let expected = [
+        1.0 - softmax.as_slice()[0],
+        -softmax.as_slice()[1],
+        -softmax.as_slice()[2],
+    ];
+    for (g, e) in a.grad().unwrap().as_slice().iter().zip(expected) {
+        assert!((g - e).abs() < 1e-6);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_log_softmax_batched_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 0.0, 0.0, 0.0], &[2, 3]);
+    let weights = Tensor::new(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0], &[2, 3]);
+    let loss = (&a.log_softmax(1) * &weights).sum();
+    loss.backward();
+
+    // Each row only sees its own softmax
+    let first = Tensor::new(vec![1.0, 2.0, 3.0], &[3]).softmax(0);
+    let expected = [
+        1.0 - first.as_slice()[0],
+        -first.as_slice()[1],
+        -first.as_slice()[2],
+        -1.0 / 3.0,
+        2.0 / 3.0,
+        -1.0 / 3.0,
+    ];
+    for (g, e) in a.grad().unwrap().as_slice().iter().zip(expected) {
+        assert!((g - e).abs() < 1e-6);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_softmax_grad() {
+    let a = Tensor::with_grad(vec![0.0, 0.0], &[2]);
+    let weights = Tensor::new(vec![1.0, 0.0], &[2]);
+    let loss = (&a.softmax(0) * &weights).sum();
+    loss.backward();
+    // d softmax_0 / dx = softmax_0 * (delta_0j - softmax_j) = [0.25, -0.25]
+    let grad = a.grad().unwrap();
+    assert!((grad.as_slice()[0] - 0.25).abs() < 1e-6);
+    assert!((grad.as_slice()[1] + 0.25).abs() < 1e-6);
+}
+
+#[cfg(test)]
+#[test]
+fn test_logsumexp_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let loss = a.logsumexp(0, false).sum();
+    loss.backward();
+    // The gradient is the softmax over each column
+    let low = 1.0 / (1.0 + 2.0f32.exp());
+    let expected = [low, low, 1.0 - low, 1.0 - low];
+    for (g, e) in a.grad().unwrap().as_slice().iter().zip(expected) {
+        assert!((g - e).abs() < 1e-6);
+    }
+}
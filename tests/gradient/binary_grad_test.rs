@@ -48,3 +48,95 @@ fn test_sub_grad() {
     assert_eq!(grad_a.as_slice(), &expected_grad_a);
     assert_eq!(grad_b.as_slice(), &expected_grad_b);
 }
+
+#[cfg(test)]
+#[test]
+fn test_broadcast_mul_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3, 1]);
+    let b = Tensor::with_grad(vec![4.0, 5.0], &[2]);
+
+    let loss = (&a * &b).sum();
+    loss.backward();
+
+    let grad_a = a.grad().unwrap();
+    let grad_b = b.grad().unwrap();
+
+    assert_eq!(grad_a.shape(), &[3, 1]);
+    assert_eq!(grad_b.shape(), &[2]);
+    assert_eq!(grad_a.as_slice(), &[9.0, 9.0, 9.0]);
+    assert_eq!(grad_b.as_slice(), &[6.0, 6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_broadcast_sub_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let b = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2]);
+
+    let loss = (&a - &b).sum();
+    loss.backward();
+
+    assert_eq!(a.grad().unwrap().as_slice(), &[3.0, 3.0]);
+    assert_eq!(b.grad().unwrap().as_slice(), &[-1.0; 6]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_div_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let b = Tensor::with_grad(vec![2.0, 4.0], &[2, 1]);
+
+    let loss = (&a / &b).sum();
+    loss.backward();
+
+    let grad_a = a.grad().unwrap();
+    let grad_b = b.grad().unwrap();
+
+    // d/da = 1 / b
+    assert_eq!(grad_a.as_slice(), &[0.5, 0.5, 0.25, 0.25]);
+    // d/db = -sum(a) / b^2 over the broadcast row
+    assert_eq!(grad_b.shape(), &[2, 1]);
+    assert_eq!(grad_b.as_slice(), &[-3.0 / 4.0, -7.0 / 16.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_scalar_div_grad() {
+    let b = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let loss = (2.0 / &b).sum();
+    loss.backward();
+    assert_eq!(b.grad().unwrap().as_slice(), &[-2.0, -0.5]);
+
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let loss = (&a / 4.0).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[0.25, 0.25]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_where_grad() {
+    let condition = Tensor::from_vec(vec![true, false, false, true], &[2, 2]);
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let b = Tensor::with_grad(vec![10.0, 20.0], &[2]);
+    let loss = (Tensor::where_(&condition, &a, &b) * 2.0).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[2.0, 0.0, 0.0, 2.0]);
+    // The broadcast branch accumulates the gradient of every row it was selected in
+    assert_eq!(b.grad().unwrap().as_slice(), &[2.0, 2.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_masked_fill_grad() {
+    let x = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let mask = Tensor::from_vec(vec![false, true, false], &[3]);
+    let loss = x
+        .masked_fill(&mask, f32::NEG_INFINITY)
+        .softmax(0)
+        .select(0, 0);
+    loss.backward();
+    let grad = x.grad().unwrap();
+    assert_eq!(grad.get(&[1]), 0.0);
+    assert!(grad.as_slice().iter().all(|g| g.is_finite()));
+}
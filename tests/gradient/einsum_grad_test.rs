@@ -0,0 +1,59 @@
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_einsum_matmul_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let b = Tensor::with_grad(vec![5.0, 6.0, 7.0, 8.0], &[2, 2]);
+    let loss = Tensor::einsum("ij,jk->ik", &[&a, &b]).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[11.0, 15.0, 11.0, 15.0]);
+    assert_eq!(b.grad().unwrap().as_slice(), &[4.0, 4.0, 6.0, 6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_trace_grad() {
+    let a = Tensor::with_grad((1..=9).map(|x| x as f32).collect(), &[3, 3]);
+    let loss = Tensor::einsum("ii", &[&a]);
+    loss.backward();
+    let grad = a.grad().unwrap();
+    assert_eq!(grad.shape(), &[3, 3]);
+    assert_eq!(
+        grad.contiguous().as_slice(),
+        &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_bilinear_grad() {
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let w = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let y = Tensor::with_grad(vec![1.0, -1.0], &[2]);
+    let loss = Tensor::einsum("i,ij,j", &[&x, &w, &y]);
+    loss.backward();
+    // d/dx = W y, d/dW = x y^T, d/dy = W^T x
+    assert_eq!(x.grad().unwrap().contiguous().as_slice(), &[-1.0, -1.0]);
+    assert_eq!(
+        w.grad().unwrap().contiguous().as_slice(),
+        &[1.0, -1.0, 2.0, -2.0]
+    );
+    assert_eq!(y.grad().unwrap().contiguous().as_slice(), &[7.0, 10.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_broadcast_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[1, 3]);
+    let b = Tensor::with_grad(vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0], &[2, 1, 3]);
+    let loss = Tensor::einsum("...j,...j->...", &[&a, &b]).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().shape(), &[1, 3]);
+    assert_eq!(a.grad().unwrap().contiguous().as_slice(), &[3.0, 3.0, 3.0]);
+    assert_eq!(b.grad().unwrap().shape(), &[2, 1, 3]);
+    assert_eq!(
+        b.grad().unwrap().contiguous().as_slice(),
+        &[1.0, 2.0, 3.0, 1.0, 2.0, 3.0]
+    );
+}
@@ -0,0 +1,214 @@
+use nn_rs::linalg::autograd::forward_ad::{DualTensor, jacobian, jvp, try_jvp};
+use nn_rs::linalg::autograd::functional;
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+fn assert_close(actual: &Tensor, expected: &Tensor) {
+    assert_eq!(actual.shape(), expected.shape());
+    let (actual, expected) = (actual.contiguous(), expected.contiguous());
+    for (a, e) in actual.as_slice().iter().zip(expected.as_slice().iter()) {
+        assert!(
+            (a - e).abs() < 1e-5,
+            "{:?} != {:?}",
+            actual.as_slice(),
+            expected.as_slice()
+        );
+    }
+}
+
+fn dual(primal: Vec<f32>, tangent: Vec<f32>, shape: &[usize]) -> DualTensor {
+    DualTensor::new(Tensor::new(primal, shape), Tensor::new(tangent, shape))
+}
+
+#[cfg(test)]
+#[test]
+fn test_elementwise_tangents() {
+    let a = dual(vec![1.0, 2.0], vec![1.0, 0.0], &[2]);
+    let b = dual(vec![4.0, 5.0], vec![0.0, 1.0], &[2]);
+    assert_eq!((&a + &b).tangent().as_slice(), &[1.0, 1.0]);
+    assert_eq!((&a - &b).tangent().as_slice(), &[1.0, -1.0]);
+    // d(ab) = b da + a db
+    assert_eq!((&a * &b).tangent().as_slice(), &[4.0, 2.0]);
+    // d(a / b) = da / b - a db / b^2
+    assert_close(
+        &(&a / &b).tangent(),
+        &Tensor::new(vec![0.25, -2.0 / 25.0], &[2]),
+    );
+    assert_eq!((-&a).tangent().as_slice(), &[-1.0, 0.0]);
+    assert_eq!((&(&a * 3.0) + 1.0).tangent().as_slice(), &[3.0, 0.0]);
+    assert_eq!((&(&a / 2.0) - 1.0).tangent().as_slice(), &[0.5, 0.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_broadcast_tangents() {
+    let a = dual(vec![1.0, 2.0, 3.0], vec![1.0, 1.0, 1.0], &[3]);
+    let b = DualTensor::constant(Tensor::new(vec![1.0, 2.0], &[2, 1]));
+    let y = &a * &b;
+    assert_eq!(y.shape(), &[2, 3]);
+    assert_eq!(y.tangent().shape(), &[2, 3]);
+    assert_eq!(y.tangent().as_slice(), &[1.0, 1.0, 1.0, 2.0, 2.0, 2.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_unary_tangents() {
+    let x = dual(vec![1.0, 4.0], vec![1.0, 2.0], &[2]);
+    assert_eq!(x.pow(3.0).tangent().as_slice(), &[3.0, 96.0]);
+    assert_eq!(x.square().tangent().as_slice(), &[2.0, 16.0]);
+    assert_eq!(x.sqrt().tangent().as_slice(), &[0.5, 0.5]);
+    assert_eq!(x.log().tangent().as_slice(), &[1.0, 0.5]);
+    assert_close(
+        &x.exp().tangent(),
+        &Tensor::new(vec![1.0f32.exp(), 2.0 * 4.0f32.exp()], &[2]),
+    );
+    assert_eq!(x.clamp(0.0, 2.0).tangent().as_slice(), &[1.0, 0.0]);
+
+    let y = dual(vec![-1.0, 0.0, 2.0], vec![1.0, 1.0, 1.0], &[3]);
+    assert_eq!(y.abs().tangent().as_slice(), &[-1.0, 0.0, 1.0]);
+    assert_eq!(y.relu().tangent().as_slice(), &[0.0, 1.0, 1.0]);
+    assert_eq!(y.sigmoid().tangent().as_slice()[1], 0.25);
+}
+
+#[cfg(test)]
+#[test]
+fn test_reduction_tangents() {
+    let x = dual(
+        vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0],
+        vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        &[2, 3],
+    );
+    assert_eq!(x.sum().tangent().as_slice(), &[21.0]);
+    assert_eq!(x.sum_axes(&[1], false).tangent().as_slice(), &[6.0, 15.0]);
+    assert_eq!(
+        x.mean_axes(&[0], true).tangent().as_slice(),
+        &[2.5, 3.5, 4.5]
+    );
+    // The tangent of the selected element is kept
+    let max = x.max_axes(&[1], true);
+    assert_eq!(max.primal().as_slice(), &[5.0, 6.0]);
+    assert_eq!(max.tangent().shape(), &[2, 1]);
+    assert_eq!(max.tangent().as_slice(), &[2.0, 6.0]);
+    assert_eq!(x.min_axes(&[0, 1], false).tangent().as_slice(), &[1.0]);
+    assert_eq!(
+        x.max_axes(&[0], false).tangent().as_slice(),
+        &[4.0, 2.0, 6.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_view_tangents() {
+    let x = dual(
+        vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        vec![6.0, 5.0, 4.0, 3.0, 2.0, 1.0],
+        &[2, 3],
+    );
+    assert_eq!(x.clone().reshape(&[3, 2]).tangent().shape(), &[3, 2]);
+    assert_eq!(
+        x.transpose().tangent().contiguous().as_slice(),
+        &[6.0, 3.0, 5.0, 2.0, 4.0, 1.0]
+    );
+    assert_eq!(x.select(0, 1).tangent().as_slice(), &[3.0, 2.0, 1.0]);
+    assert_eq!(
+        x.narrow(1, 1, 2).tangent().contiguous().as_slice(),
+        &[5.0, 4.0, 2.0, 1.0]
+    );
+    assert_eq!(x.unsqueeze(0).tangent().shape(), &[1, 2, 3]);
+    assert_eq!(x.permute(&[1, 0]).tangent().shape(), &[3, 2]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_batched_transpose_tangent() {
+    let x = dual(
+        vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+        vec![8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0],
+        &[2, 2, 2],
+    );
+    let t = x.transpose();
+    assert_eq!(t.primal().shape(), &[2, 2, 2]);
+    assert_eq!(
+        t.primal().contiguous().as_slice(),
+        &[1.0, 3.0, 2.0, 4.0, 5.0, 7.0, 6.0, 8.0]
+    );
+    assert_eq!(
+        t.tangent().contiguous().as_slice(),
+        &[8.0, 6.0, 7.0, 5.0, 4.0, 2.0, 3.0, 1.0]
+    );
+    assert!(matches!(
+        dual(vec![1.0], vec![1.0], &[1]).try_transpose(),
+        Err(TensorError::Rank { .. })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_constant_has_zero_tangent() {
+    let c = DualTensor::constant(Tensor::new(vec![1.0, 2.0], &[2]));
+    assert_eq!(c.exp().tangent().as_slice(), &[0.0, 0.0]);
+    let labels = DualTensor::constant(Tensor::new(vec![1.0, 0.0], &[2]).to_dtype(DType::I64));
+    assert_eq!(labels.tangent().dtype(), DType::I64);
+}
+
+#[cfg(test)]
+#[test]
+fn test_jvp_matches_reverse_mode() {
+    let x = Tensor::new(vec![0.5, -1.0, 2.0, 0.0, 1.0, -0.5], &[2, 3]);
+    let w = Tensor::new(vec![0.1, 0.2, -0.3, 0.4, 0.5, -0.6], &[3, 2]);
+    let v_x = Tensor::new(vec![1.0, 0.0, -1.0, 0.5, 0.5, 0.0], &[2, 3]);
+    let v_w = Tensor::new(vec![0.0, 1.0, 1.0, 0.0, -1.0, 2.0], &[3, 2]);
+
+    let (output, tangent) = jvp(
+        |inputs| {
+            let hidden = inputs[0].matmul(&inputs[1]).sigmoid();
+            (&hidden.log_softmax(1) + &hidden.logsumexp(0, true)).mean_axes(&[0], false)
+        },
+        &[&x, &w],
+        &[&v_x, &v_w],
+    );
+    let (expected_output, expected_tangent) = functional::jvp(
+        |inputs| {
+            let hidden = inputs[0].matmul(&inputs[1]).sigmoid();
+            (&hidden.log_softmax(1) + &hidden.logsumexp(0, true)).mean_axes(&[0], false)
+        },
+        &[&x, &w],
+        &[&v_x, &v_w],
+    );
+    assert_close(&output, &expected_output);
+    assert_close(&tangent, &expected_tangent);
+}
+
+#[cfg(test)]
+#[test]
+fn test_jacobian_matches_reverse_mode() {
+    let x = Tensor::new(vec![1.0, 2.0], &[2]);
+    let w = Tensor::new(vec![0.5, -1.0, 2.0, 0.0, 1.0, -0.5], &[2, 3]);
+    let forward = jacobian(|inputs| inputs[0].matmul(&inputs[1]).softmax(0), &[&x, &w]);
+    let reverse = functional::jacobian(|inputs| inputs[0].matmul(&inputs[1]).softmax(0), &[&x, &w]);
+    assert_eq!(forward[0].shape(), &[3, 2]);
+    assert_eq!(forward[1].shape(), &[3, 2, 3]);
+    assert_close(&forward[0], &reverse[0]);
+    assert_close(&forward[1], &reverse[1]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_jvp_errors() {
+    let x = Tensor::new(vec![1.0, 2.0], &[2]);
+    let v = Tensor::new(vec![1.0], &[1]);
+    assert!(matches!(
+        try_jvp(|inputs| inputs[0].clone(), &[&x], &[&v]),
+        Err(TensorError::ShapeMismatch { op: "dual", .. })
+    ));
+    assert!(matches!(
+        try_jvp(|inputs| inputs[0].clone(), &[&x], &[]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    let ints = x.to_dtype(DType::I64);
+    assert!(matches!(
+        DualTensor::try_new(ints.clone(), ints),
+        Err(TensorError::DType { .. })
+    ));
+}
@@ -0,0 +1,118 @@
+use nn_rs::linalg::autograd::function::{Context, Function};
+use nn_rs::linalg::autograd::grad_mode::no_grad;
+use nn_rs::linalg::error::{Result, TensorError};
+use nn_rs::linalg::tensor::Tensor;
+
+/// Computes `scale * a * b`, saving both inputs.
+struct ScaledProduct {
+    scale: f32,
+}
+
+impl Function for ScaledProduct {
+    fn forward(&self, ctx: &mut Context, inputs: &[&Tensor]) -> Result<Tensor> {
+        ctx.save_for_backward(&[inputs[0], inputs[1]]);
+        Ok(inputs[0].try_mul(inputs[1])? * self.scale)
+    }
+
+    fn backward(&self, ctx: &Context, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+        let [a, b] = ctx.saved_tensors() else {
+            panic!("two tensors are saved")
+        };
+        let grad = grad_output * self.scale;
+        vec![
+            ctx.needs_input_grad(0).then(|| &grad * b),
+            ctx.needs_input_grad(1).then(|| &grad * a),
+        ]
+    }
+}
+
+/// Rounds to the nearest integer, passing gradients straight through.
+struct StraightThroughRound;
+
+impl Function for StraightThroughRound {
+    fn forward(&self, _ctx: &mut Context, inputs: &[&Tensor]) -> Result<Tensor> {
+        let input = inputs[0].contiguous();
+        let rounded = input.as_slice().iter().map(|x| x.round()).collect();
+        Ok(Tensor::new(rounded, input.shape()))
+    }
+
+    fn backward(&self, _ctx: &Context, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+        vec![Some(grad_output.clone())]
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_function_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let b = Tensor::with_grad(vec![4.0, 5.0, 6.0], &[3]);
+    let y = ScaledProduct { scale: 2.0 }.apply(&[&a, &b]);
+    assert_eq!(y.as_slice(), &[8.0, 20.0, 36.0]);
+    // The function composes with built-in operations on both sides
+    (&(y * 0.5) + &a).sum().backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[5.0, 6.0, 7.0]);
+    assert_eq!(b.grad().unwrap().as_slice(), &[1.0, 2.0, 3.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_function_skips_inputs_without_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let b = Tensor::new(vec![3.0, 4.0], &[1, 2]);
+    let y = ScaledProduct { scale: 1.0 }.apply(&[&a, &b]);
+    assert_eq!(y.shape(), &[1, 2]);
+    y.sum().backward();
+    // The gradient of the broadcast input is summed back to its shape
+    assert_eq!(a.grad().unwrap().as_slice(), &[3.0, 4.0]);
+    assert!(b.grad().is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn test_function_non_differentiable_forward() {
+    let x = Tensor::with_grad(vec![0.4, 1.6], &[2]);
+    let y = StraightThroughRound.apply(&[&x]);
+    assert_eq!(y.as_slice(), &[0.0, 2.0]);
+    (y * 3.0).sum().backward();
+    assert_eq!(x.grad().unwrap().as_slice(), &[3.0, 3.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_function_records_nothing_without_grad() {
+    let a = Tensor::new(vec![1.0], &[1]);
+    assert!(
+        !ScaledProduct { scale: 1.0 }
+            .apply(&[&a, &a])
+            .requires_grad()
+    );
+    let w = Tensor::with_grad(vec![1.0], &[1]);
+    let _guard = no_grad();
+    assert!(
+        !ScaledProduct { scale: 1.0 }
+            .apply(&[&w, &w])
+            .requires_grad()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_function_errors() {
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let b = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    assert!(matches!(
+        ScaledProduct { scale: 1.0 }.try_apply(&[&a, &b]),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "modified in place")]
+fn test_function_saved_tensor_modified() {
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let mut b = Tensor::new(vec![3.0, 4.0], &[2]);
+    let y = ScaledProduct { scale: 1.0 }.apply(&[&a, &b]);
+    b.fill_(0.0);
+    y.sum().backward();
+}
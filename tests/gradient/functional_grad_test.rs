@@ -0,0 +1,222 @@
+use nn_rs::linalg::autograd::functional::{
+    grad, grad_with, hessian, jacobian, jvp, try_grad, try_hessian, try_jacobian, vjp,
+};
+use nn_rs::linalg::autograd::grad_mode::{inference_mode, no_grad};
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_grad_leaves_grad_untouched() {
+    let x = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let w = Tensor::with_grad(vec![2.0], &[1]);
+    let y = (&x * &w).sum();
+    let grads = grad(&[&y], &[&x, &w], None);
+    assert_eq!(grads[0].as_slice(), &[2.0, 2.0, 2.0]);
+    assert_eq!(grads[1].as_slice(), &[6.0]);
+    assert!(x.grad().is_none());
+    assert!(w.grad().is_none());
+    assert!(!grads[0].requires_grad());
+
+    // The graph is kept, so it can be differentiated again
+    assert_eq!(grad(&[&y], &[&w], None)[0].as_slice(), &[6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_grad_with_grad_outputs() {
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let y = x.square();
+    let v = Tensor::new(vec![1.0, -1.0], &[2]);
+    assert_eq!(grad(&[&y], &[&x], Some(&[&v]))[0].as_slice(), &[2.0, -4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_grad_of_intermediate_and_unused_inputs() {
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let unused = Tensor::with_grad(vec![5.0], &[1]);
+    let hidden = &x * 3.0;
+    let y = hidden.square().sum();
+    let grads = grad(&[&y], &[&hidden, &unused], None);
+    assert_eq!(grads[0].as_slice(), &[6.0, 12.0]);
+    assert_eq!(grads[1].as_slice(), &[0.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_grad_of_several_outputs() {
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let a = x.sum();
+    let b = x.square().sum();
+    assert_eq!(grad(&[&a, &b], &[&x], None)[0].as_slice(), &[3.0, 5.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_grad_with_create_graph() {
+    let x = Tensor::with_grad(vec![2.0], &[1]);
+    let y = x.pow(3.0).sum();
+    let first = grad_with(&[&y], &[&x], None, true).remove(0);
+    assert!(first.requires_grad());
+    let second = grad(&[&first.sum()], &[&x], None).remove(0);
+    assert_eq!(second.as_slice(), &[12.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_try_grad_errors() {
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let y = x.square();
+    let v = Tensor::new(vec![1.0], &[1]);
+    assert!(matches!(
+        try_grad(&[&y], &[&x], Some(&[&v])),
+        Err(TensorError::ShapeMismatch { op: "grad", .. })
+    ));
+    assert!(matches!(
+        try_grad(&[&y], &[&x], Some(&[])),
+        Err(TensorError::InvalidArgument(_))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_vjp() {
+    let x = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    let v = Tensor::new(vec![1.0, 0.0, 2.0], &[3]);
+    let (output, grads) = vjp(|inputs| inputs[0].square(), &[&x], &v);
+    assert_eq!(output.as_slice(), &[1.0, 4.0, 9.0]);
+    assert!(!output.requires_grad());
+    assert_eq!(grads[0].as_slice(), &[2.0, 0.0, 12.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_jvp() {
+    // f(x, w) = x @ w, so J v = v_x @ w + x @ v_w
+    let x = Tensor::new(vec![1.0, 2.0], &[1, 2]);
+    let w = Tensor::new(vec![1.0, 0.0, 0.0, 1.0], &[2, 2]);
+    let v_x = Tensor::new(vec![1.0, 1.0], &[1, 2]);
+    let v_w = Tensor::new(vec![0.0, 1.0, 0.0, 0.0], &[2, 2]);
+    let (output, product) = jvp(
+        |inputs| inputs[0].matmul(&inputs[1]),
+        &[&x, &w],
+        &[&v_x, &v_w],
+    );
+    assert_eq!(output.as_slice(), &[1.0, 2.0]);
+    assert_eq!(product.shape(), &[1, 2]);
+    assert_eq!(product.as_slice(), &[1.0, 2.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_jvp_through_nonlinear_ops() {
+    let x = Tensor::new(vec![0.0, 1.0], &[2]);
+    let v = Tensor::new(vec![1.0, 2.0], &[2]);
+    let (_, product) = jvp(|inputs| inputs[0].exp().max_axes(&[0], false), &[&x], &[&v]);
+    // Only the maximum e^1 moves, by e^1 * 2
+    assert!((product.as_slice()[0] - 2.0 * 1.0f32.exp()).abs() < 1e-5);
+}
+
+#[cfg(test)]
+#[test]
+fn test_jacobian() {
+    let x = Tensor::new(vec![1.0, 2.0], &[2]);
+    let w = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let jacobians = jacobian(|inputs| inputs[0].matmul(&inputs[1]), &[&x, &w]);
+    // d(x @ w)_j / dx_i = w_ij
+    assert_eq!(jacobians[0].shape(), &[3, 2]);
+    assert_eq!(jacobians[0].as_slice(), &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+    // d(x @ w)_j / dw_ik = x_i if j == k
+    assert_eq!(jacobians[1].shape(), &[3, 2, 3]);
+    assert_eq!(
+        jacobians[1].as_slice(),
+        &[
+            1.0, 0.0, 0.0, 2.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, 2.0, 0.0, //
+            0.0, 0.0, 1.0, 0.0, 0.0, 2.0,
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_jacobian_of_constant_function() {
+    let x = Tensor::new(vec![1.0, 2.0], &[2]);
+    let jacobians = jacobian(|_| Tensor::ones(&[3]), &[&x]);
+    assert_eq!(jacobians[0].shape(), &[3, 2]);
+    assert_eq!(jacobians[0].as_slice(), &[0.0; 6]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_hessian() {
+    // f(x, y) = x0^2 y + x1 y^2
+    let x = Tensor::new(vec![1.0, 2.0], &[2]);
+    let y = Tensor::new(vec![3.0], &[1]);
+    let h = hessian(
+        |inputs| {
+            let [x, y] = inputs else { unreachable!() };
+            (&(&x.select(0, 0).square() * y) + &(&x.select(0, 1) * &y.square())).sum()
+        },
+        &[&x, &y],
+    );
+    assert_eq!(h[0][0].shape(), &[2, 2]);
+    assert_eq!(h[0][0].as_slice(), &[6.0, 0.0, 0.0, 0.0]);
+    assert_eq!(h[0][1].shape(), &[2, 1]);
+    assert_eq!(h[0][1].as_slice(), &[2.0, 6.0]);
+    assert_eq!(h[1][0].as_slice(), &[2.0, 6.0]);
+    assert_eq!(h[1][1].as_slice(), &[4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_hessian_of_prod_with_zero() {
+    let x = Tensor::new(vec![0.0, 2.0, 3.0], &[3]);
+    let h = hessian(|inputs| inputs[0].prod_axes(&[0], false), &[&x]);
+    assert_eq!(
+        h[0][0].as_slice(),
+        &[0.0, 3.0, 2.0, 3.0, 0.0, 0.0, 2.0, 0.0, 0.0]
+    );
+
+    // f(x) = x00 x10 + x01 x11, reduced along the first axis
+    let x = Tensor::new(vec![0.0, 2.0, 3.0, 0.0], &[2, 2]);
+    let h = hessian(|inputs| inputs[0].prod_axes(&[0], false).sum(), &[&x]);
+    assert_eq!(h[0][0].shape(), &[2, 2, 2, 2]);
+    assert_eq!(
+        h[0][0].as_slice(),
+        &[
+            0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_functional_within_no_grad() {
+    let x = Tensor::new(vec![3.0], &[1]);
+    let _guard = no_grad();
+    let h = hessian(|inputs| inputs[0].pow(3.0).sum(), &[&x]);
+    assert_eq!(h[0][0].as_slice(), &[18.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_functional_errors() {
+    let x = Tensor::new(vec![1.0, 2.0], &[2]);
+    assert!(matches!(
+        try_hessian(|inputs| inputs[0].square(), &[&x]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    let ints = x.to_dtype(DType::I64);
+    assert!(matches!(
+        try_jacobian(|inputs| inputs[0].clone(), &[&ints]),
+        Err(TensorError::DType { .. })
+    ));
+    let _guard = inference_mode();
+    assert!(matches!(
+        try_jacobian(|inputs| inputs[0].square(), &[&x]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+}
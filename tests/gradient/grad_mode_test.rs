@@ -0,0 +1,76 @@
+use nn_rs::linalg::autograd::grad_mode::{
+    enable_grad, inference_mode, is_grad_enabled, is_inference_mode_enabled, no_grad,
+};
+use nn_rs::linalg::tensor::Tensor;
+use nn_rs::nn::linear::Linear;
+use nn_rs::nn::models::NeuralNetwork;
+
+#[cfg(test)]
+#[test]
+fn test_no_grad_stops_recording() {
+    let w = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    {
+        let _guard = no_grad();
+        assert!(!is_grad_enabled());
+        let y = w.matmul(&w).exp().sum_axis(0).select(0, 1);
+        assert!(!y.requires_grad());
+        let joined = Tensor::cat(&[&w, &w.transpose()], 0);
+        assert!(!joined.requires_grad());
+        // Leaves created in the scope still require gradients
+        assert!(Tensor::with_grad(vec![1.0], &[1]).requires_grad());
+    }
+    assert!(is_grad_enabled());
+    assert!(w.matmul(&w).requires_grad());
+}
+
+#[cfg(test)]
+#[test]
+fn test_grad_mode_guards_nest() {
+    let w = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let _outer = no_grad();
+    {
+        let _inner = enable_grad();
+        let loss = (&w * &w).sum();
+        assert!(loss.requires_grad());
+        loss.backward();
+    }
+    assert!(!is_grad_enabled());
+    assert_eq!(w.grad().unwrap().as_slice(), &[2.0, 4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_inference_mode_cannot_be_lifted() {
+    let w = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    {
+        let _guard = inference_mode();
+        assert!(is_inference_mode_enabled());
+        let _inner = enable_grad();
+        assert!(!is_grad_enabled());
+        assert!(!(&w * 2.0).requires_grad());
+    }
+    assert!(!is_inference_mode_enabled());
+    assert!(is_grad_enabled());
+}
+
+#[cfg(test)]
+#[test]
+fn test_grad_mode_is_per_thread() {
+    let _guard = no_grad();
+    let enabled = std::thread::spawn(is_grad_enabled).join().unwrap();
+    assert!(enabled);
+    assert!(!is_grad_enabled());
+}
+
+#[cfg(test)]
+#[test]
+fn test_predict_does_not_record() {
+    let weights = Tensor::with_grad(vec![1.0, 2.0], &[2, 1]);
+    let bias = Tensor::with_grad(vec![0.5], &[1, 1]);
+    let net = NeuralNetwork::init(vec![Box::new(Linear::from_parameters(weights, bias))]);
+    let input = Tensor::new(vec![1.0, 1.0], &[1, 2]);
+    let prediction = net.predict(&input);
+    assert!(!prediction.requires_grad());
+    assert_eq!(prediction.as_slice(), &[3.5]);
+    assert!(net.forward(input).requires_grad());
+}
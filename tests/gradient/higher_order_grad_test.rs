@@ -0,0 +1,158 @@
+use nn_rs::linalg::autograd::function::{Context, Function};
+use nn_rs::linalg::error::Result;
+use nn_rs::linalg::tensor::Tensor;
+
+fn assert_close(actual: &Tensor, expected: &[f32]) {
+    let actual = actual.contiguous();
+    assert_eq!(actual.numel(), expected.len());
+    for (a, e) in actual.as_slice().iter().zip(expected) {
+        assert!(
+            (a - e).abs() < 1e-5,
+            "{:?} != {expected:?}",
+            actual.as_slice()
+        );
+    }
+}
+
+/// Computes the first derivative of `y` with respect to `x` with a graph, then the derivative of
+/// its sum with respect to `x`.
+fn second_derivative(x: &Tensor, y: &Tensor) -> (Tensor, Tensor) {
+    y.backward_with(true);
+    let first = x.grad().unwrap();
+    x.zero_grad();
+    first.sum().backward();
+    (first, x.grad().unwrap())
+}
+
+#[cfg(test)]
+#[test]
+fn test_backward_without_graph_has_no_history() {
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    x.pow(3.0).sum().backward();
+    let grad = x.grad().unwrap();
+    assert!(!grad.requires_grad());
+    assert_eq!(grad.as_slice(), &[3.0, 12.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_pow_second_derivative() {
+    let x = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let (first, second) = second_derivative(&x, &x.pow(3.0).sum());
+    assert!(first.requires_grad());
+    assert_eq!(first.as_slice(), &[3.0, 12.0, 27.0]);
+    assert_eq!(second.as_slice(), &[6.0, 12.0, 18.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_mul_second_derivative() {
+    let x = Tensor::with_grad(vec![1.0, -2.0], &[2]);
+    let y = (&(&x * &x) * &x).sum();
+    let (_, second) = second_derivative(&x, &y);
+    assert_eq!(second.as_slice(), &[6.0, -12.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_exp_second_derivative() {
+    let x = Tensor::with_grad(vec![0.0, 1.0], &[2]);
+    let (first, second) = second_derivative(&x, &x.exp().sum());
+    let expected = [1.0, 1.0f32.exp()];
+    assert_close(&first, &expected);
+    assert_close(&second, &expected);
+}
+
+#[cfg(test)]
+#[test]
+fn test_sigmoid_second_derivative() {
+    let x = Tensor::with_grad(vec![0.0, 1.0], &[2]);
+    let (_, second) = second_derivative(&x, &x.sigmoid().sum());
+    let s = 1.0 / (1.0 + (-1.0f32).exp());
+    assert_close(&second, &[0.0, s * (1.0 - s) * (1.0 - 2.0 * s)]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_prod_second_derivative() {
+    let x = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let (first, second) = second_derivative(&x, &x.prod_axes(&[0], false).sum());
+    assert_eq!(first.as_slice(), &[6.0, 3.0, 2.0]);
+    assert_eq!(second.as_slice(), &[5.0, 4.0, 3.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_max_second_derivative() {
+    let x = Tensor::with_grad(vec![1.0, 3.0, 4.0, 2.0], &[2, 2]);
+    let y = x.max_axes(&[1], false).square().sum();
+    let (first, second) = second_derivative(&x, &y);
+    assert_eq!(first.as_slice(), &[0.0, 6.0, 8.0, 0.0]);
+    assert_eq!(second.as_slice(), &[0.0, 2.0, 2.0, 0.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_view_second_derivative() {
+    let x = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let (first, second) = second_derivative(&x, &x.select(0, 1).pow(3.0).sum());
+    assert_eq!(first.as_slice(), &[0.0, 0.0, 27.0, 48.0]);
+    assert_eq!(second.as_slice(), &[0.0, 0.0, 18.0, 24.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_logsumexp_gradient_penalty() {
+    // The gradient of logsumexp is the softmax p, and the gradient of sum(p^2) is
+    // 2 p_j^2 - 2 p_j sum(p^2)
+    let x = Tensor::with_grad(vec![0.0, 3.0f32.ln()], &[2]);
+    x.logsumexp(0, false).sum().backward_with(true);
+    let softmax = x.grad().unwrap();
+    assert_close(&softmax, &[0.25, 0.75]);
+    x.zero_grad();
+    softmax.square().sum().backward();
+    assert_close(&x.grad().unwrap(), &[-0.1875, 0.1875]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_matmul_gradient_penalty() {
+    // The gradient of sum(x @ w) with respect to x holds the row sums of w, and penalizing its
+    // squared norm gives w the gradient 2 * rowsum in every column
+    let x = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[1, 3]);
+    let w = Tensor::with_grad(vec![1.0, 2.0, 0.0, -1.0, 3.0, 1.0], &[3, 2]);
+    x.matmul(&w).sum().backward_with(true);
+    let grad_x = x.grad().unwrap();
+    assert_eq!(grad_x.as_slice(), &[3.0, -1.0, 4.0]);
+    x.zero_grad();
+    w.zero_grad();
+    grad_x.square().sum().backward();
+    assert!(x.grad().is_none());
+    assert_eq!(
+        w.grad().unwrap().as_slice(),
+        &[6.0, 6.0, -2.0, -2.0, 8.0, 8.0]
+    );
+}
+
+/// Computes `x^3`, with a backward made of differentiable operations.
+struct Cube;
+
+impl Function for Cube {
+    fn forward(&self, ctx: &mut Context, inputs: &[&Tensor]) -> Result<Tensor> {
+        ctx.save_for_backward(&[inputs[0]]);
+        Ok(&inputs[0].square() * inputs[0])
+    }
+
+    fn backward(&self, ctx: &Context, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+        let x = &ctx.saved_tensors()[0];
+        vec![Some(grad_output * &(x.square() * 3.0))]
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_function_second_derivative() {
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let (_, second) = second_derivative(&x, &Cube.apply(&[&x]).sum());
+    assert_eq!(second.as_slice(), &[6.0, 12.0]);
+}
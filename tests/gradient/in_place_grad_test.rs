@@ -0,0 +1,75 @@
+use nn_rs::helpers::optimizer::{Optimizer, SGD};
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_in_place_on_leaf_keeps_it_a_leaf() {
+    let mut w = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    w.sub_(&Tensor::new(vec![0.5, 0.5], &[2]));
+    assert!(w.requires_grad());
+    let loss = (&w * &w).sum();
+    loss.backward();
+    assert_eq!(w.grad().unwrap().as_slice(), &[1.0, 3.0]);
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "modified in place")]
+fn test_backward_after_in_place_panics() {
+    let mut w = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let loss = (&w * &w).sum();
+    w.add_(&Tensor::from_scalar(1.0));
+    loss.backward();
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "modified in place")]
+fn test_backward_after_in_place_on_constant_panics() {
+    let w = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let mut scale = Tensor::new(vec![3.0, 4.0], &[2]);
+    let loss = (&w * &scale).sum();
+    scale.fill_(0.0);
+    loss.backward();
+}
+
+#[cfg(test)]
+#[test]
+fn test_sgd_updates_parameters_in_place() {
+    let mut w = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let mut optimizer = SGD::new(0.5);
+    for _ in 0..2 {
+        let loss = (&w * &w).sum();
+        loss.backward();
+        drop(loss);
+        optimizer.step(vec![&mut w], true);
+    }
+    // Each step halves the parameters, which stay leaves of the next graph
+    assert_eq!(w.as_slice(), &[0.0, 0.0]);
+    assert!(w.requires_grad());
+    assert!(w.grad().is_none());
+    assert_eq!(w.version(), 2);
+}
+
+#[cfg(test)]
+#[test]
+fn test_in_place_on_operand_not_saved_keeps_backward() {
+    // Addition does not read its operands to compute their gradients
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let mut b = Tensor::new(vec![3.0, 4.0], &[2]);
+    let c = (&a + &b).sum();
+    b.add_(&Tensor::from_scalar(1.0));
+    c.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[1.0, 1.0]);
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "modified in place")]
+fn test_backward_after_in_place_through_view_panics() {
+    let w = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let x = Tensor::with_grad(vec![1.0, 1.0], &[2]);
+    let loss = (&x * &w.select(0, 0)).sum();
+    w.select(0, 0).fill_(0.0);
+    loss.backward();
+}
@@ -0,0 +1,81 @@
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_gather_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let index = Tensor::from_vec(vec![2i64, 2, 0, 1], &[2, 2]);
+    let loss = a.gather(1, &index).sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[0.0, 0.0, 2.0, 1.0, 1.0, 0.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_label_indexed_loss_grad() {
+    // Negative log-likelihood of the labels, read with gather
+    let log_probs = Tensor::with_grad(vec![-0.5, -1.0, -2.0, -0.1], &[2, 2]);
+    let labels = Tensor::from_vec(vec![1i64, 0], &[2, 1]);
+    let loss = -log_probs.gather(1, &labels).mean_scalar();
+    loss.backward();
+    assert_eq!(
+        log_probs.grad().unwrap().as_slice(),
+        &[0.0, -0.5, -0.5, 0.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_scatter_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let src = Tensor::with_grad(vec![10.0, 20.0], &[2]);
+    let index = Tensor::from_vec(vec![2i64, 0], &[2]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    let loss = (a.scatter(0, &index, &src) * weights).sum();
+    loss.backward();
+    // Overwritten elements of the input get no gradient
+    assert_eq!(a.grad().unwrap().as_slice(), &[0.0, 2.0, 0.0]);
+    assert_eq!(src.grad().unwrap().as_slice(), &[3.0, 1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_scatter_add_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let src = Tensor::with_grad(vec![1.0, 1.0, 1.0], &[3]);
+    let index = Tensor::from_vec(vec![1i64, 1, 0], &[3]);
+    let weights = Tensor::new(vec![2.0, 3.0], &[2]);
+    let loss = (a.scatter_add(0, &index, &src) * weights).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[2.0, 3.0]);
+    assert_eq!(src.grad().unwrap().as_slice(), &[3.0, 3.0, 2.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_index_add_grad() {
+    let a = Tensor::with_grad(vec![0.0; 4], &[2, 2]);
+    let source = Tensor::with_grad(vec![1.0; 6], &[3, 2]);
+    let index = Tensor::from_vec(vec![1i64, 0, 1], &[3]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let loss = (a.index_add(0, &index, &source) * weights).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(
+        source.grad().unwrap().as_slice(),
+        &[3.0, 4.0, 1.0, 2.0, 3.0, 4.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_masked_select_grad() {
+    let a = Tensor::with_grad(vec![1.0, -2.0, 3.0, -4.0], &[2, 2]);
+    let mask = Tensor::from_vec(vec![true, false], &[2]);
+    let loss = (a.masked_select(&mask) * 2.0).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[2.0, 0.0, 2.0, 0.0]);
+}
@@ -28,3 +28,46 @@ fn test_matmul_grad() {
     assert_eq!(grad_a.as_slice(), &expected_grad_a);
     assert_eq!(grad_b.as_slice(), &expected_grad_b);
 }
+
+#[cfg(test)]
+#[test]
+fn test_matmul_vector_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let v = Tensor::with_grad(vec![1.0, -1.0], &[2]);
+    let loss = a.matmul(&v).sum();
+    loss.backward();
+    assert_eq!(v.grad().unwrap().shape(), &[2]);
+    assert_eq!(v.grad().unwrap().as_slice(), &[4.0, 6.0]);
+    assert_eq!(a.grad().unwrap().as_slice(), &[1.0, -1.0, 1.0, -1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_bmm_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[2, 2, 2]);
+    let b = Tensor::with_grad(vec![1.0, 0.0, 0.0, 1.0, 2.0, 0.0, 0.0, 2.0], &[2, 2, 2]);
+    let loss = a.bmm(&b).sum();
+    loss.backward();
+    // dL/dA = 1 @ B^T, dL/dB = A^T @ 1 for every batch
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0]
+    );
+    assert_eq!(
+        b.grad().unwrap().as_slice(),
+        &[4.0, 4.0, 6.0, 6.0, 12.0, 12.0, 14.0, 14.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_matmul_broadcast_batch_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[2, 2, 2]);
+    let w = Tensor::with_grad(vec![1.0, 0.0, 0.0, 1.0], &[2, 2]);
+    let loss = a.matmul(&w).sum();
+    loss.backward();
+    // The gradient of the shared matrix is summed over the batch
+    assert_eq!(w.grad().unwrap().shape(), &[2, 2]);
+    assert_eq!(w.grad().unwrap().as_slice(), &[16.0, 16.0, 20.0, 20.0]);
+    assert_eq!(a.grad().unwrap().as_slice(), &[1.0; 8]);
+}
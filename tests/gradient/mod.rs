@@ -1,5 +1,15 @@
+mod activation_grad_test;
 mod binary_grad_test;
+mod einsum_grad_test;
+mod forward_ad_test;
+mod function_grad_test;
+mod functional_grad_test;
+mod grad_mode_test;
+mod higher_order_grad_test;
+mod in_place_grad_test;
+mod index_grad_test;
 mod layer_grad_test;
 mod matmul_grad_test;
 mod reduce_grad_test;
 mod unary_grad_test;
+mod view_grad_test;
@@ -12,3 +12,119 @@ fn test_mean_scalar_grad() {
     assert_eq!(Tensor::reduce_shape(grad_a.shape()), expected_shape);
     assert_eq!(grad_a.as_slice(), &expected_grad_a);
 }
+
+#[cfg(test)]
+#[test]
+fn test_max_grad() {
+    let a = Tensor::with_grad(vec![1.0, 5.0, 3.0, 2.0], &[2, 2]);
+    let loss = a.max() * 2.0;
+    loss.backward();
+    assert_eq!(a.grad().unwrap().shape(), &[2, 2]);
+    assert_eq!(a.grad().unwrap().as_slice(), &[0.0, 2.0, 0.0, 0.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_index_select_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    // Index 2 is gathered twice, so its gradient accumulates
+    let index = Tensor::from_vec(vec![2i64, 0, 2], &[3]);
+    let loss = a.index_select(1, &index).sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[1.0, 0.0, 2.0, 1.0, 0.0, 2.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_index_select_axis0_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let index = Tensor::from_vec(vec![1i64, 2], &[2]);
+    let loss = (&a.index_select(0, &index) * &weights).sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[0.0, 0.0, 1.0, 2.0, 3.0, 4.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_sum_axes_grad() {
+    let a = Tensor::with_grad((1..=12).map(|x| x as f32).collect(), &[2, 3, 2]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 1, 2]);
+    let loss = (&a.sum_axes(&[1], true) * &weights).sum();
+    loss.backward();
+    let grad = a.grad().unwrap();
+    assert_eq!(grad.shape(), &[2, 3, 2]);
+    assert_eq!(
+        grad.as_slice(),
+        &[1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 3.0, 4.0, 3.0, 4.0, 3.0, 4.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_mean_axis_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let weights = Tensor::new(vec![3.0, 6.0], &[2]);
+    let loss = (&a.mean(&[1]) * &weights).sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[1.0, 1.0, 1.0, 2.0, 2.0, 2.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_max_min_axes_grad() {
+    let a = Tensor::with_grad(vec![4.0, 3.0, 6.0, 1.0, 5.0, 2.0], &[2, 3]);
+    let loss = a.max_axes(&[1], false).sum() + a.min_axes(&[0], false).sum();
+    loss.backward();
+    // Row maxima are 6 and 5, column minima are 1, 3 and 2
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[0.0, 1.0, 1.0, 1.0, 1.0, 1.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_prod_axes_grad() {
+    let a = Tensor::with_grad(vec![2.0, 3.0, 4.0, 0.0, 5.0, 6.0, 0.0, 0.0, 7.0], &[3, 3]);
+    let loss = a.prod_axes(&[1], false).sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[12.0, 8.0, 6.0, 30.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_var_axes_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[1, 4]);
+    let loss = a.var_axes(&[1], false, false).sum();
+    loss.backward();
+    // d var / dx_i = 2 (x_i - mean) / n
+    let expected = [-0.75, -0.25, 0.25, 0.75];
+    for (g, e) in a.grad().unwrap().as_slice().iter().zip(expected) {
+        assert!((g - e).abs() < 1e-6);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_norm_axes_grad() {
+    let a = Tensor::with_grad(vec![3.0, -4.0], &[1, 2]);
+    let loss = a.norm_axes(2.0, &[1], false).sum();
+    loss.backward();
+    let expected = [0.6, -0.8];
+    for (g, e) in a.grad().unwrap().as_slice().iter().zip(expected) {
+        assert!((g - e).abs() < 1e-6);
+    }
+}
@@ -13,3 +13,13 @@ fn test_pow_grad() {
     assert_eq!(grad_base.shape(), expected_shape);
     assert_eq!(grad_base.as_slice(), &expected_grad_base);
 }
+
+#[cfg(test)]
+#[test]
+fn test_sign_grad() {
+    let a = Tensor::with_grad(vec![-2.0, 0.0, 3.0], &[3]);
+    let loss = (&a.sign() * &a).sum();
+    loss.backward();
+    // Only the product rule term through `a` contributes: d(sign(a) * a) = sign(a)
+    assert_eq!(a.grad().unwrap().as_slice(), &[-1.0, 0.0, 1.0]);
+}
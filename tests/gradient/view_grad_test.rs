@@ -0,0 +1,176 @@
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_narrow_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let loss = a.narrow(1, 1, 2).square().sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[0.0, 4.0, 6.0, 0.0, 10.0, 12.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_select_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let weights = Tensor::new(vec![1.0, 2.0], &[2]);
+    let loss = (&a.select(1, 2) * &weights).sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[0.0, 0.0, 1.0, 0.0, 0.0, 2.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_permute_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[1, 2, 3]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 1, 2]);
+    let loss = (&a.permute(&[2, 0, 1]) * &weights).sum();
+    loss.backward();
+    // Element (0, i, j) of `a` is multiplied by weights[j, 0, i]
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[1.0, 3.0, 5.0, 2.0, 4.0, 6.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_expand_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2, 1]);
+    let loss = a.expand(&[3, 2, 4]).sum();
+    loss.backward();
+    let grad = a.grad().unwrap();
+    assert_eq!(grad.shape(), &[2, 1]);
+    assert_eq!(grad.as_slice(), &[12.0, 12.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_squeeze_flatten_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 1, 2]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[4]);
+    let loss = (&a.squeeze().unsqueeze(0).flatten(0, 2) * &weights).sum();
+    loss.backward();
+    let grad = a.grad().unwrap();
+    assert_eq!(grad.shape(), &[2, 1, 2]);
+    assert_eq!(grad.as_slice(), &[1.0, 2.0, 3.0, 4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_reshape_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2]);
+    let loss = (&a.clone().reshape(&[3, 2]) * &weights).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().shape(), &[2, 3]);
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_reshape_transposed_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[6]);
+    let loss = (&a.transpose().reshape(&[6]) * &weights).sum();
+    loss.backward();
+    // The transposed element order is [1, 4, 2, 5, 3, 6]
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[1.0, 3.0, 5.0, 2.0, 4.0, 6.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_slice_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2]);
+    let loss = a.slice(0, 1, 2).sum();
+    loss.backward();
+    assert_eq!(
+        a.grad().unwrap().as_slice(),
+        &[0.0, 0.0, 1.0, 1.0, 1.0, 1.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_unsqueeze_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let loss = a.unsqueeze(1).square().sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().shape(), &[3]);
+    assert_eq!(a.grad().unwrap().as_slice(), &[2.0, 4.0, 6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_cat_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let constant = Tensor::new(vec![0.0, 0.0], &[2, 1]);
+    let b = Tensor::with_grad(vec![5.0, 6.0], &[2, 1]);
+    let joined = Tensor::cat(&[&a, &constant, &b], 1);
+    let weights = Tensor::arange(1.0, 9.0, 1.0).reshape(&[2, 4]);
+    let loss = (joined * weights).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[1.0, 2.0, 5.0, 6.0]);
+    assert_eq!(b.grad().unwrap().as_slice(), &[4.0, 8.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_stack_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let b = Tensor::with_grad(vec![3.0, 4.0], &[2]);
+    let loss = Tensor::stack(&[&a, &b], 1).select(0, 1).sum();
+    loss.backward();
+    // Row 1 of the stacked tensor holds the second element of each input
+    assert_eq!(a.grad().unwrap().as_slice(), &[0.0, 1.0]);
+    assert_eq!(b.grad().unwrap().as_slice(), &[0.0, 1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_split_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0], &[5]);
+    let pieces = a.chunk(2, 0);
+    let loss = (&pieces[0].sum() * 2.0 + pieces[1].square().sum()).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[2.0, 2.0, 2.0, 8.0, 10.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_diag_grad() {
+    let v = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let weights = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let loss = (v.diag() * weights).sum();
+    loss.backward();
+    assert_eq!(v.grad().unwrap().as_slice(), &[1.0, 4.0]);
+
+    let m = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let loss = m.diag().square().sum();
+    loss.backward();
+    assert_eq!(
+        m.grad().unwrap().as_slice(),
+        &[2.0, 0.0, 0.0, 0.0, 10.0, 0.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_tril_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let loss = (a.tril(0) * 3.0).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[3.0, 0.0, 3.0, 3.0]);
+}
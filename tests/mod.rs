@@ -1,2 +1,4 @@
+#![allow(clippy::needless_range_loop, clippy::useless_vec)]
+
 mod gradient;
 mod tensor;
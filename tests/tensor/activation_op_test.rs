@@ -1,4 +1,6 @@
 use nn_rs::linalg::tensor::Tensor;
+use nn_rs::nn::Layer;
+use nn_rs::nn::activation::Softmax;
 
 #[cfg(test)]
 #[test]
@@ -23,7 +25,7 @@ fn test_sigmoid() {
 fn test_log_softmax() {
     let data = vec![1.0, 2.0, 3.0];
     let tensor = Tensor::new(data.clone(), &[3]);
-    let result = tensor.log_softmax();
+    let result = tensor.log_softmax(0);
     let sum_exp: f32 = data.iter().map(|&x| x.exp()).sum();
     let expected_data: Vec<f32> = data.iter().map(|&x| (x.exp() / sum_exp).ln()).collect();
     for i in 0..3 {
@@ -36,7 +38,7 @@ fn test_log_softmax() {
 fn test_softmax() {
     let data = vec![1.0, 2.0, 3.0];
     let tensor = Tensor::new(data.clone(), &[3]);
-    let result = tensor.softmax();
+    let result = tensor.softmax(0);
     let sum_exp: f32 = data.iter().map(|&x| x.exp()).sum();
     let expected_data: Vec<f32> = data.iter().map(|&x| x.exp() / sum_exp).collect();
     println!("{:?} {:?}", result, expected_data);
@@ -60,3 +62,60 @@ fn test_relu() {
         assert_eq!(result.get(&[i]), expected_data[i]);
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_softmax_batched() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 1.0, 1.0, 1.0], &[2, 3]);
+    let result = tensor.softmax(1);
+    assert_eq!(result.shape(), &[2, 3]);
+    let row = Tensor::new(vec![1.0, 2.0, 3.0], &[3]).softmax(0);
+    for i in 0..3 {
+        assert!((result.get(&[0, i]) - row.get(&[i])).abs() < 1e-6);
+        assert!((result.get(&[1, i]) - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    let columns = tensor.softmax(0);
+    for j in 0..3 {
+        assert!((columns.get(&[0, j]) + columns.get(&[1, j]) - 1.0).abs() < 1e-6);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_log_softmax_large_inputs() {
+    let tensor = Tensor::new(vec![1000.0, 1000.0, -1000.0, 0.0], &[2, 2]);
+    let result = tensor.log_softmax(1);
+    let expected = [-(2.0f32.ln()), -(2.0f32.ln()), -1000.0, 0.0];
+    for (value, expected) in result.as_slice().iter().zip(expected) {
+        assert!((value - expected).abs() < 1e-4);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_logsumexp() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let rows = tensor.logsumexp(1, false);
+    assert_eq!(rows.shape(), &[2]);
+    let expected = (1.0f32.exp() + 2.0f32.exp() + 3.0f32.exp()).ln();
+    assert!((rows.get(&[0]) - expected).abs() < 1e-5);
+    assert!((rows.get(&[1]) - (expected + 3.0)).abs() < 1e-5);
+
+    let columns = tensor.logsumexp(0, true);
+    assert_eq!(columns.shape(), &[1, 3]);
+    let expected = (1.0f32.exp() + 4.0f32.exp()).ln();
+    assert!((columns.get(&[0, 0]) - expected).abs() < 1e-5);
+
+    assert!(tensor.try_logsumexp(2, false).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_softmax_layer_normalizes_each_sample() {
+    let input = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2]);
+    let output = Softmax.forward(&input);
+    for row in 0..3 {
+        assert!((output.get(&[row, 0]) + output.get(&[row, 1]) - 1.0).abs() < 1e-6);
+    }
+}
@@ -216,3 +216,73 @@ fn test_broadcast_add_3d() {
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_broadcast_shape() {
+    assert_eq!(Tensor::broadcast_shape(&[3, 1], &[4]).unwrap(), vec![3, 4]);
+    assert_eq!(
+        Tensor::broadcast_shape(&[5, 1, 3], &[2, 1]).unwrap(),
+        vec![5, 2, 3]
+    );
+    assert!(Tensor::broadcast_shape(&[3, 2], &[3]).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_broadcast_binary_ops() {
+    let column = Tensor::new(vec![1.0, 2.0, 3.0], &[3, 1]);
+    let row = Tensor::new(vec![10.0, 20.0], &[2]);
+
+    let sum = &column + &row;
+    assert_eq!(sum.shape(), &[3, 2]);
+    assert_eq!(sum.as_slice(), &[11.0, 21.0, 12.0, 22.0, 13.0, 23.0]);
+
+    let difference = &row - &column;
+    assert_eq!(difference.as_slice(), &[9.0, 19.0, 8.0, 18.0, 7.0, 17.0]);
+
+    let product = &column * &row;
+    assert_eq!(product.as_slice(), &[10.0, 20.0, 20.0, 40.0, 30.0, 60.0]);
+
+    let quotient = &row / &column;
+    assert_eq!(
+        quotient.as_slice(),
+        &[10.0, 20.0, 5.0, 10.0, 10.0 / 3.0, 20.0 / 3.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_broadcast_transposed_operand() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]).transpose();
+    let b = Tensor::new(vec![100.0, 200.0], &[1, 2]);
+    let result = &a + &b;
+    assert_eq!(result.shape(), &[3, 2]);
+    assert_eq!(
+        result.as_slice(),
+        &[101.0, 204.0, 102.0, 205.0, 103.0, 206.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_broadcast_to() {
+    let tensor = Tensor::new(vec![1.0, 2.0], &[2, 1]);
+    let result = tensor.broadcast_to(&[3, 2, 2]);
+    assert_eq!(result.shape(), &[3, 2, 2]);
+    assert_eq!(
+        result.as_slice(),
+        &[1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 2.0, 2.0]
+    );
+    assert!(tensor.try_broadcast_to(&[3, 3]).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_sum_to_shape() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    assert_eq!(tensor.sum_to_shape(&[1, 3]).as_slice(), &[5.0, 7.0, 9.0]);
+    assert_eq!(tensor.sum_to_shape(&[2, 1]).as_slice(), &[6.0, 15.0]);
+    assert_eq!(tensor.sum_to_shape(&[3]).as_slice(), &[5.0, 7.0, 9.0]);
+    assert_eq!(tensor.sum_to_shape(&[1]).as_slice(), &[21.0]);
+}
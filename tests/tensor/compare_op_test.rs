@@ -0,0 +1,131 @@
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_comparisons() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    let b = Tensor::new(vec![3.0, 2.0, 1.0], &[3]);
+    assert_eq!(a.eq(&b).dtype(), DType::Bool);
+    assert_eq!(a.eq(&b).as_typed_slice::<bool>(), &[false, true, false]);
+    assert_eq!(a.ne(&b).as_typed_slice::<bool>(), &[true, false, true]);
+    assert_eq!(a.lt(&b).as_typed_slice::<bool>(), &[true, false, false]);
+    assert_eq!(a.le(&b).as_typed_slice::<bool>(), &[true, true, false]);
+    assert_eq!(a.gt(&b).as_typed_slice::<bool>(), &[false, false, true]);
+    assert_eq!(a.ge(&b).as_typed_slice::<bool>(), &[false, true, true]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_comparisons_broadcast_and_promote() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let b = Tensor::from_vec(vec![2i64, 3], &[2]);
+    let mask = a.lt(&b);
+    assert_eq!(mask.shape(), &[2, 2]);
+    assert_eq!(mask.as_typed_slice::<bool>(), &[true, true, false, false]);
+    let threshold = a.ge(&Tensor::from_scalar(3.0));
+    assert_eq!(
+        threshold.as_typed_slice::<bool>(),
+        &[false, false, true, true]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_comparisons_nan() {
+    let a = Tensor::new(vec![f32::NAN, 1.0], &[2]);
+    assert_eq!(a.eq(&a).as_typed_slice::<bool>(), &[false, true]);
+    assert_eq!(a.ne(&a).as_typed_slice::<bool>(), &[true, false]);
+    assert_eq!(a.le(&a).as_typed_slice::<bool>(), &[false, true]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_logical_ops() {
+    let a = Tensor::from_vec(vec![true, true, false, false], &[4]);
+    let b = Tensor::from_vec(vec![true, false, true, false], &[4]);
+    assert_eq!(
+        a.logical_and(&b).as_typed_slice::<bool>(),
+        &[true, false, false, false]
+    );
+    assert_eq!(
+        a.logical_or(&b).as_typed_slice::<bool>(),
+        &[true, true, true, false]
+    );
+    assert_eq!(
+        a.logical_not().as_typed_slice::<bool>(),
+        &[false, false, true, true]
+    );
+    // Non-zero numbers are true
+    let numbers = Tensor::new(vec![0.0, 2.0, -1.0, 0.0], &[4]);
+    assert_eq!(
+        numbers.logical_and(&a).as_typed_slice::<bool>(),
+        &[false, true, false, false]
+    );
+    assert_eq!(
+        numbers.logical_not().as_typed_slice::<bool>(),
+        &[true, false, false, true]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_comparison_of_transposed_view() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]).swap_axes(0, 1);
+    let b = Tensor::new(vec![1.0, 3.0, 2.0, 4.0], &[2, 2]);
+    assert_eq!(a.eq(&b).as_typed_slice::<bool>(), &[true, true, true, true]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_where() {
+    let condition = Tensor::from_vec(vec![true, false, true, false], &[2, 2]);
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let b = Tensor::new(vec![-1.0, -2.0], &[2]);
+    let result = Tensor::where_(&condition, &a, &b);
+    assert_eq!(result.shape(), &[2, 2]);
+    assert_eq!(result.as_slice(), &[1.0, -2.0, 3.0, -2.0]);
+
+    // The condition broadcasts as well, and integer branches are promoted
+    let rows = Tensor::from_vec(vec![true, false], &[2, 1]);
+    let ints = Tensor::from_vec(vec![7i64, 8], &[2]);
+    let result = Tensor::where_(&rows, &ints, &a);
+    assert_eq!(result.dtype(), DType::F32);
+    assert_eq!(result.as_slice(), &[7.0, 8.0, 3.0, 4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_masked_fill() {
+    let scores = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let padding = Tensor::from_vec(vec![false, false, true], &[3]);
+    let masked = scores.masked_fill(&padding, f32::NEG_INFINITY);
+    assert_eq!(
+        masked.as_slice(),
+        &[1.0, 2.0, f32::NEG_INFINITY, 4.0, 5.0, f32::NEG_INFINITY]
+    );
+    let probabilities = masked.softmax(1);
+    assert_eq!(probabilities.get(&[0, 2]), 0.0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_mask_errors() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    let b = Tensor::new(vec![1.0, 2.0], &[2]);
+    assert!(matches!(
+        a.try_eq(&b),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    assert!(matches!(
+        Tensor::try_where_(&b.gt(&Tensor::from_scalar(1.0)), &a, &a),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    // The mask may not grow the tensor
+    let mask = Tensor::from_vec(vec![true; 6], &[2, 3]);
+    assert!(matches!(
+        a.try_masked_fill(&mask, 0.0),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+}
@@ -0,0 +1,94 @@
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_cat() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let b = Tensor::new(vec![5.0, 6.0], &[2, 1]);
+    let joined = Tensor::cat(&[&a, &b], 1);
+    assert_eq!(joined.shape(), &[2, 3]);
+    assert_eq!(joined.as_slice(), &[1.0, 2.0, 5.0, 3.0, 4.0, 6.0]);
+
+    let rows = Tensor::cat(&[&a, &a.swap_axes(0, 1)], 0);
+    assert_eq!(rows.shape(), &[4, 2]);
+    assert_eq!(rows.as_slice(), &[1.0, 2.0, 3.0, 4.0, 1.0, 3.0, 2.0, 4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_cat_promotes_dtype() {
+    let a = Tensor::from_vec(vec![1i64, 2], &[2]);
+    let b = Tensor::new(vec![0.5], &[1]);
+    let joined = Tensor::cat(&[&a, &b], 0);
+    assert_eq!(joined.dtype(), DType::F32);
+    assert_eq!(joined.as_slice(), &[1.0, 2.0, 0.5]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_stack() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    let b = Tensor::new(vec![4.0, 5.0, 6.0], &[3]);
+    let rows = Tensor::stack(&[&a, &b], 0);
+    assert_eq!(rows.shape(), &[2, 3]);
+    assert_eq!(rows.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let columns = Tensor::stack(&[&a, &b], 1);
+    assert_eq!(columns.shape(), &[3, 2]);
+    assert_eq!(columns.as_slice(), &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_split_and_chunk() {
+    let a = Tensor::arange(0.0, 10.0, 1.0).reshape(&[2, 5]);
+    let pieces = a.split(&[2, 3], 1);
+    assert_eq!(pieces.len(), 2);
+    assert_eq!(pieces[0].shape(), &[2, 2]);
+    assert_eq!(pieces[0].contiguous().as_slice(), &[0.0, 1.0, 5.0, 6.0]);
+    assert_eq!(
+        pieces[1].contiguous().as_slice(),
+        &[2.0, 3.0, 4.0, 7.0, 8.0, 9.0]
+    );
+
+    let chunks = a.chunk(2, 1);
+    let sizes = chunks.iter().map(|c| c.shape()[1]).collect::<Vec<usize>>();
+    assert_eq!(sizes, vec![3, 2]);
+    // Fewer chunks are returned when they cannot all be filled
+    assert_eq!(a.chunk(4, 0).len(), 2);
+
+    let refs = chunks.iter().collect::<Vec<&Tensor>>();
+    assert_eq!(Tensor::cat(&refs, 1).as_slice(), a.as_slice());
+}
+
+#[cfg(test)]
+#[test]
+fn test_concat_errors() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let b = Tensor::new(vec![1.0, 2.0, 3.0], &[3, 1]);
+    assert!(matches!(
+        Tensor::try_cat(&[], 0),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Tensor::try_cat(&[&a, &b], 1),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    assert!(matches!(
+        Tensor::try_cat(&[&a, &a], 2),
+        Err(TensorError::IndexOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        Tensor::try_stack(&[&a, &b.clone().reshape(&[1, 3])], 0),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    assert!(matches!(
+        a.try_split(&[1, 2], 0),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        a.try_chunk(0, 0),
+        Err(TensorError::InvalidArgument(_))
+    ));
+}
@@ -0,0 +1,144 @@
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_arange() {
+    assert_eq!(
+        Tensor::arange(0.0, 5.0, 1.0).as_slice(),
+        &[0.0, 1.0, 2.0, 3.0, 4.0]
+    );
+    assert_eq!(
+        Tensor::arange(1.0, 2.0, 0.25).as_slice(),
+        &[1.0, 1.25, 1.5, 1.75]
+    );
+    assert_eq!(Tensor::arange(2.0, -1.0, -1.5).as_slice(), &[2.0, 0.5]);
+    assert_eq!(Tensor::arange(3.0, 1.0, 1.0).shape(), &[0]);
+    assert!(matches!(
+        Tensor::try_arange(0.0, 1.0, 0.0),
+        Err(TensorError::InvalidArgument(_))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_linspace_and_logspace() {
+    assert_eq!(
+        Tensor::linspace(-1.0, 1.0, 5).as_slice(),
+        &[-1.0, -0.5, 0.0, 0.5, 1.0]
+    );
+    assert_eq!(Tensor::linspace(3.0, 7.0, 1).as_slice(), &[3.0]);
+    assert_eq!(Tensor::linspace(3.0, 7.0, 0).shape(), &[0]);
+    let values = Tensor::logspace(0.0, 3.0, 4, 10.0);
+    assert_eq!(values.as_slice(), &[1.0, 10.0, 100.0, 1000.0]);
+    assert_eq!(
+        Tensor::logspace(0.0, 2.0, 3, 2.0).as_slice(),
+        &[1.0, 2.0, 4.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_eye_and_full() {
+    let eye = Tensor::eye(3);
+    assert_eq!(eye.shape(), &[3, 3]);
+    assert_eq!(eye.dtype(), DType::F32);
+    assert_eq!(
+        eye.as_slice(),
+        &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+    );
+    let full = Tensor::full(&[2, 2], 7.5);
+    assert_eq!(full.as_slice(), &[7.5; 4]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_like_constructors() {
+    let a = Tensor::from_vec(vec![1i64, 2, 3, 4, 5, 6], &[2, 3]);
+    let zeros = a.zeros_like();
+    assert_eq!(zeros.dtype(), DType::I64);
+    assert_eq!(zeros.shape(), &[2, 3]);
+    assert_eq!(zeros.as_typed_slice::<i64>(), &[0; 6]);
+    assert_eq!(a.ones_like().as_typed_slice::<i64>(), &[1; 6]);
+    assert_eq!(a.full_like(4.0).as_typed_slice::<i64>(), &[4; 6]);
+    // The shape of a view is used, not the one of its storage
+    let column = a.to_dtype(DType::F64).select(1, 0);
+    assert_eq!(column.ones_like().as_typed_slice::<f64>(), &[1.0, 1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_diag() {
+    let matrix = Tensor::new(vec![1.0, 2.0], &[2]).diag();
+    assert_eq!(matrix.as_slice(), &[1.0, 0.0, 0.0, 2.0]);
+    let rectangular = Tensor::arange(1.0, 7.0, 1.0).reshape(&[2, 3]);
+    assert_eq!(rectangular.diag().contiguous().as_slice(), &[1.0, 5.0]);
+    assert_eq!(
+        rectangular.transpose().diag().contiguous().as_slice(),
+        &[1.0, 5.0]
+    );
+    assert!(matches!(
+        Tensor::ones(&[2, 2, 2]).try_diag(),
+        Err(TensorError::Rank { .. })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_tril_and_triu() {
+    let a = Tensor::arange(1.0, 10.0, 1.0).reshape(&[3, 3]);
+    assert_eq!(
+        a.tril(0).as_slice(),
+        &[1.0, 0.0, 0.0, 4.0, 5.0, 0.0, 7.0, 8.0, 9.0]
+    );
+    assert_eq!(
+        a.triu(1).as_slice(),
+        &[0.0, 2.0, 3.0, 0.0, 0.0, 6.0, 0.0, 0.0, 0.0]
+    );
+    assert_eq!(
+        a.tril(-1).as_slice(),
+        &[0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 7.0, 8.0, 0.0]
+    );
+    // Every matrix of a batch is masked the same way
+    let batch = Tensor::ones(&[2, 2, 3]).triu(0);
+    assert_eq!(
+        batch.as_slice(),
+        &[1.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 1.0]
+    );
+    assert!(matches!(
+        Tensor::ones(&[3]).try_tril(0),
+        Err(TensorError::Rank { .. })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_from_nested() {
+    let matrix = Tensor::from_nested(vec![vec![1.0f32, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]);
+    assert_eq!(matrix.shape(), &[3, 2]);
+    assert_eq!(matrix.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    let cube = Tensor::from_nested([[[1i64, 2], [3, 4]], [[5, 6], [7, 8]]]);
+    assert_eq!(cube.shape(), &[2, 2, 2]);
+    assert_eq!(cube.dtype(), DType::I64);
+
+    assert_eq!(Tensor::from_nested(2.5f64).shape(), &[1]);
+    assert_eq!(Tensor::from_nested(Vec::<Vec<f32>>::new()).shape(), &[0, 0]);
+    assert!(matches!(
+        Tensor::try_from_nested(vec![vec![1.0f32, 2.0], vec![3.0]]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_collect() {
+    let squares: Tensor = (1..=4).map(|x| (x * x) as f32).collect();
+    assert_eq!(squares.shape(), &[4]);
+    assert_eq!(squares.as_slice(), &[1.0, 4.0, 9.0, 16.0]);
+
+    let rows: Tensor = (0..3).map(|i| [i as u8, 2 * i as u8]).collect();
+    assert_eq!(rows.shape(), &[3, 2]);
+    assert_eq!(rows.as_typed_slice::<u8>(), &[0, 0, 1, 2, 2, 4]);
+}
@@ -0,0 +1,55 @@
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_display_matrix() {
+    let a = Tensor::new(vec![1.0, -2.5, 3.0, 40.0], &[2, 2]);
+    assert_eq!(a.to_string(), "[[ 1.0000 -2.5000]\n [ 3.0000 40.0000]]");
+    assert_eq!(format!("{a:.1}"), "[[ 1.0 -2.5]\n [ 3.0 40.0]]");
+}
+
+#[cfg(test)]
+#[test]
+fn test_display_dtypes() {
+    let labels = Tensor::from_vec(vec![3i64, 10, -1], &[3]);
+    assert_eq!(labels.to_string(), "[ 3 10 -1]");
+    let mask = Tensor::from_vec(vec![true, false], &[2]);
+    assert_eq!(mask.to_string(), "[ true false]");
+    let small = Tensor::from_vec(vec![1e-6f64, 1.0], &[2]);
+    assert_eq!(format!("{small:.2}"), "[1.00e-6  1.00e0]");
+}
+
+#[cfg(test)]
+#[test]
+fn test_display_views() {
+    let a = Tensor::arange(0.0, 6.0, 1.0).reshape(&[2, 3]);
+    assert_eq!(format!("{:.0}", a.transpose()), "[[0 3]\n [1 4]\n [2 5]]");
+    assert_eq!(format!("{:.0}", a.select(1, 2)), "[2 5]");
+    assert_eq!(Tensor::zeros(&[2, 0]).to_string(), "[]");
+}
+
+#[cfg(test)]
+#[test]
+fn test_display_3d() {
+    let a = Tensor::from_vec((0..8).collect::<Vec<i64>>(), &[2, 2, 2]);
+    assert_eq!(a.to_string(), "[[[0 1]\n  [2 3]]\n\n [[4 5]\n  [6 7]]]");
+}
+
+#[cfg(test)]
+#[test]
+fn test_display_summarizes_large_tensors() {
+    let a = Tensor::from_vec((0..2000).collect::<Vec<i64>>(), &[2000]);
+    assert_eq!(a.to_string(), "[   0    1    2 ... 1997 1998 1999]");
+    let b = Tensor::from_vec((0..1200).collect::<Vec<i64>>(), &[100, 12]);
+    let text = b.to_string();
+    assert!(text.starts_with("[[   0    1    2 ...    9   10   11]\n"));
+    assert!(text.contains("\n ...\n"));
+    assert!(text.ends_with("[1188 1189 1190 ... 1197 1198 1199]]"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_debug_empty() {
+    let text = format!("{:?}", Tensor::zeros(&[0]));
+    assert!(text.contains("shape: [0]"));
+}
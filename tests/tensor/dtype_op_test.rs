@@ -0,0 +1,120 @@
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_from_vec_dtype() {
+    assert_eq!(Tensor::new(vec![1.0], &[1]).dtype(), DType::F32);
+    assert_eq!(Tensor::from_vec(vec![1.0f64], &[1]).dtype(), DType::F64);
+    assert_eq!(Tensor::from_vec(vec![1i64], &[1]).dtype(), DType::I64);
+    assert_eq!(Tensor::from_vec(vec![1u8], &[1]).dtype(), DType::U8);
+    assert_eq!(Tensor::from_vec(vec![true], &[1]).dtype(), DType::Bool);
+}
+
+#[cfg(test)]
+#[test]
+fn test_to_dtype() {
+    let tensor = Tensor::new(vec![-1.5, 0.0, 2.7], &[3]);
+
+    let as_i64 = tensor.to_dtype(DType::I64);
+    assert_eq!(as_i64.dtype(), DType::I64);
+    assert_eq!(as_i64.as_typed_slice::<i64>(), &[-1, 0, 2]);
+
+    let as_bool = tensor.to_dtype(DType::Bool);
+    assert_eq!(as_bool.as_typed_slice::<bool>(), &[true, false, true]);
+
+    let as_f64 = tensor.to_dtype(DType::F64);
+    assert_eq!(as_f64.get_as::<f64>(&[2]), 2.7f32 as f64);
+}
+
+#[cfg(test)]
+#[test]
+fn test_binary_promotion() {
+    let ints = Tensor::from_vec(vec![1i64, 2, 3], &[3]);
+    let floats = Tensor::new(vec![0.5, 0.5, 0.5], &[3]);
+    let doubles = Tensor::from_vec(vec![0.25f64, 0.25, 0.25], &[3]);
+
+    let int_sum = &ints + &ints;
+    assert_eq!(int_sum.dtype(), DType::I64);
+    assert_eq!(int_sum.as_typed_slice::<i64>(), &[2, 4, 6]);
+
+    let mixed = &ints + &floats;
+    assert_eq!(mixed.dtype(), DType::F32);
+    assert_eq!(mixed.as_slice(), &[1.5, 2.5, 3.5]);
+
+    let wide = &floats * &doubles;
+    assert_eq!(wide.dtype(), DType::F64);
+    assert_eq!(wide.as_typed_slice::<f64>(), &[0.125, 0.125, 0.125]);
+
+    let scaled = &ints * 0.5;
+    assert_eq!(scaled.dtype(), DType::F32);
+    assert_eq!(scaled.as_slice(), &[0.5, 1.0, 1.5]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_f64_ops() {
+    let tensor = Tensor::from_vec(vec![1.0f64, 2.0, 3.0, 4.0], &[2, 2]);
+    let result = tensor.matmul(&tensor).sum();
+    assert_eq!(result.dtype(), DType::F64);
+    assert_eq!(result.item::<f64>(), 54.0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_one_hot() {
+    let labels = Tensor::from_vec(vec![1u8, 0, 2], &[3]);
+    let one_hot = labels.one_hot(3);
+    assert_eq!(one_hot.shape(), &[3, 3]);
+    assert_eq!(
+        one_hot.as_slice(),
+        &[0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_to_dtype_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let loss = a.to_dtype(DType::F64).square().sum();
+    assert_eq!(loss.dtype(), DType::F64);
+    loss.backward();
+
+    let grad_a = a.grad().unwrap();
+    assert_eq!(grad_a.dtype(), DType::F32);
+    assert_eq!(grad_a.as_slice(), &[2.0, 4.0, 6.0]);
+
+    assert!(!a.to_dtype(DType::I64).requires_grad());
+}
+
+#[cfg(test)]
+#[test]
+fn test_integer_division() {
+    let a = Tensor::from_vec(vec![7i64, -7, i64::MIN], &[3]);
+    let b = Tensor::from_vec(vec![2i64, 2, -1], &[3]);
+    // Integers are divided with truncation, and the overflowing quotient wraps
+    let quotient = a.try_div(&b).unwrap();
+    assert_eq!(quotient.dtype(), DType::I64);
+    assert_eq!(quotient.as_typed_slice::<i64>(), &[3, -3, i64::MIN]);
+
+    let zero = Tensor::from_vec(vec![1i64, 0, 1], &[3]);
+    assert!(matches!(
+        a.try_div(&zero),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    let bytes = Tensor::from_vec(vec![4u8], &[1]);
+    assert!(matches!(
+        bytes.try_div(&Tensor::from_vec(vec![0u8], &[1])),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    // Float division by zero is not an error
+    let floats = Tensor::new(vec![1.0], &[1]);
+    assert_eq!(
+        floats
+            .try_div(&Tensor::new(vec![0.0], &[1]))
+            .unwrap()
+            .as_slice(),
+        &[f32::INFINITY]
+    );
+}
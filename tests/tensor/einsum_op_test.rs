@@ -0,0 +1,157 @@
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_einsum_matmul() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let b = Tensor::new(vec![1.0, 0.0, -1.0, 2.0, 0.5, 1.0], &[3, 2]);
+    let result = Tensor::einsum("ij,jk->ik", &[&a, &b]);
+    assert_eq!(result.shape(), &[2, 2]);
+    assert_eq!(
+        result.contiguous().as_slice(),
+        a.matmul(&b).contiguous().as_slice()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_batched_matmul() {
+    let a = Tensor::arange(0.0, 12.0, 1.0).reshape(&[2, 2, 3]);
+    let b = Tensor::new((0..12).map(|x| x as f32 * 0.5).collect(), &[2, 3, 2]);
+    let result = Tensor::einsum("bij,bjk->bik", &[&a, &b]);
+    assert_eq!(result.shape(), &[2, 2, 2]);
+    assert_eq!(
+        result.contiguous().as_slice(),
+        a.bmm(&b).contiguous().as_slice()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_transposed_output() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let result = Tensor::einsum("ij->ji", &[&a]);
+    assert_eq!(result.shape(), &[3, 2]);
+    assert_eq!(
+        result.contiguous().as_slice(),
+        &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_trace_and_diagonal() {
+    let a = Tensor::arange(1.0, 10.0, 1.0).reshape(&[3, 3]);
+    assert_eq!(Tensor::einsum("ii", &[&a]).as_slice(), &[15.0]);
+    assert_eq!(
+        Tensor::einsum("ii->i", &[&a]).contiguous().as_slice(),
+        &[1.0, 5.0, 9.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_outer_and_dot() {
+    let u = Tensor::new(vec![1.0, 2.0], &[2]);
+    let v = Tensor::new(vec![3.0, 4.0, 5.0], &[3]);
+    let outer = Tensor::einsum("i,j->ij", &[&u, &v]);
+    assert_eq!(outer.shape(), &[2, 3]);
+    assert_eq!(
+        outer.contiguous().as_slice(),
+        &[3.0, 4.0, 5.0, 6.0, 8.0, 10.0]
+    );
+    assert_eq!(Tensor::einsum("i,i", &[&u, &u]).as_slice(), &[5.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_implicit_output() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let b = Tensor::new(vec![1.0, 0.0, -1.0, 2.0, 0.5, 1.0], &[3, 2]);
+    // The output keeps the subscripts appearing once, in alphabetical order
+    let result = Tensor::einsum("ij,jk", &[&a, &b]);
+    assert_eq!(
+        result.contiguous().as_slice(),
+        a.matmul(&b).contiguous().as_slice()
+    );
+    let transposed = Tensor::einsum("ba", &[&a]);
+    assert_eq!(transposed.shape(), &[3, 2]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_bilinear_form() {
+    let x = Tensor::new(vec![1.0, 2.0, 0.0, 1.0], &[2, 2]);
+    let w = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let y = Tensor::new(vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0], &[2, 3]);
+    // x_b^T W y_b for every row b
+    let result = Tensor::einsum("bi,ij,bj->b", &[&x, &w, &y]);
+    assert_eq!(result.shape(), &[2]);
+    assert_eq!(result.contiguous().as_slice(), &[24.0, 5.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_ellipsis_broadcast() {
+    let a = Tensor::arange(0.0, 12.0, 1.0).reshape(&[2, 2, 3]);
+    let b = Tensor::new(vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0], &[3, 2]);
+    let result = Tensor::einsum("...ij,jk->...ik", &[&a, &b]);
+    assert_eq!(result.shape(), &[2, 2, 2]);
+    assert_eq!(
+        result.contiguous().as_slice(),
+        a.matmul(&b).contiguous().as_slice()
+    );
+
+    // Ellipsis dimensions of size one broadcast against the other operand
+    let c = Tensor::new(vec![1.0, 2.0, 3.0], &[1, 3]);
+    let d = Tensor::new(vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0], &[2, 1, 3]);
+    let result = Tensor::einsum("...j,...j->...", &[&c, &d]);
+    assert_eq!(result.shape(), &[2, 1]);
+    assert_eq!(result.contiguous().as_slice(), &[6.0, 12.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_attention_scores() {
+    let q = Tensor::arange(0.0, 12.0, 1.0).reshape(&[1, 2, 2, 3]);
+    let k = Tensor::new((0..12).map(|x| (x % 5) as f32).collect(), &[1, 2, 2, 3]);
+    let scores = Tensor::einsum("bhqd,bhkd->bhqk", &[&q, &k]);
+    let expected = q.matmul(&k.swap_axes(2, 3));
+    assert_eq!(scores.shape(), &[1, 2, 2, 2]);
+    assert_eq!(
+        scores.contiguous().as_slice(),
+        expected.contiguous().as_slice()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_einsum_errors() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let b = Tensor::new(vec![1.0, 2.0], &[2]);
+    assert!(matches!(
+        Tensor::try_einsum("ij,j->i", &[&a]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Tensor::try_einsum("ijk->i", &[&a]),
+        Err(TensorError::Rank { .. })
+    ));
+    assert!(matches!(
+        Tensor::try_einsum("ij,j->i", &[&a, &b]),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    assert!(matches!(
+        Tensor::try_einsum("ii", &[&a]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Tensor::try_einsum("ij->k", &[&a]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Tensor::try_einsum("i1->i", &[&a]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+}
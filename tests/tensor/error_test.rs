@@ -0,0 +1,175 @@
+use nn_rs::helpers::metrics::{try_accuracy, try_mse, try_nll_loss};
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+use nn_rs::nn::activation::{ReLU, Sigmoid};
+use nn_rs::nn::linear::Linear;
+use nn_rs::nn::models::NeuralNetwork;
+
+#[cfg(test)]
+#[test]
+fn test_try_new_shape_mismatch() {
+    let result = Tensor::try_new(vec![1.0, 2.0, 3.0], &[2, 2]);
+    assert!(matches!(result, Err(TensorError::ShapeMismatch { .. })));
+    assert!(Tensor::try_new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]).is_ok());
+}
+
+#[cfg(test)]
+#[test]
+fn test_try_get_and_set() {
+    let mut tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    assert!(matches!(
+        tensor.try_get(&[0]),
+        Err(TensorError::Rank {
+            expected: 2,
+            actual: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        tensor.try_get(&[2, 0]),
+        Err(TensorError::IndexOutOfBounds {
+            index: 2,
+            bound: 2,
+            ..
+        })
+    ));
+    assert!(tensor.try_set(&[0, 2], 1.0).is_err());
+    tensor.try_set(&[1, 1], 5.0).unwrap();
+    assert_eq!(tensor.try_get(&[1, 1]).unwrap(), 5.0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_try_binary_ops() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    let b = Tensor::new(vec![1.0, 2.0], &[2]);
+    assert!(matches!(
+        a.try_add(&b),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    assert!(a.try_sub(&b).is_err());
+    assert!(a.try_mul(&b).is_err());
+    assert!(a.try_div(&b).is_err());
+    assert_eq!(a.try_add(&a).unwrap().as_slice(), &[2.0, 4.0, 6.0]);
+
+    let mask = Tensor::from_vec(vec![true, false], &[2]);
+    assert!(matches!(
+        mask.try_add(&mask),
+        Err(TensorError::DType {
+            dtype: DType::Bool,
+            ..
+        })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_try_matmul() {
+    let a = Tensor::new(vec![1.0; 6], &[2, 3]);
+    let b = Tensor::new(vec![1.0; 6], &[2, 3]);
+    assert!(matches!(
+        a.try_matmul(&b),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    let c = Tensor::new(vec![1.0; 8], &[2, 2, 2]);
+    assert!(matches!(
+        a.try_bmm(&c),
+        Err(TensorError::Rank { actual: 2, .. })
+    ));
+    assert!(matches!(
+        c.try_matmul(&Tensor::new(vec![1.0; 12], &[3, 2, 2])),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    assert_eq!(a.try_matmul(&b.transpose()).unwrap().shape(), &[2, 2]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_try_shape_and_reduce_ops() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    assert!(tensor.clone().try_reshape(&[4, 2]).is_err());
+    assert!(tensor.try_slice(2, 0, 1).is_err());
+    assert!(tensor.try_slice(1, 2, 2).is_err());
+    assert!(
+        tensor
+            .try_index_select(1, &Tensor::from_vec(vec![0i64, 3], &[2]))
+            .is_err()
+    );
+    assert!(tensor.try_sum_axis(2).is_err());
+    assert!(tensor.try_mean(&[2]).is_err());
+    assert!(tensor.try_argmax_axis(2).is_err());
+    assert!(
+        tensor
+            .try_broadcast_add(&Tensor::new(vec![1.0, 2.0], &[2]))
+            .is_err()
+    );
+    assert!(
+        Tensor::new(vec![1.0; 8], &[2, 2, 2])
+            .try_transpose()
+            .is_err()
+    );
+    assert!(tensor.try_one_hot(3).is_err());
+    assert!(Tensor::from_vec(vec![3i64], &[1]).try_one_hot(3).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_try_metrics() {
+    let pred = Tensor::new(vec![0.2, 0.8, 0.6, 0.4], &[2, 2]);
+    let target = Tensor::new(vec![0.0, 1.0, 1.0, 0.0], &[2, 2]);
+    assert!(try_mse(&target, &Tensor::new(vec![0.0; 2], &[2])).is_err());
+    assert!(try_nll_loss(&Tensor::new(vec![1.0; 2], &[2]), &pred).is_err());
+    assert!(try_nll_loss(&target, &pred).is_ok());
+    assert!(matches!(
+        try_accuracy(&target, &pred),
+        Err(TensorError::DType { .. })
+    ));
+    let labels = Tensor::from_vec(vec![1i64, 0], &[2]);
+    assert_eq!(try_accuracy(&labels, &pred).unwrap(), 1.0);
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "shape mismatch")]
+fn test_panicking_wrapper_reports_error() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0], &[3]);
+    let b = Tensor::new(vec![1.0, 2.0], &[2]);
+    let _ = &a + &b;
+}
+
+#[cfg(test)]
+#[test]
+fn test_dump_and_restore_network() {
+    let path = std::env::temp_dir().join(format!("nn_rs_dump_test_{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    let weights = Tensor::with_grad(vec![1.0, -2.0, 3.0, -4.0, 5.0, -6.0], &[3, 2]);
+    let bias = Tensor::with_grad(vec![0.5, -0.5], &[1, 2]);
+    let net = NeuralNetwork::init(vec![
+        Box::new(Linear::from_parameters(weights, bias)),
+        Box::new(ReLU {}),
+        Box::new(Sigmoid),
+    ]);
+    net.try_dump_memory(path).unwrap();
+
+    let restored = NeuralNetwork::try_restore(path).unwrap();
+    assert_eq!(restored.layers.len(), 3);
+    let input = Tensor::new(vec![1.0, 0.5, -1.0], &[1, 3]);
+    assert_eq!(
+        restored.forward(input.clone()).as_slice(),
+        net.forward(input).as_slice()
+    );
+
+    std::fs::write(path, b"unknown\n").unwrap();
+    assert!(matches!(
+        NeuralNetwork::try_restore(path),
+        Err(TensorError::Format(_))
+    ));
+    std::fs::remove_file(path).unwrap();
+
+    assert!(matches!(
+        NeuralNetwork::try_restore(path),
+        Err(TensorError::Io(_))
+    ));
+}
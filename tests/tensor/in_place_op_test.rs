@@ -0,0 +1,132 @@
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(test)]
+#[test]
+fn test_in_place_arithmetic() {
+    let mut a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    a.add_(&Tensor::new(vec![10.0, 20.0], &[2]));
+    assert_eq!(a.as_slice(), &[11.0, 22.0, 13.0, 24.0]);
+    a.sub_(&Tensor::from_scalar(1.0));
+    assert_eq!(a.as_slice(), &[10.0, 21.0, 12.0, 23.0]);
+    a.mul_(&Tensor::new(vec![2.0, 0.5], &[2, 1]));
+    assert_eq!(a.as_slice(), &[20.0, 42.0, 6.0, 11.5]);
+    assert_eq!(a.version(), 3);
+}
+
+#[cfg(test)]
+#[test]
+fn test_fill_copy_and_zero() {
+    let mut a = Tensor::from_vec(vec![1i64, 2, 3], &[3]);
+    a.fill_(7.9);
+    assert_eq!(a.dtype(), DType::I64);
+    assert_eq!(a.as_typed_slice::<i64>(), &[7, 7, 7]);
+    a.copy_(&Tensor::new(vec![1.0, 2.0, 3.0], &[3]));
+    assert_eq!(a.as_typed_slice::<i64>(), &[1, 2, 3]);
+    a.zero_();
+    assert_eq!(a.as_typed_slice::<i64>(), &[0, 0, 0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_in_place_writes_to_shared_storage() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let mut b = a.clone();
+    b.add_(&Tensor::from_scalar(1.0));
+    assert_eq!(a.as_slice(), &[2.0, 3.0, 4.0, 5.0]);
+    assert_eq!(a.version(), 1);
+
+    // Writing through a view only changes the elements of the view
+    let mut column = a.select(1, 0);
+    column.fill_(0.0);
+    assert_eq!(column.contiguous().as_slice(), &[0.0, 0.0]);
+    assert_eq!(a.as_slice(), &[0.0, 3.0, 0.0, 5.0]);
+    assert_eq!(a.version(), 2);
+    assert_eq!(column.version(), 2);
+}
+
+#[cfg(test)]
+#[test]
+fn test_in_place_through_view_updates_base() {
+    let base = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    base.select(0, 0).fill_(9.0);
+    assert_eq!(base.as_slice(), &[9.0, 9.0, 3.0, 4.0]);
+    assert_eq!(base.version(), 1);
+
+    // Copying from an overlapping view of the same storage reads the values before writing
+    let mut row = base.select(0, 1);
+    row.copy_(&base.select(0, 0));
+    assert_eq!(base.as_slice(), &[9.0, 9.0, 9.0, 9.0]);
+    assert_eq!(base.version(), 2);
+}
+
+#[cfg(test)]
+#[test]
+fn test_set_and_as_mut_slice_copy_shared_storage() {
+    let a = Tensor::new(vec![1.0, 2.0], &[2]);
+    let mut b = a.clone();
+    b.set(&[0], 5.0);
+    b.as_mut_slice()[1] = 6.0;
+    assert_eq!(a.as_slice(), &[1.0, 2.0]);
+    assert_eq!(b.as_slice(), &[5.0, 6.0]);
+    assert_eq!(a.version(), 0);
+    assert_eq!(b.version(), 2);
+}
+
+#[cfg(test)]
+#[test]
+fn test_in_place_errors() {
+    let mut a = Tensor::ones(&[2, 2]);
+    assert!(matches!(
+        a.try_add_(&Tensor::ones(&[3])),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    // The shape of the tensor cannot grow
+    let mut row = Tensor::ones(&[1, 2]);
+    assert!(matches!(
+        row.try_copy_(&Tensor::ones(&[2, 2])),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    let mut expanded = Tensor::ones(&[1, 2]).expand(&[3, 2]);
+    assert!(matches!(
+        expanded.try_fill_(2.0),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    let x = Tensor::with_grad(vec![1.0, 2.0], &[2]);
+    let mut y = &x * 2.0;
+    assert!(matches!(
+        y.try_mul_(&Tensor::from_scalar(2.0)),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert_eq!(a.version(), 0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_reading_shared_operands_while_writing_in_place() {
+    // Operands sharing their storage are read under a single lock, so a writer waiting for it
+    // cannot block the second read
+    let p = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let mask = Tensor::ones(&[2]);
+    let done = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            let mut q = p.clone();
+            let done = &done;
+            scope.spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    q.add_(&Tensor::from_scalar(0.0));
+                }
+            });
+        }
+        for _ in 0..20000 {
+            assert_eq!((&p * &p).shape(), &[2, 2]);
+            assert_eq!(p.matmul(&p.transpose()).shape(), &[2, 2]);
+            assert_eq!(Tensor::cat(&[&p, &p], 0).shape(), &[4, 2]);
+            assert_eq!(Tensor::where_(&mask, &p, &p).shape(), &[2, 2]);
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+}
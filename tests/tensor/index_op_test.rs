@@ -0,0 +1,124 @@
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_gather() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let columns = Tensor::from_vec(vec![2i64, 0, 1, 1], &[2, 2]);
+    let result = a.gather(1, &columns);
+    assert_eq!(result.shape(), &[2, 2]);
+    assert_eq!(result.as_slice(), &[3.0, 1.0, 5.0, 5.0]);
+
+    let rows = Tensor::from_vec(vec![1i64, 0, 1], &[1, 3]);
+    assert_eq!(a.gather(0, &rows).as_slice(), &[4.0, 2.0, 6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_gather_transposed_view() {
+    // The transposed view is [[1, 4], [2, 5], [3, 6]]
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]).swap_axes(0, 1);
+    let index = Tensor::from_vec(vec![1u8, 0, 1], &[3, 1]);
+    assert_eq!(a.gather(1, &index).as_slice(), &[4.0, 2.0, 6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_scatter() {
+    let a = Tensor::zeros(&[3, 2]);
+    let index = Tensor::from_vec(vec![2i64, 0, 0, 1], &[2, 2]);
+    let src = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let result = a.scatter(0, &index, &src);
+    assert_eq!(result.as_slice(), &[3.0, 2.0, 0.0, 4.0, 1.0, 0.0]);
+
+    // Only the part of a larger source covered by the index is read
+    let wide = Tensor::new(vec![1.0, 2.0, 9.0, 3.0, 4.0, 9.0], &[2, 3]);
+    assert_eq!(a.scatter(0, &index, &wide).as_slice(), result.as_slice());
+}
+
+#[cfg(test)]
+#[test]
+fn test_scatter_add() {
+    let a = Tensor::ones(&[2, 3]);
+    let index = Tensor::from_vec(vec![0i64, 0, 2, 1], &[2, 2]);
+    let src = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let result = a.scatter_add(1, &index, &src);
+    assert_eq!(result.as_slice(), &[4.0, 1.0, 1.0, 1.0, 5.0, 4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_index_select() {
+    let a = Tensor::arange(0.0, 6.0, 1.0).reshape(&[2, 3]);
+    let index = Tensor::from_vec(vec![2i64, 2, 0], &[3]);
+    let result = a.index_select(1, &index);
+    assert_eq!(result.shape(), &[2, 3]);
+    assert_eq!(result.as_slice(), &[2.0, 2.0, 0.0, 5.0, 5.0, 3.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_index_add() {
+    let a = Tensor::zeros(&[3, 2]);
+    let index = Tensor::from_vec(vec![0i64, 2, 0], &[3]);
+    let source = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2]);
+    let result = a.index_add(0, &index, &source);
+    assert_eq!(result.as_slice(), &[6.0, 8.0, 0.0, 0.0, 3.0, 4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_masked_select() {
+    let a = Tensor::new(vec![1.0, -2.0, 3.0, -4.0], &[2, 2]);
+    let positive = a.gt(&Tensor::from_scalar(0.0));
+    assert_eq!(a.masked_select(&positive).as_slice(), &[1.0, 3.0]);
+
+    // The mask broadcasts against the tensor
+    let column = Tensor::from_vec(vec![false, true], &[2]);
+    let selected = a.masked_select(&column);
+    assert_eq!(selected.shape(), &[2]);
+    assert_eq!(selected.as_slice(), &[-2.0, -4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_index_errors() {
+    let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let out_of_bounds = Tensor::from_vec(vec![0i64, 3], &[1, 2]);
+    assert!(matches!(
+        a.try_gather(1, &out_of_bounds),
+        Err(TensorError::IndexOutOfBounds { .. })
+    ));
+    let negative = Tensor::from_vec(vec![-1i64], &[1, 1]);
+    assert!(matches!(
+        a.try_gather(1, &negative),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    let float_index = Tensor::new(vec![0.0], &[1, 1]);
+    assert!(matches!(
+        a.try_gather(1, &float_index),
+        Err(TensorError::DType { .. })
+    ));
+    let too_large = Tensor::from_vec(vec![0i64; 3], &[3, 1]);
+    assert!(matches!(
+        a.try_gather(1, &too_large),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    let small_src = Tensor::new(vec![1.0], &[1, 1]);
+    let index = Tensor::from_vec(vec![0i64, 1], &[1, 2]);
+    assert!(matches!(
+        a.try_scatter(1, &index, &small_src),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+    let matrix_index = Tensor::from_vec(vec![0i64, 1], &[1, 2]);
+    assert!(matches!(
+        a.try_index_select(0, &matrix_index),
+        Err(TensorError::Rank { .. })
+    ));
+    let vector_index = Tensor::from_vec(vec![0i64, 1, 1], &[3]);
+    assert!(matches!(
+        a.try_index_add(0, &vector_index, &a),
+        Err(TensorError::ShapeMismatch { .. })
+    ));
+}
@@ -30,3 +30,126 @@ fn test_mul() {
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_matmul_vectors() {
+    let matrix = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let column = Tensor::new(vec![1.0, 0.0, -1.0], &[3]);
+    let row = Tensor::new(vec![1.0, 1.0], &[2]);
+    assert_eq!(matrix.matmul(&column).shape(), &[2]);
+    assert_eq!(matrix.matmul(&column).as_slice(), &[-2.0, -2.0]);
+    assert_eq!(row.matmul(&matrix).as_slice(), &[5.0, 7.0, 9.0]);
+    assert_eq!(column.matmul(&column).as_slice(), &[2.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_matmul_batched() {
+    // Two batches: the identity and twice the identity
+    let a = Tensor::new(vec![1.0, 0.0, 0.0, 1.0, 2.0, 0.0, 0.0, 2.0], &[2, 2, 2]);
+    let b = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[2, 2, 2]);
+    let result = a.bmm(&b);
+    assert_eq!(result.shape(), &[2, 2, 2]);
+    assert_eq!(
+        result.as_slice(),
+        &[1.0, 2.0, 3.0, 4.0, 10.0, 12.0, 14.0, 16.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_matmul_broadcast_batch() {
+    let a = Tensor::arange(0.0, 12.0, 1.0).reshape(&[3, 1, 2, 2]);
+    let b = Tensor::new(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], &[2, 2, 2]);
+    let result = a.matmul(&b);
+    assert_eq!(result.shape(), &[3, 2, 2, 2]);
+    for i in 0..3 {
+        for j in 0..2 {
+            let expected = a.select(0, i).select(0, 0).matmul(&b.select(0, j));
+            let actual = result.select(0, i).select(0, j).contiguous();
+            assert_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+
+    // A plain matrix is shared across every batch
+    let weights = Tensor::new(vec![1.0, 1.0], &[2, 1]);
+    let summed = b.matmul(&weights);
+    assert_eq!(summed.shape(), &[2, 2, 1]);
+    assert_eq!(summed.as_slice(), &[1.0, 1.0, 1.0, 1.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_matmul_transposed_batch() {
+    let a = Tensor::arange(0.0, 12.0, 1.0).reshape(&[2, 3, 2]);
+    let b = Tensor::arange(0.0, 12.0, 1.0).reshape(&[2, 3, 2]);
+    let result = a.swap_axes(1, 2).matmul(&b);
+    let expected = a.swap_axes(1, 2).contiguous().matmul(&b);
+    assert_eq!(result.shape(), &[2, 2, 2]);
+    assert_eq!(result.as_slice(), expected.as_slice());
+}
+
+/// Reference triple loop the blocked kernel is checked against
+fn naive_matmul(a: &[f64], b: &[f64], m: usize, k: usize, n: usize) -> Vec<f64> {
+    let mut out = vec![0.0; m * n];
+    for i in 0..m {
+        for j in 0..n {
+            for p in 0..k {
+                out[i * n + j] += a[i * k + p] * b[p * n + j];
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[test]
+fn test_matmul_blocked_sizes() {
+    // Sizes that are not multiples of the register and cache blocks
+    for &(m, k, n) in &[(1, 1, 1), (5, 3, 9), (67, 259, 13), (7, 300, 530)] {
+        let a_data = (0..m * k)
+            .map(|x| ((x * 7) % 11) as f64 - 5.0)
+            .collect::<Vec<_>>();
+        let b_data = (0..k * n)
+            .map(|x| ((x * 5) % 13) as f64 - 6.0)
+            .collect::<Vec<_>>();
+        let expected = naive_matmul(&a_data, &b_data, m, k, n);
+
+        let a = Tensor::new(a_data.iter().map(|&x| x as f32).collect(), &[m, k]);
+        let b = Tensor::new(b_data.iter().map(|&x| x as f32).collect(), &[k, n]);
+        let result = a.matmul(&b);
+        assert_eq!(result.shape(), &[m, n]);
+        for (value, expected) in result.as_slice().iter().zip(&expected) {
+            assert!((*value as f64 - expected).abs() < 1e-3);
+        }
+
+        let a = Tensor::from_vec(a_data.clone(), &[m, k]);
+        let b = Tensor::from_vec(b_data.clone(), &[k, n]);
+        assert_eq!(a.matmul(&b).as_typed_slice::<f64>(), &expected[..]);
+
+        let a = Tensor::from_vec(a_data.iter().map(|&x| x as i64).collect(), &[m, k]);
+        let b = Tensor::from_vec(b_data.iter().map(|&x| x as i64).collect(), &[k, n]);
+        let expected = expected.iter().map(|&x| x as i64).collect::<Vec<_>>();
+        assert_eq!(a.matmul(&b).as_typed_slice::<i64>(), &expected[..]);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_matmul_blocked_transposed_operands() {
+    let (m, k, n) = (70, 300, 20);
+    let a_data = (0..m * k)
+        .map(|x| (x % 17) as f64 - 8.0)
+        .collect::<Vec<_>>();
+    let b_data = (0..k * n)
+        .map(|x| (x % 19) as f64 - 9.0)
+        .collect::<Vec<_>>();
+    let expected = naive_matmul(&a_data, &b_data, m, k, n);
+
+    // Store both operands transposed and read them through transposed views
+    let a = Tensor::from_vec(a_data, &[m, k]).transpose().contiguous();
+    let b = Tensor::from_vec(b_data, &[k, n]).transpose().contiguous();
+    let result = a.transpose().matmul(&b.transpose());
+    assert_eq!(result.as_typed_slice::<f64>(), &expected[..]);
+}
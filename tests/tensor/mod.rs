@@ -1,7 +1,21 @@
 mod activation_op_test;
 mod binary_op_test;
+mod compare_op_test;
+mod concat_op_test;
+mod creation_op_test;
+mod display_test;
+mod dtype_op_test;
+mod einsum_op_test;
+mod error_test;
+mod in_place_op_test;
+mod index_op_test;
 mod matmul_op_test;
+mod parallel_test;
+mod random_op_test;
 mod reduce_op_test;
 mod shape_op_test;
+mod strided_op_test;
 mod tensor_op_test;
+mod thread_test;
 mod unary_op_test;
+mod view_op_test;
@@ -0,0 +1,66 @@
+use nn_rs::linalg::parallel::{num_threads, set_num_threads};
+use nn_rs::linalg::tensor::Tensor;
+
+/// Runs `f` with 1 and 4 threads and returns both results
+fn with_thread_counts<T>(f: impl Fn() -> T) -> (T, T) {
+    set_num_threads(1);
+    let single = f();
+    set_num_threads(4);
+    let multi = f();
+    set_num_threads(0);
+    (single, multi)
+}
+
+fn values(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|x| ((x * 37) % 101) as f32 * 0.013 - 0.6)
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn test_num_threads() {
+    assert!(num_threads() >= 1);
+    if !cfg!(feature = "parallel") {
+        set_num_threads(8);
+        assert_eq!(num_threads(), 1);
+        set_num_threads(0);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_reductions_are_deterministic() {
+    let tensor = Tensor::new(values(300_000), &[300, 1000]);
+    let (single, multi) = with_thread_counts(|| {
+        (
+            tensor.sum().as_slice().to_vec(),
+            tensor.sum_axes(&[0], false).as_slice().to_vec(),
+            tensor.transpose().mean_axes(&[1], true).as_slice().to_vec(),
+            tensor.max_axes(&[1], false).as_slice().to_vec(),
+            tensor
+                .argmin_axes(&[0, 1], false)
+                .as_typed_slice::<i64>()
+                .to_vec(),
+        )
+    });
+    assert_eq!(single, multi);
+}
+
+#[cfg(test)]
+#[test]
+fn test_element_wise_and_matmul_across_threads() {
+    let a = Tensor::new(values(200 * 300), &[200, 300]);
+    let b = Tensor::new(values(300 * 150), &[300, 150]);
+    let (single, multi) = with_thread_counts(|| {
+        (
+            (&a.exp() * &a.transpose().transpose()).as_slice().to_vec(),
+            a.matmul(&b).as_slice().to_vec(),
+            a.unsqueeze(0)
+                .matmul(&b.transpose().contiguous().transpose())
+                .as_slice()
+                .to_vec(),
+        )
+    });
+    assert_eq!(single, multi);
+}
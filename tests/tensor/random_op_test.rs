@@ -0,0 +1,136 @@
+use nn_rs::linalg::dtype::DType;
+use nn_rs::linalg::error::TensorError;
+use nn_rs::linalg::random::Generator;
+use nn_rs::linalg::tensor::Tensor;
+
+#[cfg(test)]
+#[test]
+fn test_generator_is_reproducible() {
+    let mut a = Generator::new(7);
+    let mut b = Generator::new(7);
+    assert_eq!(a.rand(&[4, 3]).as_slice(), b.rand(&[4, 3]).as_slice());
+    assert_eq!(a.randn(&[5]).as_slice(), b.randn(&[5]).as_slice());
+    assert_eq!(
+        a.randint(0, 10, &[6]).as_typed_slice::<i64>(),
+        b.randint(0, 10, &[6]).as_typed_slice::<i64>()
+    );
+
+    let first = a.rand(&[8]);
+    a.manual_seed(7);
+    b.manual_seed(7);
+    assert_eq!(a.rand(&[8]).as_slice(), b.rand(&[8]).as_slice());
+    assert_ne!(first.as_slice(), a.rand(&[8]).as_slice());
+}
+
+#[cfg(test)]
+#[test]
+fn test_uniform_and_normal() {
+    let mut generator = Generator::new(0);
+    let uniform = generator.uniform(&[1000], -2.0, 3.0);
+    assert_eq!(uniform.shape(), &[1000]);
+    assert_eq!(uniform.dtype(), DType::F32);
+    assert!(uniform.as_slice().iter().all(|&x| (-2.0..3.0).contains(&x)));
+
+    let normal = generator.normal(&[2001], 5.0, 2.0);
+    assert_eq!(normal.shape(), &[2001]);
+    let mean = normal.mean_scalar().as_scalar();
+    let variance = normal
+        .as_slice()
+        .iter()
+        .map(|x| (x - mean).powi(2))
+        .sum::<f32>()
+        / 2001.0;
+    assert!((mean - 5.0).abs() < 0.2);
+    assert!((variance.sqrt() - 2.0).abs() < 0.2);
+}
+
+#[cfg(test)]
+#[test]
+fn test_bernoulli() {
+    let mut generator = Generator::new(1);
+    let probabilities = Tensor::new(vec![0.0, 1.0, 0.5, 0.0, 1.0, 0.5], &[2, 3]);
+    let samples = generator.bernoulli(&probabilities);
+    assert_eq!(samples.shape(), &[2, 3]);
+    let values = samples.as_slice();
+    assert_eq!(
+        [values[0], values[1], values[3], values[4]],
+        [0.0, 1.0, 0.0, 1.0]
+    );
+    assert!(values.iter().all(|&x| x == 0.0 || x == 1.0));
+}
+
+#[cfg(test)]
+#[test]
+fn test_randint_and_randperm() {
+    let mut generator = Generator::new(2);
+    let integers = generator.randint(-3, 4, &[10, 10]);
+    assert_eq!(integers.dtype(), DType::I64);
+    assert!(
+        integers
+            .as_typed_slice::<i64>()
+            .iter()
+            .all(|&x| (-3..4).contains(&x))
+    );
+
+    let permutation = generator.randperm(20);
+    assert_eq!(permutation.shape(), &[20]);
+    let mut sorted = permutation.as_typed_slice::<i64>().to_vec();
+    sorted.sort();
+    assert_eq!(sorted, (0..20).collect::<Vec<i64>>());
+}
+
+#[cfg(test)]
+#[test]
+fn test_multinomial() {
+    let mut generator = Generator::new(3);
+    let weights = Tensor::new(vec![0.0, 2.0, 0.0, 1.0, 1.0, 0.0, 3.0, 0.0], &[2, 4]);
+    let samples = generator.multinomial(&weights, 50, true);
+    assert_eq!(samples.shape(), &[2, 50]);
+    let samples = samples.as_typed_slice::<i64>();
+    assert!(samples[..50].iter().all(|&i| i == 1 || i == 3));
+    assert!(samples[50..].iter().all(|&i| i == 0 || i == 2));
+
+    let drawn = generator.multinomial(&Tensor::new(vec![1.0, 0.0, 5.0, 2.0], &[4]), 3, false);
+    let mut drawn = drawn.as_typed_slice::<i64>().to_vec();
+    drawn.sort();
+    assert_eq!(drawn, vec![0, 2, 3]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_random_errors() {
+    let mut generator = Generator::new(4);
+    assert!(matches!(
+        generator.try_uniform(&[2], 1.0, 1.0),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        generator.try_normal(&[2], 0.0, -1.0),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        generator.try_randint(5, 5, &[2]),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        generator.try_bernoulli(&Tensor::new(vec![1.5], &[1])),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        generator.try_bernoulli(&Tensor::from_vec(vec![1i64], &[1])),
+        Err(TensorError::DType { .. })
+    ));
+    let weights = Tensor::new(vec![1.0, 0.0, 1.0], &[3]);
+    assert!(matches!(
+        generator.try_multinomial(&weights, 3, false),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        generator.try_multinomial(&Tensor::new(vec![-1.0, 1.0], &[2]), 1, true),
+        Err(TensorError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        generator.try_multinomial(&Tensor::new(vec![1.0; 8], &[2, 2, 2]), 1, true),
+        Err(TensorError::Rank { .. })
+    ));
+}
@@ -1,3 +1,4 @@
+use nn_rs::linalg::dtype::DType;
 use nn_rs::linalg::tensor::Tensor;
 
 #[cfg(test)]
@@ -6,7 +7,7 @@ fn test_mean() {
     let data = vec![1.0, 2.0, 3.0, 4.0];
     let tensor = Tensor::new(data, &[2, 2]);
     let mean_tensor = tensor.mean(&[1]);
-    let expected_data = vec![2.0, 3.0];
+    let expected_data = vec![1.5, 3.5];
     assert_eq!(mean_tensor.shape().len(), 1);
     assert_eq!(mean_tensor.shape()[0], 2);
     for i in 0..2 {
@@ -44,12 +45,12 @@ fn test_slice() {
 
 #[cfg(test)]
 #[test]
-fn test_gather() {
+fn test_index_select() {
     let data = vec![
         1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0,
     ];
     let tensor = Tensor::new(data, &[4, 3]);
-    let gathered_tensor = tensor.gather(0, &[0, 2]);
+    let gathered_tensor = tensor.index_select(0, &Tensor::from_vec(vec![0i64, 2], &[2]));
     let expected_data = vec![1.0, 2.0, 3.0, 7.0, 8.0, 9.0];
     assert_eq!(gathered_tensor.shape(), vec![2, 3]);
     for i in 0..2 {
@@ -66,8 +67,9 @@ fn test_argmax_axis() {
     let tensor = Tensor::new(data, &[2, 3]);
     let argmax_tensor = tensor.argmax_axis(1);
     let expected_data = vec![1, 1];
-    assert_eq!(argmax_tensor.len(), 2);
-    assert_eq!(argmax_tensor, expected_data);
+    assert_eq!(argmax_tensor.dtype(), DType::I64);
+    assert_eq!(argmax_tensor.shape(), &[2]);
+    assert_eq!(argmax_tensor.as_typed_slice::<i64>(), expected_data);
 }
 
 #[cfg(test)]
@@ -102,3 +104,164 @@ fn test_sum_axis() {
         assert_eq!(sum_tensor.get(&[i]), expected_data[i]);
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_sum_axes_middle_axis() {
+    let tensor = Tensor::arange(1.0, 13.0, 1.0).reshape(&[2, 3, 2]);
+    let sum = tensor.sum_axes(&[1], false);
+    assert_eq!(sum.shape(), &[2, 2]);
+    assert_eq!(sum.as_slice(), &[9.0, 12.0, 27.0, 30.0]);
+
+    let kept = tensor.sum_axes(&[0, 2], true);
+    assert_eq!(kept.shape(), &[1, 3, 1]);
+    assert_eq!(kept.as_slice(), &[18.0, 26.0, 34.0]);
+
+    // Reduced axes are removed, other dimensions of size one are kept
+    let single = Tensor::new(vec![1.0, 2.0], &[1, 2]);
+    assert_eq!(single.sum_axis(1).shape(), &[1]);
+    assert_eq!(single.sum_axis(0).shape(), &[2]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_mean_axes() {
+    let tensor = Tensor::arange(1.0, 13.0, 1.0).reshape(&[2, 3, 2]);
+    let mean = tensor.mean_axes(&[2, 0], true);
+    assert_eq!(mean.shape(), &[1, 3, 1]);
+    assert_eq!(mean.as_slice(), &[4.5, 6.5, 8.5]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_max_min_axes() {
+    let tensor = Tensor::new(vec![4.0, 3.0, 6.0, 1.0, 5.0, 2.0], &[2, 3]);
+    assert_eq!(tensor.max_axes(&[1], false).as_slice(), &[6.0, 5.0]);
+    assert_eq!(tensor.min_axes(&[0], true).shape(), &[1, 3]);
+    assert_eq!(tensor.min_axes(&[0], true).as_slice(), &[1.0, 3.0, 2.0]);
+    assert_eq!(tensor.min().as_slice(), &[1.0]);
+
+    let ints = Tensor::from_vec(vec![3i64, -1, 7, 2], &[2, 2]);
+    assert_eq!(ints.max_axes(&[0], false).as_typed_slice::<i64>(), &[7, 2]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_argmax_argmin_axes() {
+    let tensor = Tensor::new(vec![4.0, 3.0, 6.0, 1.0, 5.0, 2.0], &[2, 3]);
+    assert_eq!(
+        tensor.argmax_axes(&[0], false).as_typed_slice::<i64>(),
+        &[0, 1, 0]
+    );
+    assert_eq!(
+        tensor.argmin_axes(&[1], true).as_typed_slice::<i64>(),
+        &[1, 0]
+    );
+    assert_eq!(tensor.argmin_axes(&[1], true).shape(), &[2, 1]);
+    // Over several axes, the index is row-major within the reduced axes
+    assert_eq!(
+        tensor.argmin_axes(&[0, 1], false).as_typed_slice::<i64>(),
+        &[3]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_prod_axes() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    assert_eq!(tensor.prod_axes(&[1], false).as_slice(), &[6.0, 120.0]);
+    assert_eq!(tensor.prod_axes(&[0], false).as_slice(), &[4.0, 10.0, 18.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_var_std_axes() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 6.0, 8.0], &[2, 4]);
+    let var = tensor.var_axes(&[1], false, false);
+    assert_eq!(var.as_slice(), &[1.25, 5.0]);
+    let unbiased = tensor.var_axes(&[1], true, true);
+    assert_eq!(unbiased.shape(), &[2, 1]);
+    assert!((unbiased.get(&[0, 0]) - 5.0 / 3.0).abs() < 1e-6);
+    let std = tensor.std_axes(&[1], false, false);
+    assert!((std.get(&[1]) - 5.0f32.sqrt()).abs() < 1e-6);
+}
+
+#[cfg(test)]
+#[test]
+fn test_norm_axes() {
+    let tensor = Tensor::new(vec![3.0, -4.0, 1.0, -1.0], &[2, 2]);
+    assert_eq!(tensor.norm_axes(2.0, &[1], false).get(&[0]), 5.0);
+    assert_eq!(tensor.norm_axes(1.0, &[1], false).as_slice(), &[7.0, 2.0]);
+    assert_eq!(
+        tensor.norm_axes(f32::INFINITY, &[0], false).as_slice(),
+        &[3.0, 4.0]
+    );
+    let cubic = tensor.norm_axes(3.0, &[1], false).get(&[1]);
+    assert!((cubic - 2.0f32.powf(1.0 / 3.0)).abs() < 1e-6);
+}
+
+#[cfg(test)]
+#[test]
+fn test_reduction_axes_errors() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    assert!(tensor.try_sum_axes(&[0, 0], false).is_err());
+    assert!(tensor.try_max_axes(&[2], false).is_err());
+    assert!(tensor.try_norm_axes(0.0, &[0], false).is_err());
+    let empty = Tensor::new(vec![], &[2, 0]);
+    assert!(empty.try_argmax_axes(&[1], false).is_err());
+    assert_eq!(empty.sum_axes(&[1], false).as_slice(), &[0.0, 0.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_reductions_over_empty_axis() {
+    let x = Tensor::new(vec![], &[2, 0]);
+    assert_eq!(x.sum_axes(&[1], false).as_slice(), &[0.0, 0.0]);
+    assert_eq!(x.prod_axes(&[1], false).as_slice(), &[1.0, 1.0]);
+    assert!(
+        x.mean_axes(&[1], false)
+            .as_slice()
+            .iter()
+            .all(|v| v.is_nan())
+    );
+    assert!(
+        x.var_axes(&[1], true, false)
+            .as_slice()
+            .iter()
+            .all(|v| v.is_nan())
+    );
+    let lse = x.logsumexp(1, false);
+    assert_eq!(lse.shape(), &[2]);
+    assert_eq!(lse.as_slice(), &[f32::NEG_INFINITY, f32::NEG_INFINITY]);
+    let lse = Tensor::new(vec![], &[0, 3]).logsumexp(0, true);
+    assert_eq!(lse.shape(), &[1, 3]);
+    assert_eq!(lse.as_slice(), &[f32::NEG_INFINITY; 3]);
+
+    // The variance of no elements divides zero by zero, with or without the correction
+    let empty = Tensor::new(vec![], &[0]);
+    assert!(empty.var_axes(&[0], true, false).get(&[0]).is_nan());
+    assert!(empty.std_axes(&[0], false, false).get(&[0]).is_nan());
+}
+
+#[cfg(test)]
+#[test]
+fn test_sum_and_prod_of_mask() {
+    let pred = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let labels = Tensor::new(vec![1.0, 0.0, 3.0, 4.0], &[2, 2]);
+    let correct = pred.eq(&labels);
+    assert_eq!(correct.dtype(), DType::Bool);
+    let count = correct.sum();
+    assert_eq!(count.dtype(), DType::I64);
+    assert_eq!(count.as_typed_slice::<i64>(), &[3]);
+    assert_eq!(
+        correct.sum_axes(&[1], false).as_typed_slice::<i64>(),
+        &[1, 2]
+    );
+    assert_eq!(
+        correct
+            .try_prod_axes(&[1], false)
+            .unwrap()
+            .as_typed_slice::<i64>(),
+        &[0, 1]
+    );
+}
@@ -0,0 +1,92 @@
+use nn_rs::helpers::metrics::nll_loss;
+use nn_rs::linalg::tensor::Tensor;
+
+/// Returns a transposed (non-contiguous) view of [[1, 2, 3], [4, 5, 6]].
+fn transposed() -> Tensor {
+    Tensor::new(vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0], &[3, 2]).transpose()
+}
+
+#[cfg(test)]
+#[test]
+fn test_contiguous() {
+    let view = transposed();
+    assert!(!view.is_contiguous());
+
+    let copy = view.contiguous();
+    assert!(copy.is_contiguous());
+    assert_eq!(copy.shape(), &[2, 3]);
+    assert_eq!(copy.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    let already_contiguous = copy.contiguous();
+    assert_eq!(already_contiguous.as_slice(), copy.as_slice());
+}
+
+#[cfg(test)]
+#[test]
+fn test_contiguous_grad() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let loss =
+        (&a.transpose().contiguous() * &Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2])).sum();
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[1.0, 3.0, 2.0, 4.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_unary_ops_on_view() {
+    let view = transposed();
+    assert_eq!((&view + 1.0).as_slice(), &[2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+    assert_eq!((-&view).as_slice(), &[-1.0, -2.0, -3.0, -4.0, -5.0, -6.0]);
+    assert_eq!(view.square().as_slice(), &[1.0, 4.0, 9.0, 16.0, 25.0, 36.0]);
+    assert_eq!(view.relu().as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    let sigmoid = view.sigmoid();
+    assert!((sigmoid.get(&[0, 1]) - 1.0 / (1.0 + (-2.0f32).exp())).abs() < 1e-6);
+}
+
+#[cfg(test)]
+#[test]
+fn test_binary_ops_on_views() {
+    let view = transposed();
+    let contiguous = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    assert_eq!((&view - &contiguous).as_slice(), &[0.0; 6]);
+    assert_eq!(
+        (&view * &view).as_slice(),
+        &[1.0, 4.0, 9.0, 16.0, 25.0, 36.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_reductions_on_view() {
+    let view = transposed();
+    assert_eq!(view.sum().item::<f32>(), 21.0);
+    assert_eq!(view.max().item::<f32>(), 6.0);
+    assert_eq!(view.argmax_axis(1).as_typed_slice::<i64>(), &[2, 2]);
+    assert_eq!(view.sum_axis(0).as_slice(), &[5.0, 7.0, 9.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_log_softmax_on_view() {
+    let view = transposed();
+    let expected = view.contiguous().log_softmax(1);
+    assert_eq!(view.log_softmax(1).as_slice(), expected.as_slice());
+}
+
+#[cfg(test)]
+#[test]
+fn test_reshape_view() {
+    let reshaped = transposed().reshape(&[3, 2]);
+    assert_eq!(reshaped.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_nll_loss_on_view() {
+    let pred = Tensor::new(vec![0.5, 0.25, 0.5, 0.75], &[2, 2]).transpose();
+    let target = Tensor::new(vec![1.0, 0.0, 0.0, 1.0], &[2, 2]);
+    // Rows of the view are [0.5, 0.5] and [0.25, 0.75]
+    let expected = -(0.5f32.ln() + 0.75f32.ln()) / 2.0;
+    assert!((nll_loss(&target, &pred).item::<f32>() - expected).abs() < 1e-6);
+}
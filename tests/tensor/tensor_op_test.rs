@@ -52,8 +52,7 @@ fn test_new_set_get() {
     tensor_data[0] = 5.0;
     assert_eq!(tensor.get(&[0, 0]), 5.0);
 
-    let tensor_data = tensor.as_slice();
-    assert_eq!(tensor_data[1], 2.0);
+    assert_eq!(tensor.as_slice()[1], 2.0);
     tensor.set(&[0, 1], 3.0);
     let tensor_data = tensor.as_slice();
     assert_eq!(tensor_data[1], 3.0);
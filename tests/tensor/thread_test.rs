@@ -0,0 +1,62 @@
+use nn_rs::linalg::tensor::Tensor;
+use nn_rs::nn::activation::ReLU;
+use nn_rs::nn::linear::Linear;
+use nn_rs::nn::models::NeuralNetwork;
+use std::sync::Arc;
+use std::thread;
+
+#[cfg(test)]
+#[test]
+fn test_tensor_is_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Tensor>();
+    assert_send_sync::<NeuralNetwork>();
+}
+
+#[cfg(test)]
+#[test]
+fn test_background_tensor_creation() {
+    let handle = thread::spawn(|| Tensor::new(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]) * 2.0);
+    let tensor = handle.join().unwrap();
+    assert_eq!(tensor.as_slice(), &[2.0, 4.0, 6.0, 8.0]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_parallel_inference() {
+    let net = Arc::new(NeuralNetwork::init(vec![
+        Box::new(Linear::from_parameters(
+            Tensor::with_grad(vec![1.0, -1.0, 2.0, 0.5], &[2, 2]),
+            Tensor::with_grad(vec![0.5, -0.5], &[1, 2]),
+        )),
+        Box::new(ReLU::default()),
+    ]));
+
+    let handles = (0..4)
+        .map(|i| {
+            let net = Arc::clone(&net);
+            thread::spawn(move || {
+                let input = Tensor::new(vec![i as f32, 1.0], &[1, 2]);
+                net.forward(input).as_slice().to_vec()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let x = i as f32;
+        let expected = [(x + 2.0 + 0.5).max(0.0), (-x + 0.5 - 0.5).max(0.0)];
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_backward_across_threads() {
+    let a = Tensor::with_grad(vec![1.0, 2.0, 3.0], &[3]);
+    let loss = {
+        let a = a.clone();
+        thread::spawn(move || a.square().sum()).join().unwrap()
+    };
+    loss.backward();
+    assert_eq!(a.grad().unwrap().as_slice(), &[2.0, 4.0, 6.0]);
+}
@@ -99,8 +99,8 @@ fn test_argmax() {
     let tensor = Tensor::new(data, &[5]);
     let result = tensor.argmax_axis(0);
     let expected_index = 3; // Index of the maximum value (5.0)
-    assert_eq!(result.len(), 1);
-    assert_eq!(result[0], expected_index);
+    assert_eq!(result.numel(), 1);
+    assert_eq!(result.item::<i64>(), expected_index);
 }
 
 #[cfg(test)]
@@ -110,9 +110,9 @@ fn test_argmax2d() {
     let tensor_2d = Tensor::new(data_2d, &[2, 3]);
     let result_2d = tensor_2d.argmax_axis(1);
     let expected_indices_2d = vec![1, 2]; // Indices of max values in each row
-    assert_eq!(result_2d.len(), 2);
+    assert_eq!(result_2d.numel(), 2);
     for i in 0..2 {
-        assert_eq!(result_2d[i], expected_indices_2d[i]);
+        assert_eq!(result_2d.get_as::<i64>(&[i]), expected_indices_2d[i]);
     }
 }
 
@@ -0,0 +1,117 @@
+use nn_rs::linalg::tensor::Tensor;
+
+fn arange(shape: &[usize]) -> Tensor {
+    let numel = shape.iter().product::<usize>();
+    Tensor::new((0..numel).map(|x| x as f32).collect(), shape)
+}
+
+#[cfg(test)]
+#[test]
+fn test_narrow() {
+    let tensor = arange(&[3, 4]);
+    let view = tensor.narrow(1, 1, 2);
+    assert_eq!(view.shape(), &[3, 2]);
+    assert!(!view.is_contiguous());
+    assert_eq!(
+        view.contiguous().as_slice(),
+        &[1.0, 2.0, 5.0, 6.0, 9.0, 10.0]
+    );
+
+    let rows = tensor.narrow(0, 1, 2);
+    assert!(rows.is_contiguous());
+    assert_eq!(rows.as_slice(), &[4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0]);
+
+    assert!(tensor.try_narrow(1, 3, 2).is_err());
+    assert!(tensor.try_narrow(2, 0, 1).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_slice_is_a_view() {
+    let mut tensor = arange(&[2, 3]);
+    let slice = tensor.slice(1, 1, 1);
+    assert_eq!(slice.contiguous().as_slice(), &[1.0, 4.0]);
+    // Writing through the original tensor copies the shared storage first
+    tensor.set(&[0, 1], 100.0);
+    assert_eq!(slice.get(&[0, 0]), 1.0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_select() {
+    let tensor = arange(&[2, 3, 4]);
+    let view = tensor.select(1, 2);
+    assert_eq!(view.shape(), &[2, 4]);
+    assert_eq!(
+        view.contiguous().as_slice(),
+        &[8.0, 9.0, 10.0, 11.0, 20.0, 21.0, 22.0, 23.0]
+    );
+    assert!(tensor.try_select(1, 3).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_permute() {
+    let tensor = arange(&[2, 3, 4]);
+    let view = tensor.permute(&[2, 0, 1]);
+    assert_eq!(view.shape(), &[4, 2, 3]);
+    for i in 0..2 {
+        for j in 0..3 {
+            for k in 0..4 {
+                assert_eq!(view.get(&[k, i, j]), tensor.get(&[i, j, k]));
+            }
+        }
+    }
+    assert!(tensor.try_permute(&[0, 0, 1]).is_err());
+    assert!(tensor.try_permute(&[0, 1]).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_expand() {
+    let tensor = Tensor::new(vec![1.0, 2.0, 3.0], &[3, 1]);
+    let view = tensor.expand(&[2, 3, 4]);
+    assert_eq!(view.shape(), &[2, 3, 4]);
+    assert_eq!(view.get(&[1, 2, 3]), 3.0);
+    assert_eq!(view.sum().item::<f32>(), 48.0);
+    assert!(tensor.try_expand(&[2, 4]).is_err());
+
+    let expanded = Tensor::new(vec![1.0, 2.0], &[2]).expand_dim(0, 3);
+    assert_eq!(expanded.shape(), &[3, 2]);
+    assert_eq!(
+        expanded.contiguous().as_slice(),
+        &[1.0, 2.0, 1.0, 2.0, 1.0, 2.0]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_squeeze_unsqueeze() {
+    let tensor = arange(&[1, 3, 1, 2]);
+    assert_eq!(tensor.squeeze().shape(), &[3, 2]);
+    assert_eq!(tensor.squeeze_axis(2).shape(), &[1, 3, 2]);
+    assert!(tensor.try_squeeze_axis(1).is_err());
+    assert_eq!(Tensor::new(vec![1.0], &[1, 1]).squeeze().shape(), &[1]);
+
+    let unsqueezed = arange(&[3, 2]).unsqueeze(1);
+    assert_eq!(unsqueezed.shape(), &[3, 1, 2]);
+    assert_eq!(unsqueezed.get(&[2, 0, 1]), 5.0);
+    assert!(arange(&[3, 2]).try_unsqueeze(3).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_flatten() {
+    let tensor = arange(&[2, 3, 4]);
+    let flat = tensor.flatten(1, 2);
+    assert_eq!(flat.shape(), &[2, 12]);
+    assert_eq!(flat.get(&[1, 5]), 17.0);
+
+    // Non-mergeable dimensions are copied
+    let permuted = tensor.permute(&[1, 0, 2]).flatten(0, 1);
+    assert_eq!(permuted.shape(), &[6, 4]);
+    assert_eq!(permuted.get(&[1, 0]), 12.0);
+
+    assert_eq!(tensor.flatten(0, 2).shape(), &[24]);
+    assert!(tensor.try_flatten(2, 1).is_err());
+}